@@ -1,6 +1,6 @@
 extern crate proc_macro;
 use proc_macro::TokenStream;
-use proc_macro2::Ident;
+use proc_macro2::{Ident, Span};
 use quote::quote;
 use syn::{parse_macro_input, Data, DeriveInput};
 
@@ -77,6 +77,20 @@ pub fn cube_subset_derive(input: TokenStream) -> TokenStream {
         let pieces_declaration = make_declarations(&edges, &corners);
         let edges_extraction = edge_extraction(&edges);
         let corners_extraction = corner_extraction(&corners);
+        let edge_indexing = Indexing::new(&edges, 12, 2, &PieceType::Edge);
+        let corner_indexing = Indexing::new(&corners, 8, 3, &PieceType::Corner);
+        let cases = edge_indexing
+            .cases()
+            .chain(corner_indexing.cases())
+            .reduce(|product, cases| quote! { #product * #cases })
+            .unwrap_or(quote! { 1 });
+        let rank = edge_indexing.rank().chain(corner_indexing.rank());
+        let unrank = corner_indexing
+            .unrank()
+            .into_iter()
+            .rev()
+            .chain(edge_indexing.unrank().into_iter().rev());
+        let placement = [edge_indexing.placement(), corner_indexing.placement()];
         let edges = edges
             .iter()
             .map(|(name, _, _)| quote! { #name: #name.clone() });
@@ -86,6 +100,8 @@ pub fn cube_subset_derive(input: TokenStream) -> TokenStream {
 
         TokenStream::from(quote! {
             impl CubeSubset for #name {
+                const CASES: usize = #cases;
+
                 fn from_cube(cube: &cube::Cube) -> Self {
                     use cube::edge::Edge;
                     use cube::corner::Corner;
@@ -97,6 +113,21 @@ pub fn cube_subset_derive(input: TokenStream) -> TokenStream {
                         #(#corners,)*
                     }
                 }
+
+                fn index(&self) -> usize {
+                    let mut index = 0;
+                    #(#rank)*
+                    index
+                }
+
+                fn cube_from_index(index: usize) -> cube::Cube {
+                    let mut index = index;
+                    #(#unrank)*
+                    debug_assert_eq!(index, 0);
+                    let mut cube = cube::Cube::default();
+                    #(#placement)*
+                    cube
+                }
             }
         })
     } else {
@@ -184,3 +215,178 @@ fn corner_extraction(corners: &[(Ident, String, PieceInfo)]) -> proc_macro2::Tok
 fn to_first_letter_uppercase(s: &str) -> String {
     s[0..1].to_uppercase() + &s[1..]
 }
+
+/// Dense indexing of the pieces of one type: the positions of the pieces
+/// that track one are ranked as a partial permutation, and the orientations
+/// as digits in the orientation base.
+struct Indexing {
+    slots: usize,
+    base: u8,
+    corner: bool,
+    /// Field accessors and home slots of the pieces tracking their position
+    positions: Vec<(proc_macro2::TokenStream, Ident)>,
+    /// Field accessors and home slots of the pieces tracking their orientation
+    orientations: Vec<(proc_macro2::TokenStream, Ident, bool)>,
+    positions_array: Ident,
+    orientations_array: Ident,
+}
+
+impl Indexing {
+    fn new(
+        pieces: &[(Ident, String, PieceInfo)],
+        slots: usize,
+        base: u8,
+        piece_type: &PieceType,
+    ) -> Self {
+        let prefix = match piece_type {
+            PieceType::Edge => "edge",
+            PieceType::Corner => "corner",
+        };
+        let mut positions = vec![];
+        let mut orientations = vec![];
+        for (name, piece_name, piece_info) in pieces {
+            let home = Ident::new(&piece_name.to_uppercase(), name.span());
+            match piece_info {
+                PieceInfo::PositionAndOrientation => {
+                    positions.push((quote! { self.#name.0 }, home.clone()));
+                    orientations.push((quote! { self.#name.1 }, home, true));
+                }
+                PieceInfo::PositionOnly => positions.push((quote! { self.#name }, home)),
+                PieceInfo::OrientationOnly => {
+                    orientations.push((quote! { self.#name }, home, false));
+                }
+            }
+        }
+        Self {
+            slots,
+            base,
+            corner: matches!(piece_type, PieceType::Corner),
+            positions,
+            orientations,
+            positions_array: Ident::new(&format!("{prefix}_positions"), Span::call_site()),
+            orientations_array: Ident::new(&format!("{prefix}_orientations"), Span::call_site()),
+        }
+    }
+
+    fn position_cases(&self) -> proc_macro2::TokenStream {
+        let slots = self.slots;
+        let count = self.positions.len();
+        quote! { cube::subcases::arrangements(#slots, #count) }
+    }
+
+    fn orientation_cases(&self) -> proc_macro2::TokenStream {
+        let base = usize::from(self.base);
+        let count = u32::try_from(self.orientations.len()).unwrap();
+        quote! { usize::pow(#base, #count) }
+    }
+
+    fn cases(&self) -> impl Iterator<Item = proc_macro2::TokenStream> {
+        let mut cases = vec![];
+        if !self.positions.is_empty() {
+            cases.push(self.position_cases());
+        }
+        if !self.orientations.is_empty() {
+            cases.push(self.orientation_cases());
+        }
+        cases.into_iter()
+    }
+
+    fn rank(&self) -> impl Iterator<Item = proc_macro2::TokenStream> {
+        let slots = self.slots;
+        let base = self.base;
+        let mut rank = vec![];
+        if !self.positions.is_empty() {
+            let cases = self.position_cases();
+            let fields = self.positions.iter().map(|(field, _)| field);
+            rank.push(quote! {
+                index = index * #cases
+                    + cube::subcases::rank_positions(&[#(#fields),*], #slots);
+            });
+        }
+        if !self.orientations.is_empty() {
+            let cases = self.orientation_cases();
+            let fields = self.orientations.iter().map(|(field, _, _)| field);
+            rank.push(quote! {
+                index = index * #cases
+                    + cube::subcases::rank_orientations(&[#(#fields),*], #base);
+            });
+        }
+        rank.into_iter()
+    }
+
+    /// Statements extracting the digits in the same order as `rank` consumes them.
+    fn unrank(&self) -> Vec<proc_macro2::TokenStream> {
+        let slots = self.slots;
+        let base = self.base;
+        let positions_array = &self.positions_array;
+        let orientations_array = &self.orientations_array;
+        let position_count = self.positions.len();
+        let orientation_count = self.orientations.len();
+        let mut unrank = vec![];
+        if !self.positions.is_empty() {
+            let cases = self.position_cases();
+            unrank.push(quote! {
+                let mut #positions_array = [0u8; #position_count];
+                cube::subcases::unrank_positions(index % #cases, #slots, &mut #positions_array);
+                index /= #cases;
+            });
+        }
+        if !self.orientations.is_empty() {
+            let cases = self.orientation_cases();
+            unrank.push(quote! {
+                let mut #orientations_array = [0u8; #orientation_count];
+                cube::subcases::unrank_orientations(index % #cases, #base, &mut #orientations_array);
+                index /= #cases;
+            });
+        }
+        unrank
+    }
+
+    /// Statements placing the pieces of the subset on a solved cube. Pieces
+    /// tracking their position are placed first, so that pieces only tracking
+    /// their orientation can be twisted wherever they ended up.
+    fn placement(&self) -> proc_macro2::TokenStream {
+        let positions_array = &self.positions_array;
+        let orientations_array = &self.orientations_array;
+        let (place, pieces) = if self.corner {
+            (quote! { place_corner }, quote! { corners })
+        } else {
+            (quote! { place_edge }, quote! { edges })
+        };
+        let mut orientation_of = std::collections::HashMap::new();
+        for (i, (_, home, with_position)) in self.orientations.iter().enumerate() {
+            orientation_of.insert(home.to_string(), (i, *with_position));
+        }
+        let positioned = self.positions.iter().enumerate().map(|(i, (_, home))| {
+            let orientation = match orientation_of.get(&home.to_string()) {
+                Some((j, _)) => quote! { #orientations_array[#j] },
+                None => quote! { 0 },
+            };
+            quote! {
+                cube::subcases::#place(
+                    &mut cube,
+                    cube::#home,
+                    usize::from(#positions_array[#i]),
+                    #orientation,
+                );
+            }
+        });
+        let twisted = self
+            .orientations
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, _, with_position))| !with_position)
+            .map(|(j, (_, home, _))| {
+                quote! {
+                    let piece = cube::Cube::default().#pieces[cube::#home].piece.clone();
+                    if let Some(piece) = cube.#pieces.iter_mut().find(|p| p.piece == piece) {
+                        piece.orientation = #orientations_array[#j];
+                    }
+                }
+            });
+        quote! {
+            #(#positioned)*
+            #(#twisted)*
+        }
+    }
+}
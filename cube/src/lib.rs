@@ -3,7 +3,9 @@ use self::algorithms::Move;
 pub mod algorithms;
 pub mod corner;
 pub mod edge;
+pub mod patterns;
 pub mod subcases;
+pub mod validation;
 
 /// Edges in the following order: UR, UF, UL, UB, DR, DF, DL, DB, FR, FL, BL, BR
 pub const UR: usize = 0;
@@ -99,6 +101,36 @@ impl Cube {
         }
     }
 
+    /// The cube whose pieces are named after the slots they hold in
+    /// `target`, with their orientations relative to it. An algorithm solves
+    /// this cube exactly when it turns the cube into `target`, so solvers
+    /// reach any state by solving the relative cube. Both cubes must be
+    /// valid.
+    #[must_use]
+    pub fn relative_to(&self, target: &Cube) -> Cube {
+        let solved = Cube::default();
+        let mut relative = self.clone();
+        for edge in &mut relative.edges {
+            let slot = target
+                .edges
+                .iter()
+                .position(|e| e.piece == edge.piece)
+                .expect("Valid cubes hold every edge");
+            edge.piece = solved.edges[slot].piece.clone();
+            edge.orientation = (edge.orientation + 2 - target.edges[slot].orientation) % 2;
+        }
+        for corner in &mut relative.corners {
+            let slot = target
+                .corners
+                .iter()
+                .position(|c| c.piece == corner.piece)
+                .expect("Valid cubes hold every corner");
+            corner.piece = solved.corners[slot].piece.clone();
+            corner.orientation = (corner.orientation + 3 - target.corners[slot].orientation) % 3;
+        }
+        relative
+    }
+
     pub(crate) fn u(&mut self) {
         // Circular shift of edges
         self.edges.swap(UR, UB);
@@ -304,4 +336,25 @@ mod tests {
         println!("Solved cube: {:?}", super::Cube::default());
         assert_eq!(cube_1, cube_2);
     }
+
+    #[test]
+    fn relative_cube() {
+        let scramble = super::algorithms::parse_algorithm("R U F' L2 D B'");
+        let mut from = super::Cube::default();
+        from.execute_algorithm(&scramble);
+        let target = super::patterns::superflip();
+        assert!(target.edges.iter().all(|edge| edge.orientation == 1));
+        assert_eq!(target.corners, super::Cube::default().corners);
+
+        assert_eq!(from.relative_to(&super::Cube::default()), from);
+        assert_eq!(target.relative_to(&target), super::Cube::default());
+
+        // Undoing the scramble then doing the superflip solves the relative cube
+        let mut relative = from.relative_to(&target);
+        relative.execute_algorithm(&super::algorithms::invert_algorithm(&scramble));
+        relative.execute_algorithm(&super::algorithms::parse_algorithm(
+            super::patterns::SUPERFLIP,
+        ));
+        assert_eq!(relative, super::Cube::default());
+    }
 }
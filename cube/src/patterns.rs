@@ -0,0 +1,31 @@
+//! # Well-known patterns, as targets for the solvers.
+
+use crate::{algorithms::parse_algorithm, Cube};
+
+/// All the edges flipped in place.
+pub const SUPERFLIP: &str = "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2";
+/// Every face alternating its colour with the opposite one.
+pub const CHECKERBOARD: &str = "U2 D2 F2 B2 L2 R2";
+/// A 2x2x2 cube nested in a 3x3x3 cube nested in the cube.
+pub const CUBE_IN_CUBE: &str = "F L F U' R U F2 L2 U' L' B D' B' L2 U";
+
+fn pattern(algorithm: &str) -> Cube {
+    let mut cube = Cube::default();
+    cube.execute_algorithm(&parse_algorithm(algorithm));
+    cube
+}
+
+#[must_use]
+pub fn superflip() -> Cube {
+    pattern(SUPERFLIP)
+}
+
+#[must_use]
+pub fn checkerboard() -> Cube {
+    pattern(CHECKERBOARD)
+}
+
+#[must_use]
+pub fn cube_in_cube() -> Cube {
+    pattern(CUBE_IN_CUBE)
+}
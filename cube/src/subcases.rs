@@ -5,5 +5,151 @@ use std::hash::Hash;
 pub trait CubeSubset:
     PartialEq + Eq + Hash + Clone + Serialize + for<'de> Deserialize<'de>
 {
+    /// Number of distinct values of the subset, i.e. the size of its dense index space.
+    const CASES: usize;
+
     fn from_cube(cube: &Cube) -> Self;
+
+    /// Dense rank of the subset, in `0..Self::CASES`.
+    fn index(&self) -> usize;
+
+    /// Build a cube whose subset has the given index. Pieces that are not part
+    /// of the subset are left wherever is convenient.
+    fn cube_from_index(index: usize) -> Cube;
+}
+
+/// Number of ways to place `count` distinct pieces in `slots` slots.
+#[must_use]
+pub const fn arrangements(slots: usize, count: usize) -> usize {
+    let mut result = 1;
+    let mut i = 0;
+    while i < count {
+        result *= slots - i;
+        i += 1;
+    }
+    result
+}
+
+/// Number of ways to choose `count` slots among `slots`.
+#[must_use]
+pub const fn combinations(slots: usize, count: usize) -> usize {
+    if count > slots {
+        return 0;
+    }
+    let mut result = 1;
+    let mut i = 0;
+    while i < count {
+        result = result * (slots - i) / (i + 1);
+        i += 1;
+    }
+    result
+}
+
+/// Rank distinct slots in `0..arrangements(slots, positions.len())`.
+#[must_use]
+pub fn rank_positions(positions: &[u8], slots: usize) -> usize {
+    let mut rank = 0;
+    for (i, position) in positions.iter().enumerate() {
+        let smaller_free_slots =
+            usize::from(*position) - positions[..i].iter().filter(|p| *p < position).count();
+        rank = rank * (slots - i) + smaller_free_slots;
+    }
+    rank
+}
+
+/// Inverse of [`rank_positions`].
+pub fn unrank_positions(mut rank: usize, slots: usize, positions: &mut [u8]) {
+    let mut digits = [0; 12];
+    for i in (0..positions.len()).rev() {
+        digits[i] = rank % (slots - i);
+        rank /= slots - i;
+    }
+    let mut used = [false; 12];
+    for (position, digit) in positions.iter_mut().zip(digits) {
+        let slot = (0..slots).filter(|s| !used[*s]).nth(digit).unwrap();
+        used[slot] = true;
+        *position = u8::try_from(slot).unwrap();
+    }
+}
+
+/// Rank orientations written in the given base.
+#[must_use]
+pub fn rank_orientations(orientations: &[u8], base: u8) -> usize {
+    orientations
+        .iter()
+        .fold(0, |rank, o| rank * usize::from(base) + usize::from(*o))
+}
+
+/// Inverse of [`rank_orientations`].
+pub fn unrank_orientations(mut rank: usize, base: u8, orientations: &mut [u8]) {
+    for orientation in orientations.iter_mut().rev() {
+        *orientation = u8::try_from(rank % usize::from(base)).unwrap();
+        rank /= usize::from(base);
+    }
+}
+
+/// Rank a set of slots, given as a bit mask, in `0..combinations(slots, count)`.
+#[must_use]
+pub fn rank_combination(mask: u16) -> usize {
+    (0..16)
+        .filter(|slot| mask & (1 << slot) != 0)
+        .enumerate()
+        .map(|(k, slot)| combinations(slot, k + 1))
+        .sum()
+}
+
+/// Inverse of [`rank_combination`] for a set of `count` slots among `slots`.
+#[must_use]
+pub fn unrank_combination(mut rank: usize, slots: usize, count: usize) -> u16 {
+    let mut mask = 0;
+    let mut k = count;
+    for slot in (0..slots).rev() {
+        if k > 0 && combinations(slot, k) <= rank {
+            rank -= combinations(slot, k);
+            mask |= 1 << slot;
+            k -= 1;
+        }
+    }
+    mask
+}
+
+/// Move the edge whose home is `home` to `slot`, with the given orientation.
+/// Edges placed beforehand on other slots are not disturbed.
+pub fn place_edge(cube: &mut Cube, home: usize, slot: usize, orientation: u8) {
+    let piece = &Cube::default().edges[home].piece;
+    let current = cube.edges.iter().position(|e| e.piece == *piece).unwrap();
+    cube.edges.swap(current, slot);
+    cube.edges[slot].orientation = orientation;
+}
+
+/// Move the corner whose home is `home` to `slot`, with the given orientation.
+/// Corners placed beforehand on other slots are not disturbed.
+pub fn place_corner(cube: &mut Cube, home: usize, slot: usize, orientation: u8) {
+    let piece = &Cube::default().corners[home].piece;
+    let current = cube.corners.iter().position(|c| c.piece == *piece).unwrap();
+    cube.corners.swap(current, slot);
+    cube.corners[slot].orientation = orientation;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_round_trip() {
+        for rank in 0..arrangements(12, 4) {
+            let mut positions = [0; 4];
+            unrank_positions(rank, 12, &mut positions);
+            assert_eq!(rank_positions(&positions, 12), rank);
+        }
+    }
+
+    #[test]
+    fn combination_round_trip() {
+        for rank in 0..combinations(12, 4) {
+            let mask = unrank_combination(rank, 12, 4);
+            assert_eq!(mask.count_ones(), 4);
+            assert_eq!(rank_combination(mask), rank);
+        }
+    }
 }
@@ -0,0 +1,145 @@
+//! # Check that a cube is a legal state of the puzzle.
+//!
+//! A cube built by executing moves on a solved cube is always legal, but cubes
+//! coming from user input may have duplicated pieces, twisted corners or
+//! flipped edges that no sequence of moves can produce.
+
+use std::{error::Error, fmt::Display};
+
+use crate::Cube;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidCube {
+    /// An edge appears twice, so another one is missing
+    DuplicateEdge(usize),
+    /// A corner appears twice, so another one is missing
+    DuplicateCorner(usize),
+    /// An edge orientation is not 0 or 1
+    EdgeOrientation(usize),
+    /// A corner orientation is not 0, 1 or 2
+    CornerOrientation(usize),
+    /// The sum of the edge orientations is odd
+    FlippedEdge,
+    /// The sum of the corner orientations is not a multiple of 3
+    TwistedCorner,
+    /// The edge and corner permutations do not have the same parity
+    Parity,
+}
+
+impl Display for InvalidCube {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidCube::DuplicateEdge(slot) => write!(f, "edge in slot {slot} is duplicated"),
+            InvalidCube::DuplicateCorner(slot) => {
+                write!(f, "corner in slot {slot} is duplicated")
+            }
+            InvalidCube::EdgeOrientation(slot) => {
+                write!(f, "edge in slot {slot} has an invalid orientation")
+            }
+            InvalidCube::CornerOrientation(slot) => {
+                write!(f, "corner in slot {slot} has an invalid orientation")
+            }
+            InvalidCube::FlippedEdge => f.write_str("a single edge is flipped"),
+            InvalidCube::TwistedCorner => f.write_str("a single corner is twisted"),
+            InvalidCube::Parity => f.write_str("two pieces are swapped"),
+        }
+    }
+}
+
+impl Error for InvalidCube {}
+
+impl Cube {
+    /// Check that the cube can be reached from the solved state.
+    pub fn validate(&self) -> Result<(), InvalidCube> {
+        let mut edges = [0; 12];
+        let mut edges_seen = [false; 12];
+        for (slot, edge) in self.edges.iter().enumerate() {
+            let piece = edge.piece.clone() as usize;
+            if edges_seen[piece] {
+                return Err(InvalidCube::DuplicateEdge(slot));
+            }
+            if edge.orientation > 1 {
+                return Err(InvalidCube::EdgeOrientation(slot));
+            }
+            edges_seen[piece] = true;
+            edges[slot] = piece;
+        }
+
+        let mut corners = [0; 8];
+        let mut corners_seen = [false; 8];
+        for (slot, corner) in self.corners.iter().enumerate() {
+            let piece = corner.piece.clone() as usize;
+            if corners_seen[piece] {
+                return Err(InvalidCube::DuplicateCorner(slot));
+            }
+            if corner.orientation > 2 {
+                return Err(InvalidCube::CornerOrientation(slot));
+            }
+            corners_seen[piece] = true;
+            corners[slot] = piece;
+        }
+
+        if self.edges.iter().map(|e| e.orientation).sum::<u8>() % 2 != 0 {
+            return Err(InvalidCube::FlippedEdge);
+        }
+        if self.corners.iter().map(|c| c.orientation).sum::<u8>() % 3 != 0 {
+            return Err(InvalidCube::TwistedCorner);
+        }
+        if is_odd_permutation(&edges) != is_odd_permutation(&corners) {
+            return Err(InvalidCube::Parity);
+        }
+        Ok(())
+    }
+}
+
+/// Parity of a permutation, computed from the length of its cycles.
+fn is_odd_permutation(permutation: &[usize]) -> bool {
+    let mut visited = [false; 12];
+    let mut transpositions = 0;
+    for start in 0..permutation.len() {
+        if visited[start] {
+            continue;
+        }
+        // A cycle of length n is made of n - 1 transpositions
+        let mut slot = permutation[start];
+        visited[start] = true;
+        while slot != start {
+            visited[slot] = true;
+            slot = permutation[slot];
+            transpositions += 1;
+        }
+    }
+    transpositions % 2 == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::parse_algorithm;
+
+    #[test]
+    fn scrambled_cube_is_valid() {
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&parse_algorithm("R U F' L2 D B' R2 U'"));
+        assert_eq!(cube.validate(), Ok(()));
+    }
+
+    #[test]
+    fn detect_invalid_cubes() {
+        let mut cube = Cube::default();
+        cube.edges[crate::UF].flip();
+        assert_eq!(cube.validate(), Err(InvalidCube::FlippedEdge));
+
+        let mut cube = Cube::default();
+        cube.corners[crate::URF].rotate(1);
+        assert_eq!(cube.validate(), Err(InvalidCube::TwistedCorner));
+
+        let mut cube = Cube::default();
+        cube.edges.swap(crate::UF, crate::UR);
+        assert_eq!(cube.validate(), Err(InvalidCube::Parity));
+
+        let mut cube = Cube::default();
+        cube.edges[crate::UF] = cube.edges[crate::UR].clone();
+        assert_eq!(cube.validate(), Err(InvalidCube::DuplicateEdge(crate::UF)));
+    }
+}
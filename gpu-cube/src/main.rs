@@ -6,7 +6,10 @@ mod cubie;
 mod depth_texture;
 mod state;
 mod vertex;
-use solver::solvers::methods::from_method_name;
+use solver::solvers::{
+    methods::{from_method_name, registry},
+    solver::Method,
+};
 use winit::event_loop::EventLoop;
 
 use crate::app::App;
@@ -16,13 +19,18 @@ pub fn main() -> anyhow::Result<()> {
 
     if args.len() < 2 {
         println!("Usage: {} <solver>", args[0]);
+        for info in registry::methods() {
+            println!("    {}: {}", info.name, info.description);
+        }
         return Err(anyhow::anyhow!("Solver argument is required"));
     }
 
+    // Installed before loading the solver, which logs the generation of its tables
+    env_logger::init();
     let solver = from_method_name(&args[1]);
     match solver {
         Ok(solver) => {
-            env_logger::init();
+            log::info!("Lookup tables use {} KiB", solver.memory_usage() / 1024);
 
             let event_loop = EventLoop::with_user_event().build()?;
             let mut app = App::new(solver);
@@ -30,6 +38,6 @@ pub fn main() -> anyhow::Result<()> {
 
             Ok(())
         }
-        Err(msg) => Err(anyhow::anyhow!(msg)),
+        Err(error) => Err(error.into()),
     }
 }
@@ -1,6 +1,17 @@
-use std::sync::Arc;
+use std::{
+    sync::{
+        Arc,
+        mpsc::{self, Receiver, TryRecvError},
+    },
+    time::Duration,
+};
 
-use solver::solvers::{methods::Methods, solver::Method};
+use solver::solvers::{
+    methods::Methods,
+    options::{CancellationToken, SolveOptions},
+    progress::LogProgress,
+    solver::{Method, Solution, SolveError},
+};
 use wgpu::SurfaceError;
 use winit::{event::WindowEvent, keyboard::NamedKey, window::Window};
 
@@ -12,6 +23,15 @@ use crate::{
     vertex::Vertex,
 };
 
+/// Time after which a solve running in the background is abandoned.
+const SOLVE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Solve running in the background, with the token to cancel it.
+struct PendingSolve {
+    result: Receiver<Result<Solution, SolveError>>,
+    cancellation: CancellationToken,
+}
+
 pub struct State {
     surface: wgpu::Surface<'static>,
     device: wgpu::Device,
@@ -27,7 +47,8 @@ pub struct State {
     last_frame: std::time::Instant,
     window: Arc<Window>,
     depth_texture: depth_texture::DepthTexture,
-    solver: Methods,
+    solver: Arc<Methods>,
+    pending_solve: Option<PendingSolve>,
 }
 impl State {
     pub(crate) async fn new(window: Arc<Window>, solver: Methods) -> anyhow::Result<Self> {
@@ -146,7 +167,8 @@ impl State {
             render_pipeline,
             last_frame: std::time::Instant::now(),
             window,
-            solver,
+            solver: Arc::new(solver),
+            pending_solve: None,
         })
     }
 
@@ -171,6 +193,55 @@ impl State {
 
         self.camera.update(&self.queue);
         self.cube.update_cubies(delta_time, &self.queue);
+        self.poll_solve();
+    }
+
+    /// Solve the cube in a background thread, so that rendering goes on.
+    fn start_solve(&mut self) {
+        if self.pending_solve.is_some() {
+            return;
+        }
+        let cancellation = CancellationToken::new();
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let options = SolveOptions::new()
+            .timeout(SOLVE_TIMEOUT)
+            .cancellation(cancellation.clone())
+            .threads(threads)
+            .progress(LogProgress);
+        let solver = Arc::clone(&self.solver);
+        let cube = self.cube.algebric_representation.clone();
+        let (sender, result) = mpsc::channel();
+        std::thread::spawn(move || {
+            // The receiver is gone if the window was closed meanwhile.
+            let _ = sender.send(solver.try_solve_with(&cube, &options));
+        });
+        self.pending_solve = Some(PendingSolve {
+            result,
+            cancellation,
+        });
+    }
+
+    fn poll_solve(&mut self) {
+        let Some(pending_solve) = &self.pending_solve else {
+            return;
+        };
+        let result = match pending_solve.result.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {
+                log::error!("Solver thread stopped without a result");
+                self.pending_solve = None;
+                return;
+            }
+        };
+        self.pending_solve = None;
+        match result {
+            Ok(solution) => {
+                log::info!("Solution:\n{}", solution.reconstruction());
+                self.cube.start_algorithm(&solution.moves());
+            }
+            Err(e) => log::error!("Unable to solve {e}"),
+        }
     }
 
     pub(crate) fn render(&mut self) -> Result<(), SurfaceError> {
@@ -278,12 +349,11 @@ impl State {
                             self.cube.start_animation(Faces::Down, direction);
                         }
                     }
-                    winit::keyboard::Key::Named(NamedKey::Enter) => {
-                        let solution = self.solver.solve(&self.cube.algebric_representation);
-
-                        log::info!("Solution: {solution:?}");
-
-                        self.cube.start_algorithm(&solution);
+                    winit::keyboard::Key::Named(NamedKey::Enter) => self.start_solve(),
+                    winit::keyboard::Key::Named(NamedKey::Escape) => {
+                        if let Some(pending_solve) = &self.pending_solve {
+                            pending_solve.cancellation.cancel();
+                        }
                     }
                     _ => (),
                 }
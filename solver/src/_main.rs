@@ -5,26 +5,47 @@ use std::{
 
 use solver::{
     scramble,
-    solvers::{methods::from_method_name, solver::Method},
+    solvers::{
+        methods::{from_method_name, registry, Methods},
+        solver::Method,
+        table_store::TableStore,
+    },
 };
 
-use cube::{self};
+use cube::{self, algorithms::Move};
 
 const MOVE_COUNT: usize = 20;
 
 pub fn main() {
     let args = std::env::args().collect::<Vec<_>>();
 
+    if args.get(1).is_some_and(|arg| arg == "--list") {
+        list_methods();
+        return;
+    }
+
+    if args.get(1).is_some_and(|arg| arg == "--compare") && args.len() >= 5 {
+        let number_of_scrambles = args[2]
+            .parse::<usize>()
+            .expect("Number of scrambles must be a number");
+        compare_methods(&args[3..], number_of_scrambles);
+        return;
+    }
+
     if args.len() < 3 {
         println!(
             "Usage: {} <solver> <number of scrambles> [number of threads]",
             args[0]
         );
+        println!(
+            "       {} --compare <number of scrambles> <solver> <solver>...",
+            args[0]
+        );
+        println!("       {} --list", args[0]);
         return;
     }
 
-    let solver = from_method_name(&args[1].clone())
-        .expect("The solver must be one of cfop|free_fop|one_phase|two_phase");
+    let solver = method(&args[1]);
 
     let number_of_scrambles = args[2]
         .parse::<usize>()
@@ -74,39 +95,79 @@ pub fn main() {
     let mut method_lengths = method_lengths.lock().unwrap().clone();
 
     println!("Results:");
-    println!("Median time: {:?}", median(&mut method_times));
+    print_results(&mut method_times, &mut method_lengths);
+}
+
+/// Method of the given name, or panic with the names of the registered
+/// methods.
+fn method(name: &str) -> Methods {
+    from_method_name(name).unwrap_or_else(|error| {
+        let names = registry::methods()
+            .into_iter()
+            .map(|info| info.name)
+            .collect::<Vec<_>>();
+        panic!("{error}, the solver must be one of {}", names.join("|"))
+    })
+}
+
+/// Solve the same scrambles with each method, and print their results one
+/// method after the other.
+fn compare_methods(names: &[String], number_of_scrambles: usize) {
+    let solvers = names.iter().map(|name| method(name)).collect::<Vec<_>>();
+    let scrambles = (0..number_of_scrambles)
+        .map(|_| scramble::generate(MOVE_COUNT))
+        .collect::<Vec<_>>();
+    println!("Solving {number_of_scrambles} scrambles with each method");
+
+    for (name, solver) in names.iter().zip(&solvers) {
+        let (mut times, mut lengths) = solve_scrambles(&scrambles, solver);
+        println!("Results of {name}:");
+        print_results(&mut times, &mut lengths);
+    }
+}
+
+fn print_results(method_times: &mut Vec<Duration>, method_lengths: &mut Vec<usize>) {
+    println!("Median time: {:?}", median(method_times));
     println!(
         "Average time: {:?}",
         method_times.iter().sum::<Duration>() / method_times.len().try_into().unwrap()
     );
-    println!("Median length: {}", median(&mut method_lengths));
+    println!("Median length: {}", median(method_lengths));
     println!(
         "Average length: {}",
         method_lengths.iter().sum::<usize>() / method_lengths.len()
     );
     println!(
         "95th percentile time: {:?}",
-        ninety_five_percentile(&mut method_times)
+        ninety_five_percentile(method_times)
     );
     println!(
         "95th percentile length: {}",
-        ninety_five_percentile(&mut method_lengths)
+        ninety_five_percentile(method_lengths)
     );
     println!("Worst time: {:?}", method_times.iter().max().unwrap());
     println!("Worst length: {}", method_lengths.iter().max().unwrap());
 }
 
 fn solve_n_scrambles(count: usize, solver: &impl Method) -> (Vec<Duration>, Vec<usize>) {
+    let scrambles = (0..count)
+        .map(|_| scramble::generate(MOVE_COUNT))
+        .collect::<Vec<_>>();
+    solve_scrambles(&scrambles, solver)
+}
+
+fn solve_scrambles(scrambles: &[Vec<Move>], solver: &impl Method) -> (Vec<Duration>, Vec<usize>) {
     let mut times = vec![];
     let mut lengths = vec![];
 
-    for _ in 0..count {
-        let scramble = scramble::generate(MOVE_COUNT);
+    for scramble in scrambles {
         let mut cube = cube::Cube::default();
-        cube.execute_algorithm(&scramble);
+        cube.execute_algorithm(scramble);
 
         let now = std::time::Instant::now();
-        let solution = solver.solve(&cube);
+        let solution = solver
+            .solve(&cube)
+            .unwrap_or_else(|error| panic!("Could not solve {scramble:?}: {error}"));
         let elapsed = now.elapsed();
 
         times.push(elapsed);
@@ -125,3 +186,29 @@ fn ninety_five_percentile<T: Ord + Copy>(v: &mut Vec<T>) -> T {
     v.sort();
     v[v.len() * 95 / 100]
 }
+
+/// Print the methods with their steps, and the tables that building them
+/// would generate.
+fn list_methods() {
+    let store = TableStore::default();
+    for info in registry::methods() {
+        println!("{}: {}", info.name, info.description);
+        println!("    steps: {}", info.steps.join(", "));
+        for table in &info.tables {
+            if table.is_cached(&store) {
+                println!("    table {}: cached", table.kind);
+            } else {
+                println!(
+                    "    table {}: {} KiB, generated in {}",
+                    table.kind,
+                    table
+                        .size
+                        .map_or("?".to_string(), |size| (size / 1024).to_string()),
+                    table
+                        .generation_time
+                        .map_or("?".to_string(), |time| format!("{time:.1?}"))
+                );
+            }
+        }
+    }
+}
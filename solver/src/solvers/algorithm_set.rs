@@ -0,0 +1,490 @@
+//! # Algorithm sets of the F2L pairs and the last layer steps, loaded from
+//! RON files.
+//!
+//! A set gives the algorithms of each case of the F2L, the OLL or the PLL by
+//! the name of the case: `OLL 1` to `OLL 57`, and the usual names of the PLLs
+//! (`Aa`, `T`, `Gc`...). The F2L cases insert the front right pair from the U
+//! layer or from its slot, and are numbered `F2L 1` to `F2L 41` in the order
+//! of the generated list, not in the usual order. The first algorithm of a
+//! case is the one the solver uses, and the tags are free for the user.
+//!
+//! ```ron
+//! (
+//!     step: Pll,
+//!     cases: [
+//!         (
+//!             name: "T",
+//!             algorithms: ["R U R' U' R' F R2 U' R' U' R U R' F'"],
+//!             tags: ["2-look"],
+//!         ),
+//!     ],
+//! )
+//! ```
+//!
+//! Loading a set checks that each algorithm solves its case, up to the moves
+//! of the U face before and after it, and that every case is solved once.
+//! The F2L algorithms are turned towards the other slots when inserting their
+//! pairs.
+
+use std::{collections::HashSet, error::Error, fmt::Display, fs, io, path::Path};
+
+use cube::{
+    algorithms::{algorithm_to_string, invert_algorithm, parse_algorithm, Move},
+    Cube,
+};
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use super::{last_layer::LastLayerStep, steps::pll::PLL_NAMES};
+
+/// Algorithms of the cases of a step.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlgorithmSet {
+    pub step: LastLayerStep,
+    pub cases: Vec<CaseAlgorithms>,
+}
+
+/// Algorithms of a case, the preferred one first.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaseAlgorithms {
+    pub name: String,
+    pub algorithms: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// Problem found while checking an algorithm set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AlgorithmSetIssue {
+    UnknownCase {
+        name: String,
+    },
+    /// The algorithm has moves this crate does not know, such as rotations
+    InvalidAlgorithm {
+        case: String,
+        algorithm: String,
+    },
+    /// The algorithm solves another case, or none when `solved` is `None`
+    WrongCase {
+        case: String,
+        algorithm: String,
+        solved: Option<String>,
+    },
+    DuplicateCase {
+        name: String,
+    },
+    DuplicateAlgorithm {
+        case: String,
+        algorithm: String,
+    },
+    MissingCase {
+        name: String,
+    },
+    /// The case has no algorithm
+    EmptyCase {
+        name: String,
+    },
+}
+
+impl Display for AlgorithmSetIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlgorithmSetIssue::UnknownCase { name } => write!(f, "unknown case {name}"),
+            AlgorithmSetIssue::InvalidAlgorithm { case, algorithm } => {
+                write!(f, "{case}: invalid algorithm {algorithm}")
+            }
+            AlgorithmSetIssue::WrongCase {
+                case,
+                algorithm,
+                solved: Some(solved),
+            } => write!(f, "{case}: {algorithm} solves {solved}"),
+            AlgorithmSetIssue::WrongCase {
+                case,
+                algorithm,
+                solved: None,
+            } => write!(f, "{case}: {algorithm} does not solve a case of the step"),
+            AlgorithmSetIssue::DuplicateCase { name } => write!(f, "{name} is given twice"),
+            AlgorithmSetIssue::DuplicateAlgorithm { case, algorithm } => {
+                write!(f, "{case}: {algorithm} is given twice")
+            }
+            AlgorithmSetIssue::MissingCase { name } => write!(f, "{name} has no algorithm"),
+            AlgorithmSetIssue::EmptyCase { name } => write!(f, "{name} has an empty list"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum AlgorithmSetError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+    /// The set is for another step than the one it is loaded into
+    Step {
+        expected: LastLayerStep,
+        found: LastLayerStep,
+    },
+    Invalid(Vec<AlgorithmSetIssue>),
+    /// The solver has no step the set is for
+    Unused(LastLayerStep),
+}
+
+impl Display for AlgorithmSetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlgorithmSetError::Io(error) => write!(f, "I/O error: {error}"),
+            AlgorithmSetError::Parse(error) => write!(f, "parse error: {error}"),
+            AlgorithmSetError::Step { expected, found } => {
+                write!(f, "expected a {expected} set, found a {found} set")
+            }
+            AlgorithmSetError::Unused(step) => write!(f, "the solver has no {step} step"),
+            AlgorithmSetError::Invalid(issues) => {
+                f.write_str("invalid algorithm set")?;
+                for issue in issues {
+                    write!(f, "\n  {issue}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for AlgorithmSetError {}
+
+impl From<io::Error> for AlgorithmSetError {
+    fn from(error: io::Error) -> Self {
+        AlgorithmSetError::Io(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for AlgorithmSetError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        AlgorithmSetError::Parse(error)
+    }
+}
+
+impl AlgorithmSet {
+    /// The algorithms the solvers use by default.
+    #[must_use]
+    pub fn hardcoded(step: LastLayerStep) -> Self {
+        let cases = case_names(step)
+            .into_iter()
+            .zip(step.hardcoded_algorithms())
+            .skip(1)
+            .map(|(name, algorithm)| CaseAlgorithms {
+                name,
+                algorithms: vec![algorithm_to_string(&algorithm)],
+                tags: vec![],
+            })
+            .collect();
+        Self { step, cases }
+    }
+
+    pub fn from_ron(text: &str) -> Result<Self, AlgorithmSetError> {
+        Ok(ron::from_str(text)?)
+    }
+
+    pub fn read(path: &Path) -> Result<Self, AlgorithmSetError> {
+        Self::from_ron(&fs::read_to_string(path)?)
+    }
+
+    #[must_use]
+    pub fn to_ron(&self) -> String {
+        ron::ser::to_string_pretty(self, PrettyConfig::default())
+            .expect("Algorithm sets are plain data")
+    }
+
+    /// Problems of the set, empty when it solves every case of its step.
+    #[must_use]
+    pub fn issues(&self) -> Vec<AlgorithmSetIssue> {
+        self.check().err().unwrap_or_default()
+    }
+
+    /// Preferred algorithm of each case, along with the empty algorithm of the
+    /// solved case.
+    pub(crate) fn preferred_algorithms(
+        &self,
+        step: LastLayerStep,
+    ) -> Result<Vec<Vec<Move>>, AlgorithmSetError> {
+        if self.step != step {
+            return Err(AlgorithmSetError::Step {
+                expected: step,
+                found: self.step,
+            });
+        }
+        self.check().map_err(AlgorithmSetError::Invalid)
+    }
+
+    fn check(&self) -> Result<Vec<Vec<Move>>, Vec<AlgorithmSetIssue>> {
+        let names = case_names(self.step);
+        let cases = hardcoded_cases(self.step);
+        let case_index = |name: &str| names.iter().skip(1).position(|n| n == name);
+
+        let mut issues = vec![];
+        let mut preferred = vec![None; names.len() - 1];
+        let mut declared = HashSet::new();
+        let mut seen_algorithms = HashSet::new();
+        for case in &self.cases {
+            let Some(index) = case_index(&case.name) else {
+                issues.push(AlgorithmSetIssue::UnknownCase {
+                    name: case.name.clone(),
+                });
+                continue;
+            };
+            if !declared.insert(index) {
+                issues.push(AlgorithmSetIssue::DuplicateCase {
+                    name: case.name.clone(),
+                });
+                continue;
+            }
+            if case.algorithms.is_empty() {
+                issues.push(AlgorithmSetIssue::EmptyCase {
+                    name: case.name.clone(),
+                });
+            }
+            for algorithm in &case.algorithms {
+                let Some(moves) = parse_strict(algorithm) else {
+                    issues.push(AlgorithmSetIssue::InvalidAlgorithm {
+                        case: case.name.clone(),
+                        algorithm: algorithm.clone(),
+                    });
+                    continue;
+                };
+                if !seen_algorithms.insert(algorithm_to_string(&moves)) {
+                    issues.push(AlgorithmSetIssue::DuplicateAlgorithm {
+                        case: case.name.clone(),
+                        algorithm: algorithm.clone(),
+                    });
+                    continue;
+                }
+                let mut cube = Cube::default();
+                cube.execute_algorithm(&invert_algorithm(&moves));
+                let solved = self
+                    .step
+                    .is_case(&cube)
+                    .then(|| self.step.case(&cube))
+                    .and_then(|solved| cases.iter().position(|case| *case == solved));
+                if solved == Some(index + 1) {
+                    preferred[index].get_or_insert(moves);
+                } else {
+                    issues.push(AlgorithmSetIssue::WrongCase {
+                        case: case.name.clone(),
+                        algorithm: algorithm.clone(),
+                        solved: solved.map(|solved| names[solved].clone()),
+                    });
+                }
+            }
+        }
+        let missing = (0..preferred.len())
+            .filter(|index| !declared.contains(index))
+            .map(|index| AlgorithmSetIssue::MissingCase {
+                name: names[index + 1].clone(),
+            });
+        issues.extend(missing);
+
+        if issues.is_empty() {
+            Ok(std::iter::once(vec![])
+                .chain(preferred.into_iter().flatten())
+                .collect())
+        } else {
+            Err(issues)
+        }
+    }
+}
+
+/// Cases of the hardcoded algorithms of the step, in the order of their
+/// names.
+fn hardcoded_cases(step: LastLayerStep) -> Vec<usize> {
+    step.hardcoded_algorithms()
+        .iter()
+        .map(|algorithm| {
+            let mut cube = Cube::default();
+            cube.execute_algorithm(&invert_algorithm(algorithm));
+            step.case(&cube)
+        })
+        .collect()
+}
+
+/// Name of the case of the cube in the step, such as `OLL 27` or `PLL T`,
+/// or `None` when the step cannot solve the cube.
+pub(crate) fn case_name(step: LastLayerStep, cube: &Cube) -> Option<String> {
+    if !step.is_case(cube) {
+        return None;
+    }
+    let case = step.case(cube);
+    let index = hardcoded_cases(step).iter().position(|c| *c == case)?;
+    let name = &case_names(step)[index];
+    Some(match step {
+        LastLayerStep::F2l | LastLayerStep::Oll if index > 0 => name.clone(),
+        _ => format!("{step} {name}"),
+    })
+}
+
+/// Names of the cases of the hardcoded algorithms of the step, the solved
+/// case first.
+fn case_names(step: LastLayerStep) -> Vec<String> {
+    match step {
+        LastLayerStep::F2l => std::iter::once("skip".to_string())
+            .chain((1..42).map(|index| format!("F2L {index}")))
+            .collect(),
+        LastLayerStep::Oll => std::iter::once("skip".to_string())
+            .chain((1..58).map(|index| format!("OLL {index}")))
+            .collect(),
+        LastLayerStep::Pll => std::iter::once("skip")
+            .chain(PLL_NAMES.iter().skip(1).copied())
+            .map(str::to_string)
+            .collect(),
+    }
+}
+
+/// Parse an algorithm made of face turns only.
+fn parse_strict(algorithm: &str) -> Option<Vec<Move>> {
+    let is_move = |token: &str| {
+        let mut chars = token.chars();
+        matches!(chars.next(), Some('U' | 'D' | 'F' | 'B' | 'R' | 'L'))
+            && matches!(chars.as_str(), "" | "2" | "'" | "2'")
+    };
+    algorithm
+        .split_whitespace()
+        .all(is_move)
+        .then(|| parse_algorithm(algorithm))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{
+        options::{Budget, SolveOptions},
+        solver::Step,
+        steps::{
+            f2l_pairs::{self, Slot},
+            oll,
+        },
+    };
+
+    use super::*;
+
+    #[test]
+    fn name_cases() {
+        let case = |algorithm: &str| {
+            let mut cube = Cube::default();
+            cube.execute_algorithm(&invert_algorithm(&parse_algorithm(algorithm)));
+            cube.execute_move(&Move::U);
+            cube
+        };
+        let sune = case("R U R' U R U2 R'");
+        assert_eq!(case_name(LastLayerStep::Oll, &sune).unwrap(), "OLL 27");
+        assert_eq!(case_name(LastLayerStep::Pll, &sune), None);
+        let t_perm = case("R U R' U' R' F R2 U' R' U' R U R' F'");
+        assert_eq!(case_name(LastLayerStep::Oll, &t_perm).unwrap(), "OLL skip");
+        assert_eq!(case_name(LastLayerStep::Pll, &t_perm).unwrap(), "PLL T");
+        assert_eq!(case_name(LastLayerStep::F2l, &t_perm).unwrap(), "F2L skip");
+        assert_eq!(
+            case_name(LastLayerStep::F2l, &case("R U R'")).unwrap(),
+            "F2L 30"
+        );
+        assert_eq!(case_name(LastLayerStep::F2l, &case("L U L'")), None);
+    }
+
+    #[test]
+    fn hardcoded_sets_round_trip() {
+        for step in [LastLayerStep::F2l, LastLayerStep::Oll, LastLayerStep::Pll] {
+            let set = AlgorithmSet::hardcoded(step);
+            assert_eq!(set.issues(), vec![]);
+            assert_eq!(AlgorithmSet::from_ron(&set.to_ron()).unwrap(), set);
+        }
+    }
+
+    #[test]
+    fn solve_with_preferred_algorithms() {
+        let mut set = AlgorithmSet::hardcoded(LastLayerStep::Oll);
+        let sune = set
+            .cases
+            .iter_mut()
+            .find(|case| case.name == "OLL 27")
+            .unwrap();
+        sune.algorithms.insert(0, "U R U R' U R U2 R'".to_string());
+        let solver =
+            oll::Solver::from_algorithms(&set.preferred_algorithms(LastLayerStep::Oll).unwrap());
+
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&parse_algorithm("R U2 R' U' R U' R' U'"));
+        assert_eq!(
+            solver.solve(&cube).unwrap(),
+            parse_algorithm("U R U R' U R U2 R'")
+        );
+        assert!(matches!(
+            set.preferred_algorithms(LastLayerStep::Pll),
+            Err(AlgorithmSetError::Step { .. })
+        ));
+    }
+
+    #[test]
+    fn insert_pairs_with_preferred_algorithms() {
+        let mut set = AlgorithmSet::hardcoded(LastLayerStep::F2l);
+        let case = set
+            .cases
+            .iter_mut()
+            .find(|case| case.name == "F2L 10")
+            .unwrap();
+        assert_eq!(case.algorithms, ["F' U F R U R'"]);
+        case.algorithms = vec!["R U R' U' R U R'".to_string()];
+        let solver = f2l_pairs::Solver::generate()
+            .with_algorithms(&set.preferred_algorithms(LastLayerStep::F2l).unwrap());
+
+        // The same case on the back left slot
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&invert_algorithm(&parse_algorithm("L U L' U' L U L'")));
+        let insertions = solver
+            .solve_pairs(&cube, &Budget::new(&SolveOptions::default()))
+            .unwrap();
+        assert_eq!(insertions.len(), 1);
+        assert_eq!(insertions[0].slot, Slot::BackLeft);
+        assert_eq!(insertions[0].moves, parse_algorithm("L U L' U' L U L'"));
+    }
+
+    #[test]
+    fn report_issues() {
+        let set = AlgorithmSet::from_ron(
+            r#"(
+                step: Pll,
+                cases: [
+                    (name: "T", algorithms: ["R U R' U' R' F R2 U' R' U' R U R' F'"], tags: ["2-look"]),
+                    (name: "Jb", algorithms: ["R U R' U' R' F R2 U' R' U' R U R' F'"]),
+                    (name: "Ja", algorithms: ["R U R' F' R U R' U' R' F R2 U' R'"]),
+                    (name: "Ua", algorithms: ["x R U R'"]),
+                    (name: "T", algorithms: []),
+                    (name: "Q", algorithms: ["R U R' U'"]),
+                ],
+            )"#,
+        )
+        .unwrap();
+        let issues = set.issues();
+        assert_eq!(
+            issues[..5],
+            [
+                AlgorithmSetIssue::DuplicateAlgorithm {
+                    case: "Jb".to_string(),
+                    algorithm: "R U R' U' R' F R2 U' R' U' R U R' F'".to_string(),
+                },
+                AlgorithmSetIssue::WrongCase {
+                    case: "Ja".to_string(),
+                    algorithm: "R U R' F' R U R' U' R' F R2 U' R'".to_string(),
+                    solved: Some("Jb".to_string()),
+                },
+                AlgorithmSetIssue::InvalidAlgorithm {
+                    case: "Ua".to_string(),
+                    algorithm: "x R U R'".to_string(),
+                },
+                AlgorithmSetIssue::DuplicateCase {
+                    name: "T".to_string(),
+                },
+                AlgorithmSetIssue::UnknownCase {
+                    name: "Q".to_string(),
+                },
+            ]
+        );
+        // Ja, Jb and Ua are given, although none of their algorithms is right
+        assert_eq!(issues.len(), 5 + 21 - 4);
+        assert!(issues[5..]
+            .iter()
+            .all(|issue| matches!(issue, AlgorithmSetIssue::MissingCase { .. })));
+    }
+}
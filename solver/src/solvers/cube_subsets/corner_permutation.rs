@@ -1,10 +1,9 @@
 use cube::subcases::CubeSubset;
 use cube_macros::CubeSubset;
 use serde::{Deserialize, Serialize};
-pub(crate) const CP_CASES: usize = 8 * 7 * 6 * 5 * 4 * 3 * 2;
 
 #[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize, CubeSubset)]
-pub(crate) struct CornerPermutation {
+pub struct CornerPermutation {
     urf_p: u8,
     ubr_p: u8,
     ufl_p: u8,
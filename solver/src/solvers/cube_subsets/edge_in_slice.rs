@@ -1,31 +1,49 @@
-use cube::{edge::Piece, subcases::CubeSubset, Cube};
+use cube::{
+    edge::Piece,
+    subcases::{place_edge, rank_combination, unrank_combination, CubeSubset},
+    Cube,
+};
 use serde::{Deserialize, Serialize};
 
-pub(crate) const EDGE_IN_SLICE_CASES: usize = 495;
+pub const EDGE_IN_SLICE_CASES: usize = 495;
 
+/// Which slots hold the four E-slice edges, regardless of their order.
 #[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
-pub(crate) struct EdgeInSlice {
+pub struct EdgeInSlice {
     edge_mask: u16,
 }
 
 impl CubeSubset for EdgeInSlice {
+    const CASES: usize = EDGE_IN_SLICE_CASES;
+
     fn from_cube(cube: &Cube) -> Self {
-        let mask = !is_slice_edge(&cube.edges[cube::UF].piece)
-            ^ !is_slice_edge(&cube.edges[cube::UR].piece) << 1
-            ^ !is_slice_edge(&cube.edges[cube::UB].piece) << 2
-            ^ !is_slice_edge(&cube.edges[cube::UL].piece) << 3
-            ^ !is_slice_edge(&cube.edges[cube::DF].piece) << 4
-            ^ !is_slice_edge(&cube.edges[cube::DR].piece) << 5
-            ^ !is_slice_edge(&cube.edges[cube::DB].piece) << 6
-            ^ !is_slice_edge(&cube.edges[cube::DL].piece) << 7
-            ^ is_slice_edge(&cube.edges[cube::FR].piece) << 8
-            ^ is_slice_edge(&cube.edges[cube::FL].piece) << 9
-            ^ is_slice_edge(&cube.edges[cube::BR].piece) << 10
-            ^ is_slice_edge(&cube.edges[cube::BL].piece) << 11;
+        let mask = cube
+            .edges
+            .iter()
+            .enumerate()
+            .filter(|(_, edge)| is_slice_edge(&edge.piece))
+            .fold(0, |mask, (slot, _)| mask | 1 << slot);
         Self { edge_mask: mask }
     }
+
+    fn index(&self) -> usize {
+        rank_combination(self.edge_mask)
+    }
+
+    fn cube_from_index(index: usize) -> Cube {
+        let mask = unrank_combination(index, 12, 4);
+        let mut cube = Cube::default();
+        let slots = (0..12).filter(|slot| mask & (1 << slot) != 0);
+        for (home, slot) in [cube::FR, cube::FL, cube::BL, cube::BR]
+            .into_iter()
+            .zip(slots)
+        {
+            place_edge(&mut cube, home, slot, 0);
+        }
+        cube
+    }
 }
 
-fn is_slice_edge(edge: &Piece) -> u16 {
-    matches!(edge, Piece::FR | Piece::FL | Piece::BR | Piece::BL).into()
+fn is_slice_edge(edge: &Piece) -> bool {
+    matches!(edge, Piece::FR | Piece::FL | Piece::BR | Piece::BL)
 }
@@ -1,11 +1,18 @@
-use cube::subcases::CubeSubset;
+use cube::{
+    subcases::{place_edge, rank_positions, unrank_positions, CubeSubset},
+    Cube,
+};
 use cube_macros::CubeSubset;
 use serde::{Deserialize, Serialize};
 
-pub(crate) const EP_CASES: usize = 8 * 7 * 6 * 5 * 4 * 3 * 2 * 4 * 3 * 2;
+pub const EP_CASES: usize = 8 * 7 * 6 * 5 * 4 * 3 * 2 * 4 * 3 * 2;
 
-#[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize, CubeSubset)]
-pub(crate) struct EdgePermutation {
+/// Permutation of all the edges, for cubes where the E-slice edges are in the
+/// E slice. The U and D edges then only move among the eight first slots and
+/// the E-slice edges among the four last ones, which keeps the index space to
+/// 8! * 4! instead of 12!.
+#[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub struct EdgePermutation {
     ur_p: u8,
     uf_p: u8,
     ul_p: u8,
@@ -20,10 +27,60 @@ pub(crate) struct EdgePermutation {
     bl_p: u8,
 }
 
-pub(crate) const E6P_CASES: usize = 12 * 11 * 10 * 9 * 8 * 7;
+impl CubeSubset for EdgePermutation {
+    const CASES: usize = EP_CASES;
+
+    fn from_cube(cube: &Cube) -> Self {
+        let mut positions = [0; 12];
+        for (slot, edge) in cube.edges.iter().enumerate() {
+            positions[edge.piece.clone() as usize] = u8::try_from(slot).unwrap();
+        }
+        Self {
+            ur_p: positions[cube::UR],
+            uf_p: positions[cube::UF],
+            ul_p: positions[cube::UL],
+            ub_p: positions[cube::UB],
+            dr_p: positions[cube::DR],
+            df_p: positions[cube::DF],
+            dl_p: positions[cube::DL],
+            db_p: positions[cube::DB],
+            fr_p: positions[cube::FR],
+            fl_p: positions[cube::FL],
+            br_p: positions[cube::BR],
+            bl_p: positions[cube::BL],
+        }
+    }
+
+    /// Cubes with edges out of their layer get the out of range index `Self::CASES`.
+    fn index(&self) -> usize {
+        let ud_edges = [
+            self.ur_p, self.uf_p, self.ul_p, self.ub_p, self.dr_p, self.df_p, self.dl_p, self.db_p,
+        ];
+        let slice_edges = [self.fr_p, self.fl_p, self.bl_p, self.br_p].map(|p| p.wrapping_sub(8));
+        if slice_edges.iter().any(|p| *p >= 4) {
+            return Self::CASES;
+        }
+        rank_positions(&ud_edges, 8) * 24 + rank_positions(&slice_edges, 4)
+    }
+
+    fn cube_from_index(index: usize) -> Cube {
+        let mut ud_edges = [0; 8];
+        let mut slice_edges = [0; 4];
+        unrank_positions(index / 24, 8, &mut ud_edges);
+        unrank_positions(index % 24, 4, &mut slice_edges);
+        let mut cube = Cube::default();
+        for (home, slot) in ud_edges.iter().enumerate() {
+            place_edge(&mut cube, home, usize::from(*slot), 0);
+        }
+        for (home, slot) in slice_edges.iter().enumerate() {
+            place_edge(&mut cube, home + 8, usize::from(*slot) + 8, 0);
+        }
+        cube
+    }
+}
 
 #[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize, CubeSubset)]
-pub(crate) struct Edge6_1Permutation {
+pub struct Edge6_1Permutation {
     ur_p: u8,
     uf_p: u8,
     ul_p: u8,
@@ -33,7 +90,7 @@ pub(crate) struct Edge6_1Permutation {
 }
 
 #[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize, CubeSubset)]
-pub(crate) struct Edge6_2Permutation {
+pub struct Edge6_2Permutation {
     dr_p: u8,
     df_p: u8,
     dl_p: u8,
@@ -0,0 +1,60 @@
+use cube::{
+    subcases::{
+        place_edge, rank_orientations, rank_positions, unrank_orientations, unrank_positions,
+        CubeSubset,
+    },
+    Cube,
+};
+use serde::{Deserialize, Serialize};
+
+pub const EO_LINE_CASES: usize = usize::pow(2, 11) * 12 * 11;
+
+/// Orientation of all the edges, with the slots of the DF and DB edges: the
+/// first step of the ZZ method.
+#[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub struct EoLine {
+    orientations: [u8; 12],
+    df_p: u8,
+    db_p: u8,
+}
+
+impl CubeSubset for EoLine {
+    const CASES: usize = EO_LINE_CASES;
+
+    fn from_cube(cube: &Cube) -> Self {
+        let mut orientations = [0; 12];
+        let mut positions = [0; 12];
+        for (slot, edge) in cube.edges.iter().enumerate() {
+            orientations[slot] = edge.orientation;
+            positions[edge.piece.clone() as usize] = u8::try_from(slot).unwrap();
+        }
+        Self {
+            orientations,
+            df_p: positions[cube::DF],
+            db_p: positions[cube::DB],
+        }
+    }
+
+    /// The orientation of the last edge is implied by the others, so it is
+    /// left out of the index.
+    fn index(&self) -> usize {
+        rank_orientations(&self.orientations[..11], 2) * 12 * 11
+            + rank_positions(&[self.df_p, self.db_p], 12)
+    }
+
+    fn cube_from_index(index: usize) -> Cube {
+        let mut orientations = [0; 12];
+        let mut positions = [0; 2];
+        unrank_orientations(index / (12 * 11), 2, &mut orientations[..11]);
+        orientations[11] = orientations[..11].iter().sum::<u8>() % 2;
+        unrank_positions(index % (12 * 11), 12, &mut positions);
+
+        let mut cube = Cube::default();
+        place_edge(&mut cube, cube::DF, usize::from(positions[0]), 0);
+        place_edge(&mut cube, cube::DB, usize::from(positions[1]), 0);
+        for (edge, orientation) in cube.edges.iter_mut().zip(orientations) {
+            edge.orientation = orientation;
+        }
+        cube
+    }
+}
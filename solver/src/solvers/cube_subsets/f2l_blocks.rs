@@ -2,8 +2,6 @@ use cube::subcases::CubeSubset;
 use cube_macros::CubeSubset;
 use serde::{Deserialize, Serialize};
 
-pub(crate) const BLOCK_CASES: usize = (8 * 3) * (12 * 11 * 10 * usize::pow(2, 3));
-
 #[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize, CubeSubset)]
 pub struct FrontLeftBlock {
     dlf: (u8, u8),
@@ -0,0 +1,113 @@
+use cube::{
+    algorithms::Move,
+    subcases::{
+        place_edge, rank_orientations, rank_positions, unrank_orientations, unrank_positions,
+    },
+    Cube,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::solvers::{search_space::StateSubset, slice_cube::SliceCube};
+
+/// Slots of the last six edges of the Roux method.
+const LSE_SLOTS: [usize; 6] = [cube::UR, cube::UF, cube::UL, cube::UB, cube::DF, cube::DB];
+
+pub const LSE_CASES: usize = 720 * 32 * 4 * 4;
+
+/// Last six edges of the Roux method, with the U layer and the M slice
+/// offsets. The orientation of the last edge follows from the others.
+#[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub struct Lse {
+    positions: [u8; 6],
+    orientations: [u8; 6],
+    /// Quarter turns of the U layer, when the corners are solved up to AUF
+    u_offset: Option<u8>,
+    centers: u8,
+}
+
+impl Lse {
+    /// Whether the pieces outside the last six edges are solved, up to AUF.
+    pub(crate) fn is_valid(&self) -> bool {
+        self.u_offset.is_some() && !self.positions.contains(&u8::MAX)
+    }
+
+    /// Whether the edges are oriented, so that `<M2, U>` solves them.
+    pub(crate) fn is_oriented(&self) -> bool {
+        self.orientations
+            .iter()
+            .all(|orientation| *orientation == 0)
+            && self.centers.is_multiple_of(2)
+    }
+}
+
+impl StateSubset<SliceCube> for Lse {
+    const CASES: usize = LSE_CASES;
+
+    fn from_state(state: &SliceCube) -> Self {
+        let cube = &state.cube;
+        let mut positions = [u8::MAX; 6];
+        let mut orientations = [0; 6];
+        for (slot_rank, slot) in LSE_SLOTS.iter().enumerate() {
+            let edge = &cube.edges[*slot];
+            if let Some(home) = LSE_SLOTS
+                .iter()
+                .position(|s| *s == edge.piece.clone() as usize)
+            {
+                positions[home] = u8::try_from(slot_rank).unwrap();
+                orientations[home] = edge.orientation;
+            }
+        }
+        let u_offset = (0..4).find(|offset| {
+            let mut solved = Cube::default();
+            for _ in 0..*offset {
+                solved.execute_move(&Move::U);
+            }
+            solved.corners == cube.corners
+        });
+        Self {
+            positions,
+            orientations,
+            u_offset,
+            centers: state.centers,
+        }
+    }
+
+    /// States whose corners or other edges are not solved get the out of
+    /// range index `Self::CASES`.
+    fn index(&self) -> usize {
+        let Some(u_offset) = self.u_offset else {
+            return Self::CASES;
+        };
+        if self.positions.contains(&u8::MAX) {
+            return Self::CASES;
+        }
+        let rank = rank_positions(&self.positions, 6);
+        let rank = rank * 32 + rank_orientations(&self.orientations[..5], 2);
+        let rank = rank * 4 + usize::from(u_offset);
+        rank * 4 + usize::from(self.centers)
+    }
+
+    fn state_from_index(index: usize) -> SliceCube {
+        let centers = u8::try_from(index % 4).unwrap();
+        let u_offset = index / 4 % 4;
+        let mut orientations = [0; 6];
+        unrank_orientations(index / 16 % 32, 2, &mut orientations[..5]);
+        orientations[5] = orientations[..5].iter().sum::<u8>() % 2;
+        let mut positions = [0; 6];
+        unrank_positions(index / 16 / 32, 6, &mut positions);
+
+        let mut cube = Cube::default();
+        for _ in 0..u_offset {
+            cube.execute_move(&Move::U);
+        }
+        for (home, slot_rank) in positions.iter().enumerate() {
+            place_edge(
+                &mut cube,
+                LSE_SLOTS[home],
+                LSE_SLOTS[usize::from(*slot_rank)],
+                orientations[home],
+            );
+        }
+        SliceCube { cube, centers }
+    }
+}
@@ -1,39 +1,60 @@
+//! # Subsets of the cube tracked by the steps.
+//!
+//! Each subset implements [`CubeSubset`](cube::subcases::CubeSubset), so that
+//! it can key a lookup table or a pattern database of a custom step.
+
 mod corner_permutation;
 mod cross;
 mod edge_in_slice;
 mod edge_permutation;
+mod eo_line;
 mod f2l_blocks;
+mod lse;
 mod oll;
 mod orientation;
 mod pll;
+mod roux_blocks;
+mod thistlethwaite;
+
+pub use self::cross::Cross;
+pub use self::cross::CROSS_CASES;
+
+pub use self::f2l_blocks::BackLeftBlock;
+pub use self::f2l_blocks::BackRightBlock;
+pub use self::f2l_blocks::FrontLeftBlock;
+pub use self::f2l_blocks::FrontRightBlock;
+
+pub use self::oll::Oll;
+pub use self::oll::OLL_CASES;
+
+pub use self::pll::Pll;
+pub use self::pll::PLL_CASES;
 
-pub(super) use self::cross::Cross;
-pub(super) use self::cross::CROSS_CASES;
+pub use self::edge_permutation::EdgePermutation;
 
-pub(super) use self::f2l_blocks::BackLeftBlock;
-pub(super) use self::f2l_blocks::BackRightBlock;
-pub(super) use self::f2l_blocks::FrontLeftBlock;
-pub(super) use self::f2l_blocks::FrontRightBlock;
-pub(super) use self::f2l_blocks::BLOCK_CASES;
+pub use self::edge_permutation::Edge6_1Permutation;
+pub use self::edge_permutation::Edge6_2Permutation;
 
-pub(super) use self::oll::Oll;
-pub(super) use self::oll::OLL_CASES;
+pub use self::corner_permutation::CornerPermutation;
 
-pub(super) use self::pll::Pll;
-pub(super) use self::pll::PLL_CASES;
+pub use self::edge_in_slice::EdgeInSlice;
 
-pub(super) use self::edge_permutation::EdgePermutation;
-pub(super) use self::edge_permutation::EP_CASES;
+pub use self::orientation::Orientation;
 
-pub(super) use self::edge_permutation::Edge6_1Permutation;
-pub(super) use self::edge_permutation::Edge6_2Permutation;
-pub(super) use self::edge_permutation::E6P_CASES;
+pub use self::eo_line::EoLine;
 
-pub(super) use self::corner_permutation::CornerPermutation;
-pub(super) use self::corner_permutation::CP_CASES;
+pub(crate) use self::roux_blocks::are_blocks_solved;
+pub(crate) use self::roux_blocks::is_first_block_solved;
+pub use self::roux_blocks::Cmll;
+pub use self::roux_blocks::FirstBlockBack;
+pub use self::roux_blocks::FirstBlockFront;
+pub use self::roux_blocks::SecondBlockBack;
+pub use self::roux_blocks::SecondBlockFront;
 
-pub(super) use self::edge_in_slice::EdgeInSlice;
-pub(super) use self::edge_in_slice::EDGE_IN_SLICE_CASES;
+pub use self::lse::Lse;
 
-pub(super) use self::orientation::Orientation;
-pub(super) use self::orientation::ORIENTATION_CASES;
+pub(crate) use self::thistlethwaite::is_in_group;
+pub use self::thistlethwaite::CornerOrientation;
+pub use self::thistlethwaite::EdgeOrientation;
+pub use self::thistlethwaite::HalfTurns;
+pub use self::thistlethwaite::Tetrads;
@@ -1,13 +1,13 @@
 use serde::{Deserialize, Serialize};
 
-use cube::subcases::CubeSubset;
+use cube::subcases::{rank_orientations, unrank_orientations, CubeSubset};
 
 use cube::{self, Cube};
 
-pub(crate) const OLL_CASES: usize = 58;
+pub const OLL_CASES: usize = 58;
 
 #[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
-pub(crate) struct Oll {
+pub struct Oll {
     uf: u8,
     ur: u8,
     ub: u8,
@@ -19,6 +19,8 @@ pub(crate) struct Oll {
 }
 
 impl CubeSubset for Oll {
+    const CASES: usize = usize::pow(2, 4) * usize::pow(3, 4);
+
     fn from_cube(cube: &Cube) -> Self {
         Self {
             uf: cube.edges[cube::UF].orientation,
@@ -31,4 +33,28 @@ impl CubeSubset for Oll {
             ufl: cube.corners[cube::UFL].orientation,
         }
     }
+
+    fn index(&self) -> usize {
+        let edges = rank_orientations(&[self.uf, self.ur, self.ub, self.ul], 2);
+        let corners = rank_orientations(&[self.urf, self.ubr, self.ulb, self.ufl], 3);
+        edges * usize::pow(3, 4) + corners
+    }
+
+    fn cube_from_index(index: usize) -> Cube {
+        let mut edges = [0; 4];
+        let mut corners = [0; 4];
+        unrank_orientations(index / usize::pow(3, 4), 2, &mut edges);
+        unrank_orientations(index % usize::pow(3, 4), 3, &mut corners);
+        let mut cube = Cube::default();
+        for (slot, orientation) in [cube::UF, cube::UR, cube::UB, cube::UL].iter().zip(edges) {
+            cube.edges[*slot].orientation = orientation;
+        }
+        for (slot, orientation) in [cube::URF, cube::UBR, cube::ULB, cube::UFL]
+            .iter()
+            .zip(corners)
+        {
+            cube.corners[*slot].orientation = orientation;
+        }
+        cube
+    }
 }
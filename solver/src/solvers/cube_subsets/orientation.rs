@@ -1,10 +1,13 @@
-use cube::{subcases::CubeSubset, Cube};
+use cube::{
+    subcases::{rank_orientations, unrank_orientations, CubeSubset},
+    Cube,
+};
 use serde::{Deserialize, Serialize};
 
-pub(crate) const ORIENTATION_CASES: usize = usize::pow(3, 7) * usize::pow(2, 11);
+pub const ORIENTATION_CASES: usize = usize::pow(3, 7) * usize::pow(2, 11);
 
 #[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
-pub(crate) struct Orientation {
+pub struct Orientation {
     uf: u8,
     ur: u8,
     ub: u8,
@@ -27,7 +30,37 @@ pub(crate) struct Orientation {
     ubr: u8,
 }
 
+/// Edge slots in the order of the fields of [`Orientation`]
+const EDGE_SLOTS: [usize; 12] = [
+    cube::UF,
+    cube::UR,
+    cube::UB,
+    cube::UL,
+    cube::DF,
+    cube::DR,
+    cube::DB,
+    cube::DL,
+    cube::FR,
+    cube::FL,
+    cube::BR,
+    cube::BL,
+];
+
+/// Corner slots in the order of the fields of [`Orientation`]
+const CORNER_SLOTS: [usize; 8] = [
+    cube::DFR,
+    cube::DLF,
+    cube::DBL,
+    cube::DRB,
+    cube::URF,
+    cube::UFL,
+    cube::ULB,
+    cube::UBR,
+];
+
 impl CubeSubset for Orientation {
+    const CASES: usize = ORIENTATION_CASES;
+
     fn from_cube(cube: &Cube) -> Self {
         Self {
             uf: cube.edges[cube::UF].orientation,
@@ -52,4 +85,35 @@ impl CubeSubset for Orientation {
             ubr: cube.corners[cube::UBR].orientation,
         }
     }
+
+    /// The orientation of the last edge and of the last corner are implied
+    /// by the others, so they are left out of the index.
+    fn index(&self) -> usize {
+        let edges = [
+            self.uf, self.ur, self.ub, self.ul, self.df, self.dr, self.db, self.dl, self.fr,
+            self.fl, self.br,
+        ];
+        let corners = [
+            self.dfr, self.dlf, self.dbl, self.drb, self.urf, self.ufl, self.ulb,
+        ];
+        rank_orientations(&edges, 2) * usize::pow(3, 7) + rank_orientations(&corners, 3)
+    }
+
+    fn cube_from_index(index: usize) -> Cube {
+        let mut edges = [0; 12];
+        let mut corners = [0; 8];
+        unrank_orientations(index / usize::pow(3, 7), 2, &mut edges[..11]);
+        unrank_orientations(index % usize::pow(3, 7), 3, &mut corners[..7]);
+        edges[11] = (2 - edges[..11].iter().sum::<u8>() % 2) % 2;
+        corners[7] = (3 - corners[..7].iter().sum::<u8>() % 3) % 3;
+
+        let mut cube = Cube::default();
+        for (slot, orientation) in EDGE_SLOTS.iter().zip(edges) {
+            cube.edges[*slot].orientation = orientation;
+        }
+        for (slot, orientation) in CORNER_SLOTS.iter().zip(corners) {
+            cube.corners[*slot].orientation = orientation;
+        }
+        cube
+    }
 }
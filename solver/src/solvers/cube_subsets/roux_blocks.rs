@@ -0,0 +1,71 @@
+use cube::{subcases::CubeSubset, Cube};
+use cube_macros::CubeSubset;
+use serde::{Deserialize, Serialize};
+
+/// First block of the Roux method with its front corner, the 1x2x3 block on
+/// the L face being split in two halves sharing the edges.
+#[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize, CubeSubset)]
+pub struct FirstBlockFront {
+    dlf: (u8, u8),
+    dl: (u8, u8),
+    fl: (u8, u8),
+    bl: (u8, u8),
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize, CubeSubset)]
+pub struct FirstBlockBack {
+    dbl: (u8, u8),
+    dl: (u8, u8),
+    fl: (u8, u8),
+    bl: (u8, u8),
+}
+
+/// Second block of the Roux method with its front corner, the 1x2x3 block
+/// on the R face being split in two halves sharing the edges.
+#[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize, CubeSubset)]
+pub struct SecondBlockFront {
+    dfr: (u8, u8),
+    dr: (u8, u8),
+    fr: (u8, u8),
+    br: (u8, u8),
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize, CubeSubset)]
+pub struct SecondBlockBack {
+    drb: (u8, u8),
+    dr: (u8, u8),
+    fr: (u8, u8),
+    br: (u8, u8),
+}
+
+/// Corners of the U layer, solved by CMLL.
+#[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize, CubeSubset)]
+pub struct Cmll {
+    urf: (u8, u8),
+    ufl: (u8, u8),
+    ulb: (u8, u8),
+    ubr: (u8, u8),
+}
+
+/// Whether the pieces of the first block are solved.
+pub(crate) fn is_first_block_solved(cube: &Cube) -> bool {
+    let solved = Cube::default();
+    [cube::DL, cube::FL, cube::BL]
+        .iter()
+        .all(|slot| cube.edges[*slot] == solved.edges[*slot])
+        && [cube::DLF, cube::DBL]
+            .iter()
+            .all(|slot| cube.corners[*slot] == solved.corners[*slot])
+}
+
+/// Whether the pieces of both blocks are solved.
+pub(crate) fn are_blocks_solved(cube: &Cube) -> bool {
+    let solved = Cube::default();
+    is_first_block_solved(cube)
+        && [cube::DR, cube::FR, cube::BR]
+            .iter()
+            .all(|slot| cube.edges[*slot] == solved.edges[*slot])
+        && [cube::DFR, cube::DRB]
+            .iter()
+            .all(|slot| cube.corners[*slot] == solved.corners[*slot])
+}
@@ -0,0 +1,345 @@
+//! # Subsets of the phases of Thistlethwaite's algorithm.
+//!
+//! Each phase brings the cube into a smaller group, generated by fewer moves:
+//!
+//! - G1 = `<U, D, L, R, F2, B2>`, where the edges are oriented,
+//! - G2 = `<U, D, L2, R2, F2, B2>`, where the corners are oriented too and
+//!   the E-slice edges are in the E slice,
+//! - G3 = `<U2, D2, L2, R2, F2, B2>`,
+//!
+//! and each subset tells apart the cosets of the next group in the current
+//! one.
+
+use std::sync::OnceLock;
+
+use cube::{
+    algorithms::Move,
+    edge,
+    subcases::{
+        place_edge, rank_combination, rank_orientations, rank_positions, unrank_combination,
+        unrank_orientations, unrank_positions, CubeSubset,
+    },
+    Cube,
+};
+use serde::{Deserialize, Serialize};
+
+use super::EdgeInSlice;
+
+pub const EDGE_ORIENTATION_CASES: usize = usize::pow(2, 11);
+pub const CORNER_ORIENTATION_CASES: usize = usize::pow(3, 7);
+pub const TETRADS_CASES: usize = CORNER_COSETS * 70;
+pub const HALF_TURNS_CASES: usize = G3_CORNER_PERMUTATIONS * usize::pow(24, 3);
+
+/// Cosets of the corner permutations of G3 among all the corner permutations
+const CORNER_COSETS: usize = 420;
+/// Corner permutations, ranked as the pieces in slot order
+const CORNER_PERMUTATIONS: usize = 40320;
+/// Corner permutations reachable with half turns
+const G3_CORNER_PERMUTATIONS: usize = 96;
+
+/// Orientation of all the edges, solved in G1.
+#[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub struct EdgeOrientation {
+    orientations: [u8; 12],
+}
+
+impl CubeSubset for EdgeOrientation {
+    const CASES: usize = EDGE_ORIENTATION_CASES;
+
+    fn from_cube(cube: &Cube) -> Self {
+        Self {
+            orientations: cube.edges.clone().map(|edge| edge.orientation),
+        }
+    }
+
+    /// The orientation of the last edge is implied by the others, so it is
+    /// left out of the index.
+    fn index(&self) -> usize {
+        rank_orientations(&self.orientations[..11], 2)
+    }
+
+    fn cube_from_index(index: usize) -> Cube {
+        let mut orientations = [0; 12];
+        unrank_orientations(index, 2, &mut orientations[..11]);
+        orientations[11] = orientations[..11].iter().sum::<u8>() % 2;
+        let mut cube = Cube::default();
+        for (edge, orientation) in cube.edges.iter_mut().zip(orientations) {
+            edge.orientation = orientation;
+        }
+        cube
+    }
+}
+
+/// Orientation of the corners, solved in G2 along with [`EdgeInSlice`].
+#[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub struct CornerOrientation {
+    orientations: [u8; 8],
+}
+
+impl CubeSubset for CornerOrientation {
+    const CASES: usize = CORNER_ORIENTATION_CASES;
+
+    fn from_cube(cube: &Cube) -> Self {
+        Self {
+            orientations: cube.corners.clone().map(|corner| corner.orientation),
+        }
+    }
+
+    /// The orientation of the last corner is implied by the others, so it is
+    /// left out of the index.
+    fn index(&self) -> usize {
+        rank_orientations(&self.orientations[..7], 3)
+    }
+
+    fn cube_from_index(index: usize) -> Cube {
+        let mut orientations = [0; 8];
+        unrank_orientations(index, 3, &mut orientations[..7]);
+        orientations[7] = (3 - orientations[..7].iter().sum::<u8>() % 3) % 3;
+        let mut cube = Cube::default();
+        for (corner, orientation) in cube.corners.iter_mut().zip(orientations) {
+            corner.orientation = orientation;
+        }
+        cube
+    }
+}
+
+/// Coset of the corner permutation and slots of the M-slice edges, solved in
+/// G3. The corner cosets are those of the permutations of G3 applied before
+/// the corner permutation, so that the distance to G3 only depends on the
+/// coset.
+#[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub struct Tetrads {
+    corner_coset: u16,
+    m_slice_mask: u16,
+}
+
+impl CubeSubset for Tetrads {
+    const CASES: usize = TETRADS_CASES;
+
+    fn from_cube(cube: &Cube) -> Self {
+        Self {
+            corner_coset: corner_cosets().cosets[rank_corners(cube)],
+            m_slice_mask: m_slice_mask(cube),
+        }
+    }
+
+    /// Cubes with M-slice edges in the E slice get the out of range index
+    /// `Self::CASES`.
+    fn index(&self) -> usize {
+        if self.m_slice_mask >= 1 << 8 {
+            return Self::CASES;
+        }
+        usize::from(self.corner_coset) * 70 + rank_combination(self.m_slice_mask)
+    }
+
+    fn cube_from_index(index: usize) -> Cube {
+        let mask = unrank_combination(index % 70, 8, 4);
+        let mut cube = Cube::default();
+        set_corners(&mut cube, corner_cosets().representatives[index / 70]);
+        let slots = (0..8).filter(|slot| mask & (1 << slot) != 0);
+        for (home, slot) in [cube::UF, cube::UB, cube::DF, cube::DB]
+            .into_iter()
+            .zip(slots)
+        {
+            place_edge(&mut cube, home, slot, 0);
+        }
+        cube
+    }
+}
+
+/// Corner permutation among those of G3, and permutation of the edges of
+/// each slice, solved in the solved state. Half turns keep the edges in
+/// their slice.
+#[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub struct HalfTurns {
+    corners: usize,
+    edges: [u8; 12],
+}
+
+/// Edge slots of the M, S and E slices
+const SLICES: [[usize; 4]; 3] = [
+    [cube::UF, cube::UB, cube::DF, cube::DB],
+    [cube::UR, cube::UL, cube::DR, cube::DL],
+    [cube::FR, cube::FL, cube::BL, cube::BR],
+];
+
+impl CubeSubset for HalfTurns {
+    const CASES: usize = HALF_TURNS_CASES;
+
+    fn from_cube(cube: &Cube) -> Self {
+        Self {
+            corners: rank_corners(cube),
+            edges: cube.edges.clone().map(|edge| edge.piece as u8),
+        }
+    }
+
+    /// Cubes with corners out of G3 or edges out of their slice get the out
+    /// of range index `Self::CASES`.
+    fn index(&self) -> usize {
+        let Ok(mut index) = corner_cosets().half_turns.binary_search(&self.corners) else {
+            return Self::CASES;
+        };
+        for slots in &SLICES {
+            let mut positions = [0; 4];
+            for (position, slot) in positions.iter_mut().zip(slots) {
+                let piece = usize::from(self.edges[*slot]);
+                let Some(home) = slots.iter().position(|s| *s == piece) else {
+                    return Self::CASES;
+                };
+                *position = u8::try_from(home).unwrap();
+            }
+            index = index * 24 + rank_positions(&positions, 4);
+        }
+        index
+    }
+
+    fn cube_from_index(mut index: usize) -> Cube {
+        let solved = Cube::default();
+        let mut cube = Cube::default();
+        for slots in SLICES.iter().rev() {
+            let mut positions = [0; 4];
+            unrank_positions(index % 24, 4, &mut positions);
+            index /= 24;
+            for (slot, position) in slots.iter().zip(positions) {
+                cube.edges[*slot] = solved.edges[slots[usize::from(position)]].clone();
+            }
+        }
+        set_corners(&mut cube, corner_cosets().half_turns[index]);
+        cube
+    }
+}
+
+struct CornerCosets {
+    /// Coset of each corner permutation
+    cosets: Vec<u16>,
+    /// Rank of a corner permutation of each coset
+    representatives: Vec<usize>,
+    /// Sorted ranks of the corner permutations of G3, the coset of the
+    /// solved corners
+    half_turns: Vec<usize>,
+}
+
+/// Cosets of the corner permutations, found by flood filling each coset with
+/// the half turns applied before the permutation.
+fn corner_cosets() -> &'static CornerCosets {
+    static COSETS: OnceLock<CornerCosets> = OnceLock::new();
+    COSETS.get_or_init(|| {
+        // Pieces in slot order after each half turn, applied to the pieces
+        // of a permutation to apply the half turn before it
+        let half_turns = [Move::U2, Move::D2, Move::F2, Move::B2, Move::R2, Move::L2].map(|m| {
+            let mut cube = Cube::default();
+            cube.execute_move(&m);
+            cube.corners.map(|corner| corner.piece as u8)
+        });
+
+        let mut cosets = vec![u16::MAX; CORNER_PERMUTATIONS];
+        let mut representatives = vec![];
+        for rank in 0..CORNER_PERMUTATIONS {
+            if cosets[rank] != u16::MAX {
+                continue;
+            }
+            let coset = u16::try_from(representatives.len()).unwrap();
+            representatives.push(rank);
+            cosets[rank] = coset;
+            let mut stack = vec![rank];
+            while let Some(rank) = stack.pop() {
+                let mut pieces = [0; 8];
+                unrank_positions(rank, 8, &mut pieces);
+                for half_turn in &half_turns {
+                    let next = rank_positions(&pieces.map(|p| half_turn[usize::from(p)]), 8);
+                    if cosets[next] == u16::MAX {
+                        cosets[next] = coset;
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+        debug_assert_eq!(representatives.len(), CORNER_COSETS);
+        let solved = cosets[rank_corners(&Cube::default())];
+        let half_turns = (0..CORNER_PERMUTATIONS)
+            .filter(|rank| cosets[*rank] == solved)
+            .collect::<Vec<_>>();
+        debug_assert_eq!(half_turns.len(), G3_CORNER_PERMUTATIONS);
+        CornerCosets {
+            cosets,
+            representatives,
+            half_turns,
+        }
+    })
+}
+
+fn rank_corners(cube: &Cube) -> usize {
+    rank_positions(&cube.corners.clone().map(|corner| corner.piece as u8), 8)
+}
+
+/// Place the corners as in the permutation of the given rank.
+fn set_corners(cube: &mut Cube, rank: usize) {
+    let mut pieces = [0; 8];
+    unrank_positions(rank, 8, &mut pieces);
+    let solved = Cube::default();
+    for (corner, piece) in cube.corners.iter_mut().zip(pieces) {
+        *corner = solved.corners[usize::from(piece)].clone();
+    }
+}
+
+/// Slots of the M-slice edges, as a bit mask.
+fn m_slice_mask(cube: &Cube) -> u16 {
+    cube.edges
+        .iter()
+        .enumerate()
+        .filter(|(_, edge)| is_m_slice_edge(&edge.piece))
+        .fold(0, |mask, (slot, _)| mask | 1 << slot)
+}
+
+fn is_m_slice_edge(edge: &edge::Piece) -> bool {
+    matches!(
+        edge,
+        edge::Piece::UF | edge::Piece::UB | edge::Piece::DF | edge::Piece::DB
+    )
+}
+
+/// Whether the cube is in G1, G2 or G3, the higher the closer to solved.
+pub(crate) fn is_in_group(cube: &Cube, group: usize) -> bool {
+    let solved = Cube::default();
+    [
+        EdgeOrientation::from_cube(cube) == EdgeOrientation::from_cube(&solved),
+        CornerOrientation::from_cube(cube) == CornerOrientation::from_cube(&solved)
+            && EdgeInSlice::from_cube(cube) == EdgeInSlice::from_cube(&solved),
+        Tetrads::from_cube(cube) == Tetrads::from_cube(&solved),
+    ]
+    .iter()
+    .take(group)
+    .all(|solved| *solved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cube::algorithms::parse_algorithm;
+
+    #[test]
+    fn corner_cosets_of_half_turns() {
+        let cosets = corner_cosets();
+        assert_eq!(cosets.representatives.len(), CORNER_COSETS);
+
+        let solved = Tetrads::from_cube(&Cube::default());
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&parse_algorithm("R2 U2 F2 L2 D2 B2 R2 F2"));
+        assert!(Tetrads::from_cube(&cube) == solved);
+        cube.execute_algorithm(&parse_algorithm("U"));
+        assert!(Tetrads::from_cube(&cube) != solved);
+    }
+
+    #[test]
+    fn groups_of_moves() {
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&parse_algorithm("U2 R2 D' F2 L2 U"));
+        assert!(is_in_group(&cube, 2));
+        assert!(!is_in_group(&cube, 3));
+        cube.execute_algorithm(&parse_algorithm("R"));
+        assert!(is_in_group(&cube, 1));
+        assert!(!is_in_group(&cube, 2));
+        cube.execute_algorithm(&parse_algorithm("F"));
+        assert!(!is_in_group(&cube, 1));
+    }
+}
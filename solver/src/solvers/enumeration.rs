@@ -0,0 +1,358 @@
+//! # Enumerate several solutions of a step or a method.
+//!
+//! Steps yield their solutions in non-decreasing length. The solutions of a
+//! method chain the solutions of its steps: each partial solution is kept in
+//! a priority queue keyed by an estimate of the length of the shortest
+//! solution it can lead to, so that complete solutions also come out in
+//! non-decreasing length while the solutions of the steps are only computed
+//! when needed.
+
+use std::{cmp::Ordering, collections::BinaryHeap, iter::Peekable};
+
+use cube::{algorithms::Move, Cube};
+
+use super::{
+    rotation::{Face, Rotation},
+    solver::{Solution, Stage, Step},
+};
+
+/// Options of the enumeration of solutions.
+#[derive(Clone, Debug, Default)]
+pub struct EnumerationOptions {
+    pub(crate) max_length: Option<usize>,
+    pub(crate) max_count: Option<usize>,
+    pub(crate) max_step_solutions: Option<usize>,
+    pub(crate) deduplicate: bool,
+    pub(crate) optimal_only: bool,
+}
+
+impl EnumerationOptions {
+    /// Enumerate all the solutions, without limit.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Do not yield solutions longer than the given number of moves.
+    #[must_use]
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Stop after yielding the given number of solutions.
+    #[must_use]
+    pub fn max_count(mut self, max_count: usize) -> Self {
+        self.max_count = Some(max_count);
+        self
+    }
+
+    /// Only chain the first solutions of each step of a method, instead of
+    /// all of them. Without this limit, the solutions of the first steps are
+    /// enumerated until no shorter complete solution is possible, which is
+    /// only practical with a tight maximum length.
+    #[must_use]
+    pub fn max_step_solutions(mut self, max_step_solutions: usize) -> Self {
+        self.max_step_solutions = Some(max_step_solutions);
+        self
+    }
+
+    /// Only yield one of the sequences that differ by the order of two
+    /// consecutive moves on opposite faces, such as `U D` and `D U`.
+    #[must_use]
+    pub fn deduplicate(mut self, deduplicate: bool) -> Self {
+        self.deduplicate = deduplicate;
+        self
+    }
+
+    /// Stop once all the solutions of the shortest length are yielded.
+    #[must_use]
+    pub fn optimal_only(mut self, optimal_only: bool) -> Self {
+        self.optimal_only = optimal_only;
+        self
+    }
+
+    pub(crate) fn allows_length(&self, length: usize) -> bool {
+        self.max_length.is_none_or(|max| length <= max)
+    }
+
+    /// Options for the steps of a method, once `length` moves are used.
+    fn for_step(&self, length: usize) -> Self {
+        Self {
+            max_length: self.max_length.map(|max| max.saturating_sub(length)),
+            max_count: self.max_step_solutions,
+            max_step_solutions: None,
+            deduplicate: self.deduplicate,
+            optimal_only: false,
+        }
+    }
+}
+
+type StepSolutionIterator<'a> = Peekable<Box<dyn Iterator<Item = Vec<Move>> + 'a>>;
+
+/// Object-safe view of a step, so that the steps of a method can be chained.
+pub trait StepSolutions {
+    fn name(&self) -> &str;
+    fn case_name(&self, cube: &Cube) -> Option<String>;
+    fn lower_bound(&self, cube: &Cube) -> usize;
+    fn step_solutions<'a>(
+        &'a self,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Box<dyn Iterator<Item = Vec<Move>> + 'a>;
+}
+
+impl<S: Step> StepSolutions for S {
+    fn name(&self) -> &str {
+        S::NAME
+    }
+
+    fn case_name(&self, cube: &Cube) -> Option<String> {
+        Step::case_name(self, cube)
+    }
+
+    fn lower_bound(&self, cube: &Cube) -> usize {
+        Step::lower_bound(self, cube)
+    }
+
+    fn step_solutions<'a>(
+        &'a self,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Box<dyn Iterator<Item = Vec<Move>> + 'a> {
+        self.solutions(cube, options)
+    }
+}
+
+/// Progress of a partial solution through the next step.
+enum NextStep<'a> {
+    /// The solutions of the next step were not computed yet
+    Unexplored,
+    Solutions(StepSolutionIterator<'a>),
+    /// All the steps are solved
+    Solved,
+}
+
+/// Partial solution of a method, waiting in the priority queue.
+struct Candidate<'a> {
+    /// Estimated length of the shortest complete solution the candidate can
+    /// lead to
+    length: usize,
+    /// Insertion order, so that ties are broken deterministically
+    order: usize,
+    moves: Vec<Move>,
+    /// Number of moves at the end of each solved step
+    stage_ends: Vec<usize>,
+    cube: Cube,
+    /// Index of the next step to solve
+    step: usize,
+    next_step: NextStep<'a>,
+}
+
+impl PartialEq for Candidate<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate<'_> {}
+
+impl PartialOrd for Candidate<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed, so that the binary heap pops the shortest candidate first
+        (other.length, other.order).cmp(&(self.length, self.order))
+    }
+}
+
+/// Solutions of a method, in non-decreasing length.
+pub struct MethodSolutions<'a> {
+    steps: Vec<&'a dyn StepSolutions>,
+    /// Cube the solutions start from
+    cube: Cube,
+    options: EnumerationOptions,
+    queue: BinaryHeap<Candidate<'a>>,
+    inserted: usize,
+    found: usize,
+    optimal_length: Option<usize>,
+    /// Rotation the cube was solved with, whose moves are given back in the
+    /// orientation of the scramble
+    rotation: Option<Rotation>,
+}
+
+impl<'a> MethodSolutions<'a> {
+    /// Solutions of the cube chaining the solutions of the steps, in order.
+    #[must_use]
+    pub fn new(
+        steps: Vec<&'a dyn StepSolutions>,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Self {
+        let mut solutions = Self {
+            steps,
+            cube: cube.clone(),
+            options: options.clone(),
+            queue: BinaryHeap::new(),
+            inserted: 0,
+            found: 0,
+            optimal_length: None,
+            rotation: None,
+        };
+        solutions.push(vec![], vec![], cube.clone(), 0);
+        solutions
+    }
+
+    /// Solutions of a cube solved after the rotation, given in the
+    /// orientation of the cube before it.
+    pub(super) fn with_rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = Some(rotation);
+        self
+    }
+
+    /// Queue a partial solution whose first `step` steps are solved. Its
+    /// estimated length only relies on the heuristic of the next step, whose
+    /// solutions are computed once the candidate reaches the front of the
+    /// queue.
+    fn push(&mut self, moves: Vec<Move>, stage_ends: Vec<usize>, cube: Cube, step: usize) {
+        let (length, next_step) = if step < self.steps.len() {
+            let lower_bound = self.steps[step].lower_bound(&cube);
+            if lower_bound == usize::MAX {
+                return;
+            }
+            (moves.len() + lower_bound, NextStep::Unexplored)
+        } else {
+            (moves.len(), NextStep::Solved)
+        };
+        self.queue.push(Candidate {
+            length,
+            order: self.inserted,
+            moves,
+            stage_ends,
+            cube,
+            step,
+            next_step,
+        });
+        self.inserted += 1;
+    }
+
+    /// Solution of a candidate whose steps are all solved, one stage per step.
+    fn solution(&self, candidate: &Candidate) -> Solution {
+        let mut cube = self.cube.clone();
+        let mut start = 0;
+        let mut solution = Solution::default();
+        for (step, end) in self.steps.iter().zip(&candidate.stage_ends) {
+            let moves = candidate.moves[start..*end].to_vec();
+            let mut stage = Stage::new(step.name(), moves);
+            stage.case = step.case_name(&cube);
+            cube.execute_algorithm(&stage.moves);
+            solution.stages.push(stage);
+            start = *end;
+        }
+        if let Some(rotation) = &self.rotation {
+            solution.map_moves(|moves| rotation.original_moves(moves));
+            solution.rotation = Some(rotation.face()).filter(|face| *face != Face::D);
+        }
+        solution
+    }
+
+    /// Queue the candidate again, estimated with the next solution of its step.
+    fn push_back(&mut self, mut candidate: Candidate<'a>) {
+        let NextStep::Solutions(solutions) = &mut candidate.next_step else {
+            return;
+        };
+        let Some(next) = solutions.peek() else {
+            return;
+        };
+        candidate.length = candidate.moves.len() + next.len();
+        candidate.order = self.inserted;
+        self.inserted += 1;
+        self.queue.push(candidate);
+    }
+}
+
+impl Iterator for MethodSolutions<'_> {
+    type Item = Solution;
+
+    fn next(&mut self) -> Option<Solution> {
+        if self.options.max_count.is_some_and(|max| self.found >= max) {
+            return None;
+        }
+        while let Some(mut candidate) = self.queue.pop() {
+            match &mut candidate.next_step {
+                NextStep::Solved => {
+                    if self.optimal_length.is_some_and(|l| candidate.length > l) {
+                        self.queue.clear();
+                        return None;
+                    }
+                    if self.options.optimal_only {
+                        self.optimal_length = Some(candidate.length);
+                    }
+                    self.found += 1;
+                    return Some(self.solution(&candidate));
+                }
+                NextStep::Unexplored => {
+                    let options = self.options.for_step(candidate.moves.len());
+                    let solutions = self.steps[candidate.step]
+                        .step_solutions(&candidate.cube, &options)
+                        .peekable();
+                    candidate.next_step = NextStep::Solutions(solutions);
+                }
+                NextStep::Solutions(solutions) => {
+                    let Some(step_solution) = solutions.next() else {
+                        continue;
+                    };
+                    let mut cube = candidate.cube.clone();
+                    cube.execute_algorithm(&step_solution);
+                    let mut moves = candidate.moves.clone();
+                    moves.extend(step_solution);
+                    let mut stage_ends = candidate.stage_ends.clone();
+                    stage_ends.push(moves.len());
+                    self.push(moves, stage_ends, cube, candidate.step + 1);
+                }
+            }
+            // The candidate may lead to other solutions, with the next
+            // solutions of its step.
+            self.push_back(candidate);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solvers::steps::{oll, pll};
+    use cube::algorithms::parse_algorithm;
+
+    #[test]
+    fn chain_last_layer_steps() {
+        let oll_solver = oll::Solver::generate();
+        let pll_solver = pll::Solver::generate();
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&parse_algorithm("R U R' U R U2 R' U"));
+
+        let solutions = MethodSolutions::new(
+            vec![&oll_solver, &pll_solver],
+            &cube,
+            &EnumerationOptions::new(),
+        )
+        .collect::<Vec<_>>();
+        assert_eq!(solutions.len(), 1);
+        assert_eq!(solutions[0].stages[0].case.as_deref(), Some("OLL 26"));
+
+        cube.execute_algorithm(&solutions[0].moves());
+        assert_eq!(cube, Cube::default());
+
+        let solutions = MethodSolutions::new(
+            vec![&oll_solver, &pll_solver],
+            &cube,
+            &EnumerationOptions::new().max_length(0),
+        );
+        assert_eq!(solutions.count(), 1);
+    }
+}
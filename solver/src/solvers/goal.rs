@@ -0,0 +1,373 @@
+//! # Goals made of some pieces of the cube, ignoring the others.
+//!
+//! The steps of the methods solve hardcoded subsets of the cube, such as the
+//! cross or the blocks of the F2L. A [`PieceMask`] rather tells, piece by
+//! piece, whether its position, its orientation, both or none must be
+//! solved, so that goals like "the 2x2x2 block at DBL" or "all the edges
+//! oriented" need no new subset. Masks are solved by
+//! [`MoveSetStep`](super::move_set::MoveSetStep), with the
+//! [`Target::Pieces`](super::move_set::Target::Pieces) target.
+//!
+//! Pieces are named after their home slots, such as [`cube::DBL`]. In RON
+//! files, masks list the names of the pieces to solve, to place whatever
+//! their orientation, and to orient wherever they are:
+//!
+//! ```ron
+//! (solved: ["DBL", "DB", "DL", "BL"], placed: [], oriented: ["UF", "UB"])
+//! ```
+
+use cube::{subcases::CubeSubset, Cube};
+use serde::{Deserialize, Serialize};
+
+/// What must be solved of a piece.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tracked {
+    pub position: bool,
+    pub orientation: bool,
+}
+
+impl Tracked {
+    pub const IGNORED: Self = Self {
+        position: false,
+        orientation: false,
+    };
+    /// The piece is in its home slot, whatever its orientation
+    pub const POSITION: Self = Self {
+        position: true,
+        orientation: false,
+    };
+    /// The piece is oriented, wherever it is
+    pub const ORIENTATION: Self = Self {
+        position: false,
+        orientation: true,
+    };
+    pub const SOLVED: Self = Self {
+        position: true,
+        orientation: true,
+    };
+
+    #[must_use]
+    fn union(self, other: Self) -> Self {
+        Self {
+            position: self.position || other.position,
+            orientation: self.orientation || other.orientation,
+        }
+    }
+}
+
+/// Names of the edges, by home slot.
+pub const EDGE_NAMES: [&str; 12] = [
+    "UR", "UF", "UL", "UB", "DR", "DF", "DL", "DB", "FR", "FL", "BL", "BR",
+];
+/// Names of the corners, by home slot.
+pub const CORNER_NAMES: [&str; 8] = ["URF", "UFL", "ULB", "UBR", "DFR", "DLF", "DBL", "DRB"];
+
+/// What must be solved of each piece, by home slot. All the pieces are
+/// ignored by default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "PieceNames", into = "PieceNames")]
+pub struct PieceMask {
+    pub edges: [Tracked; 12],
+    pub corners: [Tracked; 8],
+}
+
+impl PieceMask {
+    /// Mask ignoring all the pieces.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mask of the whole cube.
+    #[must_use]
+    pub fn solved() -> Self {
+        Self {
+            edges: [Tracked::SOLVED; 12],
+            corners: [Tracked::SOLVED; 8],
+        }
+    }
+
+    /// Also solve the given edges as tracked.
+    #[must_use]
+    pub fn edges(mut self, homes: &[usize], tracked: Tracked) -> Self {
+        for home in homes {
+            self.edges[*home] = self.edges[*home].union(tracked);
+        }
+        self
+    }
+
+    /// Also solve the given corners as tracked.
+    #[must_use]
+    pub fn corners(mut self, homes: &[usize], tracked: Tracked) -> Self {
+        for home in homes {
+            self.corners[*home] = self.corners[*home].union(tracked);
+        }
+        self
+    }
+
+    /// Mask solving what either mask solves.
+    #[must_use]
+    pub fn union(mut self, other: &Self) -> Self {
+        for (edge, other) in self.edges.iter_mut().zip(other.edges) {
+            *edge = edge.union(other);
+        }
+        for (corner, other) in self.corners.iter_mut().zip(other.corners) {
+            *corner = corner.union(other);
+        }
+        self
+    }
+
+    /// Whether the tracked pieces of the cube are solved.
+    #[must_use]
+    pub fn matches(&self, cube: &Cube) -> bool {
+        let edges = cube.edges.iter().enumerate().all(|(slot, edge)| {
+            let tracked = self.edges[edge.piece.clone() as usize];
+            (!tracked.position || edge.piece.clone() as usize == slot)
+                && (!tracked.orientation || edge.orientation == 0)
+        });
+        let corners = cube.corners.iter().enumerate().all(|(slot, corner)| {
+            let tracked = self.corners[corner.piece.clone() as usize];
+            (!tracked.position || corner.piece.clone() as usize == slot)
+                && (!tracked.orientation || corner.orientation == 0)
+        });
+        edges && corners
+    }
+
+    /// Cubes matching the mask, one for each key: the pieces tracked in
+    /// orientation alone may be in any of the slots left to the pieces
+    /// whose position is not tracked.
+    pub(crate) fn goal_cubes(&self) -> Vec<Cube> {
+        let solved = Cube::default();
+        let mut cubes = vec![];
+        for edges in arrangements(&self.edges) {
+            for corners in arrangements(&self.corners) {
+                let mut cube = solved.clone();
+                for (slot, piece) in edges.iter().enumerate() {
+                    cube.edges[slot] = solved.edges[*piece].clone();
+                }
+                for (slot, piece) in corners.iter().enumerate() {
+                    cube.corners[slot] = solved.corners[*piece].clone();
+                }
+                cubes.push(cube);
+            }
+        }
+        cubes
+    }
+
+    /// Mask of the pieces a subset tells apart. A piece is tracked in
+    /// position when moving it anywhere else changes the subset, and in
+    /// orientation when twisting it in place does.
+    pub(crate) fn from_subset<T: CubeSubset>() -> Self {
+        let solved = Cube::default();
+        let subset = T::from_cube(&solved);
+        let changes = |change: &dyn Fn(&mut Cube)| {
+            let mut cube = solved.clone();
+            change(&mut cube);
+            T::from_cube(&cube) != subset
+        };
+        let mut mask = Self::new();
+        for home in 0..12 {
+            mask.edges[home] = Tracked {
+                position: (0..12)
+                    .filter(|slot| *slot != home)
+                    .all(|slot| changes(&|cube| cube.edges.swap(home, slot))),
+                orientation: changes(&|cube| cube.edges[home].orientation = 1),
+            };
+        }
+        for home in 0..8 {
+            mask.corners[home] = Tracked {
+                position: (0..8)
+                    .filter(|slot| *slot != home)
+                    .all(|slot| changes(&|cube| cube.corners.swap(home, slot))),
+                orientation: changes(&|cube| cube.corners[home].orientation = 1),
+            };
+        }
+        mask
+    }
+
+    /// Contents of each slot, packed from the UR edge to the DRB corner,
+    /// then the centers. A slot holds a piece whose position is tracked,
+    /// one of the pieces whose orientation alone is tracked, or one of the
+    /// ignored pieces, along with the orientation of the tracked ones. Such
+    /// keys are the same for all the cubes the same moves lead to.
+    pub(crate) fn key(&self, cube: &Cube, centers: u8) -> u128 {
+        let mut key = 0;
+        for edge in &cube.edges {
+            let piece = edge.piece.clone() as usize;
+            key = key << 5 | pack(self.edges[piece], piece, edge.orientation, (12, 1));
+        }
+        for corner in &cube.corners {
+            let piece = corner.piece.clone() as usize;
+            key = key << 6 | pack(self.corners[piece], piece, corner.orientation, (8, 2));
+        }
+        key << 2 | u128::from(centers)
+    }
+}
+
+/// Pieces of a mask by name, as written in RON files.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct PieceNames {
+    #[serde(default)]
+    solved: Vec<String>,
+    #[serde(default)]
+    placed: Vec<String>,
+    #[serde(default)]
+    oriented: Vec<String>,
+}
+
+impl TryFrom<PieceNames> for PieceMask {
+    type Error = String;
+
+    fn try_from(names: PieceNames) -> Result<Self, Self::Error> {
+        let mut mask = Self::new();
+        for (names, tracked) in [
+            (names.solved, Tracked::SOLVED),
+            (names.placed, Tracked::POSITION),
+            (names.oriented, Tracked::ORIENTATION),
+        ] {
+            for name in names {
+                if let Some(home) = EDGE_NAMES.iter().position(|edge| *edge == name) {
+                    mask = mask.edges(&[home], tracked);
+                } else if let Some(home) = CORNER_NAMES.iter().position(|corner| *corner == name) {
+                    mask = mask.corners(&[home], tracked);
+                } else {
+                    return Err(format!("unknown piece {name}"));
+                }
+            }
+        }
+        Ok(mask)
+    }
+}
+
+impl From<PieceMask> for PieceNames {
+    fn from(mask: PieceMask) -> Self {
+        let mut names = Self::default();
+        let pieces = EDGE_NAMES
+            .iter()
+            .zip(mask.edges)
+            .chain(CORNER_NAMES.iter().zip(mask.corners));
+        for (name, tracked) in pieces {
+            let list = match (tracked.position, tracked.orientation) {
+                (true, true) => &mut names.solved,
+                (true, false) => &mut names.placed,
+                (false, true) => &mut names.oriented,
+                (false, false) => continue,
+            };
+            list.push((*name).to_string());
+        }
+        names
+    }
+}
+
+/// Piece in each slot, for each way to place the pieces tracked in
+/// orientation alone among the slots of the pieces not tracked in position.
+fn arrangements<const N: usize>(pieces: &[Tracked; N]) -> Vec<[usize; N]> {
+    let free = (0..N)
+        .filter(|piece| !pieces[*piece].position)
+        .collect::<Vec<_>>();
+    let (oriented, ignored): (Vec<usize>, Vec<usize>) =
+        free.iter().partition(|piece| pieces[**piece].orientation);
+    (0..1_u32 << free.len())
+        .filter(|chosen| chosen.count_ones() as usize == oriented.len())
+        .map(|chosen| {
+            let mut slots = std::array::from_fn(|slot| slot);
+            let (mut oriented, mut ignored) = (oriented.iter(), ignored.iter());
+            for (index, slot) in free.iter().enumerate() {
+                let piece = if chosen & (1 << index) != 0 {
+                    oriented.next()
+                } else {
+                    ignored.next()
+                };
+                slots[*slot] = *piece.unwrap();
+            }
+            slots
+        })
+        .collect()
+}
+
+/// Label of a piece among `pieces`, then its orientation on
+/// `orientation_bits` bits.
+fn pack(
+    tracked: Tracked,
+    piece: usize,
+    orientation: u8,
+    (pieces, orientation_bits): (usize, u32),
+) -> u128 {
+    let label = match (tracked.position, tracked.orientation) {
+        (true, _) => piece,
+        (false, true) => pieces,
+        (false, false) => pieces + 1,
+    };
+    let orientation = if tracked.orientation { orientation } else { 0 };
+    (label as u128) << orientation_bits | u128::from(orientation)
+}
+
+#[cfg(test)]
+mod tests {
+    use cube::algorithms::parse_algorithm;
+
+    use super::*;
+    use crate::solvers::cube_subsets::{Cross, FrontLeftBlock};
+
+    #[test]
+    fn masks_of_subsets() {
+        let cross =
+            PieceMask::new().edges(&[cube::DF, cube::DR, cube::DB, cube::DL], Tracked::SOLVED);
+        assert_eq!(PieceMask::from_subset::<Cross>(), cross);
+        assert_eq!(
+            PieceMask::from_subset::<FrontLeftBlock>(),
+            PieceMask::new()
+                .edges(&[cube::DF, cube::FL, cube::DL], Tracked::SOLVED)
+                .corners(&[cube::DLF], Tracked::SOLVED)
+        );
+    }
+
+    #[test]
+    fn match_pieces() {
+        let block = PieceMask::new()
+            .edges(&[cube::DB, cube::DL, cube::BL], Tracked::SOLVED)
+            .corners(&[cube::DBL], Tracked::SOLVED);
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&parse_algorithm("R U F"));
+        assert!(block.matches(&cube));
+        cube.execute_algorithm(&parse_algorithm("D"));
+        assert!(!block.matches(&cube));
+
+        let edges = PieceMask::new().edges(&[cube::UF, cube::UR], Tracked::ORIENTATION);
+        cube.execute_algorithm(&parse_algorithm("D' U R2"));
+        assert!(edges.matches(&cube));
+        cube.execute_algorithm(&parse_algorithm("F"));
+        assert!(!edges.matches(&cube));
+    }
+
+    #[test]
+    fn read_names() {
+        let mask: PieceMask =
+            ron::from_str(r#"(solved: ["DBL", "DB", "DL", "BL"], oriented: ["UF"])"#).unwrap();
+        assert_eq!(
+            mask,
+            PieceMask::new()
+                .edges(&[cube::DB, cube::DL, cube::BL], Tracked::SOLVED)
+                .corners(&[cube::DBL], Tracked::SOLVED)
+                .edges(&[cube::UF], Tracked::ORIENTATION)
+        );
+        assert_eq!(
+            ron::from_str::<PieceMask>(&ron::to_string(&mask).unwrap()).unwrap(),
+            mask
+        );
+        assert!(ron::from_str::<PieceMask>(r#"(solved: ["DLB"])"#).is_err());
+    }
+
+    #[test]
+    fn goal_keys() {
+        let edges = PieceMask::new()
+            .edges(&[cube::UF, cube::UR], Tracked::ORIENTATION)
+            .edges(&[cube::DF], Tracked::SOLVED);
+        let goals = edges.goal_cubes();
+        // The two oriented edges in any two of the 11 free slots
+        assert_eq!(goals.len(), 55);
+        assert!(goals.iter().all(|cube| edges.matches(cube)));
+
+        assert_eq!(PieceMask::solved().goal_cubes(), [Cube::default()]);
+    }
+}
@@ -1,80 +1,178 @@
-use crate::solvers::utils::{print_progress, print_terminated};
-use cube::{algorithms::Move, subcases::CubeSubset, Cube};
-use std::io::Write;
-
-use super::solver::Step;
-use std::collections::{HashMap, VecDeque};
-pub(super) trait IDAStepSolver: Step + Default {
-    fn get_all_moves(&self) -> &[Move];
-    fn assess_distance(&self, cube: &Cube) -> usize;
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+use crate::solvers::pattern_database::PatternDatabase;
+use cube::{algorithms::Move, Cube};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::{
+    enumeration::EnumerationOptions,
+    options::{Budget, SolveOptions},
+    progress::{NoProgress, ProgressReporter},
+    search_space::{SearchMove, SearchSpace, SearchState, StateSubset},
+    solver::{SolveError, Step},
+};
+
+/// Depth at which a parallel search splits the tree between its workers.
+const SPLIT_DEPTH: usize = 2;
+
+/// Move of the searches of an IDA* step.
+pub(super) type MoveOf<T> = <<T as IdaSearch>::State as SearchState>::Move;
+
+/// Subtree of a parallel search, waiting for a worker.
+type Subtree<T> = (Vec<MoveOf<T>>, <T as IdaSearch>::State);
+
+pub(super) trait IDAStepSolver: Step + Default + Sync {
+    /// Name of the step, also used as the kind of its cached table
+    const NAME: &'static str;
+    /// State searched by the step, [`Cube`] for steps using face moves only
+    type State: SearchState;
+
+    fn get_all_moves(&self) -> &[<Self::State as SearchState>::Move];
+    /// Lower bound (or estimate) of the number of moves left, `usize::MAX`
+    /// when the cube cannot be solved with the candidate moves.
+    fn assess_distance(&self, state: &Self::State) -> usize;
+    /// Memory used by the heuristics, in bytes.
+    fn heuristics_memory_usage(&self) -> usize;
+    /// Size of the encoded heuristics, in bytes, when known without
+    /// generating them.
+    fn heuristics_size() -> Option<u64> {
+        None
+    }
     fn populate_candidate_moves(&mut self);
-    fn populate_heuristics(&mut self);
+    fn populate_heuristics(&mut self, progress: &dyn ProgressReporter);
 
-    fn get_candidate_moves(&self, moves: &[Move], history: &[Move]) -> Vec<Move> {
+    fn generate_heuristic<T>(
+        &self,
+        name: &str,
+        progress: &dyn ProgressReporter,
+    ) -> PatternDatabase<T>
+    where
+        T: StateSubset<Self::State>,
+    {
+        PatternDatabase::generate::<Self::State>(self.get_all_moves(), name, progress)
+    }
+}
+
+/// IDA* search with the moves and heuristic of a step. Steps implement it
+/// through [`IDAStepSolver`], while the searches that are only part of a
+/// step, such as the insertion of an F2L pair, implement it directly.
+pub(super) trait IdaSearch: Sync {
+    /// Name of the step, reported when the budget runs out
+    const NAME: &'static str;
+    type State: SearchState;
+
+    fn moves(&self) -> &[MoveOf<Self>];
+    /// Lower bound (or estimate) of the number of moves left, 0 once solved
+    /// and `usize::MAX` when the state cannot be solved with the moves.
+    fn estimate(&self, state: &Self::State) -> usize;
+
+    /// Longest path the search looks for, on top of the depth limit of the
+    /// budget.
+    fn max_depth(&self) -> usize {
+        usize::MAX
+    }
+
+    fn get_candidate_moves(
+        &self,
+        moves: &[MoveOf<Self>],
+        history: &[MoveOf<Self>],
+    ) -> Vec<MoveOf<Self>> {
         let mut candidate_moves = moves.to_vec();
         if !history.is_empty() {
             let previous_move = &history[history.len() - 1];
-            let same_face_moves = previous_move.same_face_moves();
-            candidate_moves.retain(|m| !same_face_moves.contains(m));
+            candidate_moves.retain(|m| m.layer() != previous_move.layer());
         }
         if history.len() > 1 {
             let previous_move = &history[history.len() - 1];
             let previous_previous_move = &history[history.len() - 2];
-            let opposit_face_moves = previous_move.opposite_face_moves();
-            if opposit_face_moves.contains(previous_previous_move) {
-                candidate_moves.retain(|x| !opposit_face_moves.contains(x));
+            // Two moves of an axis commute, a third one is never needed
+            if previous_previous_move.axis() == previous_move.axis() {
+                candidate_moves.retain(|m| m.axis() != previous_move.axis());
             }
         }
         candidate_moves
     }
 
-    fn generate_heuristic<T>(&self, case_count: usize, name: &str) -> HashMap<T, usize>
-    where
-        T: CubeSubset,
-    {
-        let mut cases = HashMap::with_capacity(case_count);
-        let mut queue = VecDeque::with_capacity(case_count);
-        queue.push_back((Cube::default(), 0, Move::None, Move::None));
-
-        while let Some((cube, distance, last_move, last_last_move)) = queue.pop_front() {
-            let progress = cases.len();
-            print_progress!("Generating lookup table for", name, progress, case_count);
-
-            let case = CubeSubset::from_cube(&cube);
-            if cases.contains_key(&case) {
-                continue;
+    /// Shortest path solving the state, deepening the bound until a solution
+    /// is found or the budget runs out.
+    fn solve_path(
+        &self,
+        start: &Self::State,
+        budget: &Budget,
+    ) -> Result<Vec<MoveOf<Self>>, SolveError> {
+        let step = Self::NAME;
+        budget.check(step)?;
+        let mut cube = start.clone();
+        let mut bound = self.estimate(&cube);
+        let mut path = vec![];
+        if bound == usize::MAX {
+            return Err(SolveError::UnreachableCase { step });
+        }
+        let threads = budget.threads();
+        let stop = AtomicBool::new(false);
+        loop {
+            if !budget.allows_depth(bound) || bound > self.max_depth() {
+                return Err(SolveError::SearchExhausted { step });
             }
-
-            cases.insert(case, distance);
-            let moves = self.get_all_moves();
-            for move_ in self.get_candidate_moves(moves, &[last_last_move, last_move.clone()]) {
-                let mut cube = cube.clone();
-                cube.execute_move(&move_);
-                queue.push_back((cube, distance + 1, move_, last_move.clone()));
+            let t = if threads > 1 {
+                self.parallel_search(&cube, bound, &mut path, budget, threads)?
+            } else {
+                self.search(&mut cube, bound, &mut path, budget, &stop)?
+            };
+            if t == 0 {
+                return Ok(path);
             }
+            if t == usize::MAX {
+                return Err(SolveError::SearchExhausted { step });
+            }
+            bound = t;
         }
-        print_terminated!("Generating lookup table for", name, cases.len(), case_count);
-        cases
     }
 
-    fn search(&self, cube: &mut Cube, bound: usize, path: &mut Vec<Move>) -> usize {
-        let distance = self.assess_distance(cube);
+    /// Shortest solution of the cube, in face moves.
+    fn solve_cube(&self, cube: &Cube, budget: &Budget) -> Result<Vec<Move>, SolveError> {
+        let start = Self::State::from_cube(cube);
+        let path = self.solve_path(&start, budget)?;
+        Ok(start.face_moves(&path))
+    }
+
+    fn search(
+        &self,
+        cube: &mut Self::State,
+        bound: usize,
+        path: &mut Vec<MoveOf<Self>>,
+        budget: &Budget,
+        stop: &AtomicBool,
+    ) -> Result<usize, SolveError> {
+        if stop.load(Ordering::Relaxed) {
+            return Ok(usize::MAX);
+        }
+        budget.visit(Self::NAME)?;
+        let distance = self.estimate(cube);
+        if distance == usize::MAX {
+            return Ok(usize::MAX);
+        }
         let local_lower_bound = path.len() + distance;
         if local_lower_bound > bound {
-            return local_lower_bound;
+            return Ok(local_lower_bound);
         }
         if distance == 0 {
-            return 0;
+            return Ok(0);
         }
         let mut min = usize::MAX;
 
-        let moves = self.get_all_moves();
-        for alg in self.get_candidate_moves(moves, path) {
+        for alg in self.get_candidate_moves(self.moves(), path) {
             cube.execute_move(&alg);
             path.push(alg.clone());
-            let t = self.search(cube, bound, path);
+            let t = self.search(cube, bound, path, budget, stop)?;
             if t == 0 {
-                return 0;
+                return Ok(0);
             }
             if t < min {
                 min = t;
@@ -82,31 +180,565 @@ pub(super) trait IDAStepSolver: Step + Default {
             path.pop();
             cube.execute_move(&alg.inverse());
         }
-        min
+        Ok(min)
+    }
+
+    /// Same as [`IdaSearch::search`], except that the paths reaching
+    /// `SPLIT_DEPTH` are collected in `subtrees` instead of being searched.
+    fn split(
+        &self,
+        cube: &mut Self::State,
+        bound: usize,
+        path: &mut Vec<MoveOf<Self>>,
+        budget: &Budget,
+        subtrees: &mut Vec<Subtree<Self>>,
+    ) -> Result<usize, SolveError> {
+        if path.len() == SPLIT_DEPTH {
+            subtrees.push((path.clone(), cube.clone()));
+            return Ok(usize::MAX);
+        }
+        budget.visit(Self::NAME)?;
+        let distance = self.estimate(cube);
+        if distance == usize::MAX {
+            return Ok(usize::MAX);
+        }
+        let local_lower_bound = path.len() + distance;
+        if local_lower_bound > bound {
+            return Ok(local_lower_bound);
+        }
+        if distance == 0 {
+            return Ok(0);
+        }
+        let mut min = usize::MAX;
+
+        for alg in self.get_candidate_moves(self.moves(), path) {
+            cube.execute_move(&alg);
+            path.push(alg.clone());
+            let t = self.split(cube, bound, path, budget, subtrees)?;
+            if t == 0 {
+                return Ok(0);
+            }
+            min = min.min(t);
+            path.pop();
+            cube.execute_move(&alg.inverse());
+        }
+        Ok(min)
+    }
+
+    /// One iteration of [`IdaSearch::search`] from the initial state, with
+    /// the subtrees below `SPLIT_DEPTH` spread across `threads` workers. The
+    /// workers share the bound of the iteration and all stop as soon as one
+    /// of them finds a solution, which is then written to `path`.
+    fn parallel_search(
+        &self,
+        cube: &Self::State,
+        bound: usize,
+        path: &mut Vec<MoveOf<Self>>,
+        budget: &Budget,
+        threads: usize,
+    ) -> Result<usize, SolveError> {
+        let mut subtrees = vec![];
+        let min = self.split(&mut cube.clone(), bound, path, budget, &mut subtrees)?;
+        if min == 0 {
+            return Ok(0);
+        }
+
+        let next_subtree = AtomicUsize::new(0);
+        let next_bound = AtomicUsize::new(min);
+        let stop = AtomicBool::new(false);
+        // A solution takes precedence over the errors of the other workers
+        let outcome: Mutex<Option<Result<Vec<MoveOf<Self>>, SolveError>>> = Mutex::new(None);
+        thread::scope(|scope| {
+            for _ in 0..threads.min(subtrees.len()) {
+                scope.spawn(|| {
+                    while !stop.load(Ordering::Relaxed) {
+                        let index = next_subtree.fetch_add(1, Ordering::Relaxed);
+                        let Some((prefix, cube)) = subtrees.get(index) else {
+                            break;
+                        };
+                        let mut path = prefix.clone();
+                        match self.search(&mut cube.clone(), bound, &mut path, budget, &stop) {
+                            Ok(0) => {
+                                stop.store(true, Ordering::Relaxed);
+                                let mut outcome = outcome.lock().unwrap();
+                                if !matches!(*outcome, Some(Ok(_))) {
+                                    *outcome = Some(Ok(path));
+                                }
+                            }
+                            Ok(t) => {
+                                next_bound.fetch_min(t, Ordering::Relaxed);
+                            }
+                            Err(error) => {
+                                stop.store(true, Ordering::Relaxed);
+                                outcome.lock().unwrap().get_or_insert(Err(error));
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+        match outcome.into_inner().unwrap() {
+            Some(Ok(solution)) => {
+                *path = solution;
+                Ok(0)
+            }
+            Some(Err(error)) => Err(error),
+            None => Ok(next_bound.into_inner()),
+        }
+    }
+}
+
+impl<T: IDAStepSolver> IdaSearch for T {
+    const NAME: &'static str = <T as IDAStepSolver>::NAME;
+    type State = <T as IDAStepSolver>::State;
+
+    fn moves(&self) -> &[MoveOf<Self>] {
+        self.get_all_moves()
+    }
+
+    fn estimate(&self, state: &Self::State) -> usize {
+        self.assess_distance(state)
     }
 }
 
 impl<T: IDAStepSolver> Step for T {
+    const NAME: &'static str = <T as IDAStepSolver>::NAME;
+
     fn generate() -> Self {
+        Self::generate_with_progress(&NoProgress)
+    }
+    fn generate_with_progress(progress: &dyn ProgressReporter) -> Self {
         let mut solver = Self::default();
         solver.populate_candidate_moves();
-        solver.populate_heuristics();
+        solver.populate_heuristics(progress);
         solver
     }
-    fn solve(&self, cube: &Cube) -> Vec<Move> {
-        // Solve the cube using IDA* with the max of the corner and edge heuristics.
-        let mut cube = cube.clone();
-        let mut bound = self.assess_distance(&cube);
-        let mut path = vec![];
-        loop {
-            let t = self.search(&mut cube, bound, &mut path);
-            if t == 0 {
-                return path.iter().filter(|x| **x != Move::None).cloned().collect();
-            }
-            if t == usize::MAX {
-                return vec![];
+    fn solve(&self, cube: &Cube) -> Result<Vec<Move>, SolveError> {
+        self.solve_within(cube, &Budget::new(&SolveOptions::default()))
+    }
+
+    fn solve_within(&self, cube: &Cube, budget: &Budget) -> Result<Vec<Move>, SolveError> {
+        self.solve_cube(cube, budget)
+    }
+
+    fn lower_bound(&self, cube: &Cube) -> usize {
+        self.assess_distance(&T::State::from_cube(cube))
+    }
+
+    fn solutions<'a>(
+        &'a self,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Box<dyn Iterator<Item = Vec<Move>> + 'a> {
+        Box::new(IDASolutions::new(self, cube, options))
+    }
+
+    fn table_size() -> Option<u64> {
+        T::heuristics_size()
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.heuristics_memory_usage()
+    }
+}
+
+/// Lower bound (or estimate) of the number of moves left in a search space.
+pub trait Heuristic<S: SearchSpace>:
+    Clone + Default + Send + Sync + Serialize + DeserializeOwned
+{
+    /// Build the heuristic for the moves of the space, reporting the progress
+    /// of the long generations
+    fn generate(space: &S, progress: &dyn ProgressReporter) -> Self;
+    /// Number of moves left, 0 when the step is solved and `usize::MAX` when
+    /// it cannot be solved with the moves of the space
+    fn estimate(&self, state: &S::State) -> usize;
+    /// Memory used by the heuristic, in bytes
+    fn memory_usage(&self) -> usize;
+    /// Size of the encoded heuristic, in bytes, when known without
+    /// generating it
+    fn size() -> Option<u64> {
+        None
+    }
+}
+
+/// Both heuristics, estimating the larger of their distances.
+impl<S: SearchSpace, A: Heuristic<S>, B: Heuristic<S>> Heuristic<S> for (A, B) {
+    fn generate(space: &S, progress: &dyn ProgressReporter) -> Self {
+        (A::generate(space, progress), B::generate(space, progress))
+    }
+
+    fn estimate(&self, state: &S::State) -> usize {
+        self.0.estimate(state).max(self.1.estimate(state))
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.0.memory_usage() + self.1.memory_usage()
+    }
+
+    fn size() -> Option<u64> {
+        Some(A::size()? + B::size()?)
+    }
+}
+
+/// IDA* step searching the space `S` with the heuristic `H`, which
+/// implements [`Step`] for steps defined outside of this crate.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct IdaStep<S, H> {
+    space: S,
+    heuristic: H,
+}
+
+impl<S: SearchSpace, H: Heuristic<S>> IdaStep<S, H> {
+    /// Generate the heuristic of the step for the given space.
+    #[must_use]
+    pub fn new(space: S, progress: &dyn ProgressReporter) -> Self {
+        let heuristic = H::generate(&space, progress);
+        Self { space, heuristic }
+    }
+
+    #[must_use]
+    pub fn space(&self) -> &S {
+        &self.space
+    }
+
+    #[must_use]
+    pub fn heuristic(&self) -> &H {
+        &self.heuristic
+    }
+}
+
+impl<S: SearchSpace, H: Heuristic<S>> IDAStepSolver for IdaStep<S, H> {
+    const NAME: &'static str = S::NAME;
+    type State = S::State;
+
+    fn get_all_moves(&self) -> &[MoveOf<Self>] {
+        self.space.moves()
+    }
+
+    fn assess_distance(&self, state: &S::State) -> usize {
+        self.heuristic.estimate(state)
+    }
+
+    fn heuristics_memory_usage(&self) -> usize {
+        self.heuristic.memory_usage()
+    }
+
+    fn heuristics_size() -> Option<u64> {
+        H::size()
+    }
+
+    fn populate_candidate_moves(&mut self) {}
+
+    fn populate_heuristics(&mut self, progress: &dyn ProgressReporter) {
+        self.heuristic = H::generate(&self.space, progress);
+    }
+}
+
+/// Whether `move_` may follow `previous` when sequences that only differ by
+/// the order of two consecutive moves on opposite faces are deduplicated.
+fn is_canonical_after<M: SearchMove>(previous: &M, move_: &M) -> bool {
+    previous.axis() != move_.axis() || previous.layer() < move_.layer()
+}
+
+/// Solutions of an IDA* step, iteration by iteration.
+///
+/// The search goes through the same trees as [`IdaSearch::search`], with
+/// an explicit stack so that it can be suspended after each solution. Each
+/// iteration only yields the solutions that were pruned by the previous one,
+/// i.e. whose path has an estimate above the previous bound. With an
+/// admissible heuristic these are exactly as long as the bound, so solutions
+/// come in non-decreasing length; with an overestimating one, an iteration
+/// may still yield solutions shorter than the previous ones. Paths stop at
+/// the first solved state, so a solution is never the prefix of another one.
+struct IDASolutions<'a, S: IdaSearch> {
+    solver: &'a S,
+    /// State at the start of the step, which solutions are converted from
+    start: S::State,
+    cube: S::State,
+    path: Vec<MoveOf<S>>,
+    /// Moves left to try after each prefix of the path
+    stack: Vec<Vec<MoveOf<S>>>,
+    /// Largest estimate along each prefix of the path
+    estimates: Vec<usize>,
+    in_iteration: bool,
+    bound: usize,
+    /// Bound of the previous iteration, if any
+    previous_bound: Option<usize>,
+    /// Smallest estimate that exceeded the bound in the current iteration
+    next_bound: usize,
+    options: EnumerationOptions,
+    found: usize,
+    optimal_length: Option<usize>,
+}
+
+impl<'a, S: IdaSearch> IDASolutions<'a, S> {
+    fn new(solver: &'a S, cube: &Cube, options: &EnumerationOptions) -> Self {
+        let start = S::State::from_cube(cube);
+        Self {
+            solver,
+            cube: start.clone(),
+            bound: solver.estimate(&start),
+            start,
+            path: vec![],
+            stack: vec![],
+            estimates: vec![],
+            in_iteration: false,
+            previous_bound: None,
+            next_bound: usize::MAX,
+            options: options.clone(),
+            found: 0,
+            optimal_length: None,
+        }
+    }
+
+    fn candidate_moves(&self) -> Vec<MoveOf<S>> {
+        let mut moves = self
+            .solver
+            .get_candidate_moves(self.solver.moves(), &self.path);
+        if let (true, Some(previous)) = (self.options.deduplicate, self.path.last()) {
+            moves.retain(|m| is_canonical_after(previous, m));
+        }
+        // Moves are popped from the end of the list
+        moves.reverse();
+        moves
+    }
+
+    /// Visit the end of the path: return it if it is a new solution, or
+    /// push the moves to try after it if it may lead to one within the bound.
+    fn visit(&mut self) -> Option<Vec<Move>> {
+        let distance = self.solver.estimate(&self.cube);
+        if distance == usize::MAX {
+            return None;
+        }
+        let estimate = self.path.len() + distance;
+        if estimate > self.bound {
+            self.next_bound = self.next_bound.min(estimate);
+            return None;
+        }
+        let path_estimate = self.estimates.last().map_or(estimate, |e| estimate.max(*e));
+        if distance > 0 {
+            self.stack.push(self.candidate_moves());
+            self.estimates.push(path_estimate);
+            return None;
+        }
+        let length = self.path.len();
+        let is_new = self.previous_bound.is_none_or(|b| path_estimate > b);
+        let is_wanted =
+            self.options.allows_length(length) && self.optimal_length.is_none_or(|l| length <= l);
+        (is_new && is_wanted).then(|| self.start.face_moves(&self.path))
+    }
+
+    fn undo(&mut self) {
+        if let Some(move_) = self.path.pop() {
+            self.cube.execute_move(&move_.inverse());
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.bound == usize::MAX || self.options.max_count.is_some_and(|max| self.found >= max)
+    }
+}
+
+impl<S: IdaSearch> Iterator for IDASolutions<'_, S> {
+    type Item = Vec<Move>;
+
+    fn next(&mut self) -> Option<Vec<Move>> {
+        while !self.is_done() {
+            let solution = if !self.in_iteration {
+                // Start an iteration from the initial state
+                if let Some(previous_bound) = self.previous_bound {
+                    if !self.options.allows_length(previous_bound + 1) {
+                        break;
+                    }
+                }
+                self.in_iteration = true;
+                self.next_bound = usize::MAX;
+                self.visit()
+            } else if let Some(moves) = self.stack.last_mut() {
+                if let Some(move_) = moves.pop() {
+                    self.cube.execute_move(&move_);
+                    self.path.push(move_);
+                    let solution = self.visit();
+                    // Keep the move only if the search goes on after it
+                    if self.stack.len() == self.path.len() {
+                        self.undo();
+                    }
+                    solution
+                } else {
+                    self.stack.pop();
+                    self.estimates.pop();
+                    self.undo();
+                    None
+                }
+            } else {
+                // The iteration is over, start the next one with a larger bound
+                self.in_iteration = false;
+                if self.optimal_length.is_some() {
+                    break;
+                }
+                self.previous_bound = Some(self.bound);
+                self.bound = self.next_bound;
+                None
+            };
+
+            if let Some(solution) = solution {
+                self.found += 1;
+                if self.options.optimal_only {
+                    self.optimal_length = Some(solution.len());
+                }
+                return Some(solution);
             }
-            bound = t;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solvers::cube_subsets::{CornerPermutation, Cross};
+    use cube::algorithms::parse_algorithm;
+    use serde::{Deserialize, Serialize};
+
+    /// Step solving the permutation of the corners, small enough to be
+    /// generated in tests.
+    #[derive(Serialize, Deserialize, Default, Clone)]
+    struct CornerPermutationSolver {
+        candidate_moves: Vec<Move>,
+        corner_permutation: PatternDatabase<CornerPermutation>,
+    }
+
+    impl IDAStepSolver for CornerPermutationSolver {
+        const NAME: &'static str = "corner_permutation";
+        type State = Cube;
+
+        fn get_all_moves(&self) -> &[Move] {
+            &self.candidate_moves
+        }
+
+        fn assess_distance(&self, cube: &Cube) -> usize {
+            self.corner_permutation.distance(cube)
+        }
+
+        fn heuristics_memory_usage(&self) -> usize {
+            self.corner_permutation.memory_usage()
+        }
+
+        fn populate_candidate_moves(&mut self) {
+            self.candidate_moves = cube::algorithms::ALL_MOVES.to_vec();
+        }
+
+        fn populate_heuristics(&mut self, progress: &dyn ProgressReporter) {
+            self.corner_permutation = self.generate_heuristic("CP", progress);
+        }
+    }
+
+    fn scrambled_cube() -> Cube {
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&parse_algorithm("R U2 D' B"));
+        cube
+    }
+
+    #[test]
+    fn enumerate_in_non_decreasing_length() {
+        let solver = CornerPermutationSolver::generate();
+        let cube = scrambled_cube();
+        let optimal_length = solver.solve(&cube).unwrap().len();
+
+        let solutions = solver
+            .solutions(
+                &cube,
+                &EnumerationOptions::new().max_length(optimal_length + 1),
+            )
+            .collect::<Vec<_>>();
+        assert_eq!(solutions[0].len(), optimal_length);
+        assert!(solutions.windows(2).all(|w| w[0].len() <= w[1].len()));
+        for solution in &solutions {
+            let mut cube = cube.clone();
+            cube.execute_algorithm(solution);
+            assert_eq!(solver.assess_distance(&cube), 0);
+        }
+
+        let optimal = solver
+            .solutions(&cube, &EnumerationOptions::new().optimal_only(true))
+            .collect::<Vec<_>>();
+        assert!(optimal.iter().all(|s| s.len() == optimal_length));
+        assert_eq!(
+            optimal.len(),
+            solutions
+                .iter()
+                .filter(|s| s.len() == optimal_length)
+                .count()
+        );
+
+        let first = solver
+            .solutions(&cube, &EnumerationOptions::new().max_count(3))
+            .collect::<Vec<_>>();
+        assert_eq!(first, solutions[..3]);
+    }
+
+    #[test]
+    fn parallel_search_finds_optimal_length() {
+        let solver = CornerPermutationSolver::generate();
+        let options = SolveOptions::new().threads(4);
+        for scramble in ["R U2 D' B", "F R' U L2 D B2", "U", "L D2 R' F2 U' B L'"] {
+            let mut cube = Cube::default();
+            cube.execute_algorithm(&parse_algorithm(scramble));
+            let serial = solver.solve(&cube).unwrap();
+            let parallel = solver.solve_within(&cube, &Budget::new(&options)).unwrap();
+            assert_eq!(parallel.len(), serial.len());
+            cube.execute_algorithm(&parallel);
+            assert_eq!(solver.assess_distance(&cube), 0);
+        }
+    }
+
+    #[test]
+    fn deduplicate_opposite_moves() {
+        let solver = CornerPermutationSolver::generate();
+        let cube = scrambled_cube();
+        let options = EnumerationOptions::new().optimal_only(true);
+        let all = solver.solutions(&cube, &options).count();
+        let deduplicated = solver
+            .solutions(&cube, &options.deduplicate(true))
+            .collect::<Vec<_>>();
+        assert!(deduplicated.len() < all);
+        assert!(deduplicated
+            .iter()
+            .all(|s| s.windows(2).all(|w| is_canonical_after(&w[0], &w[1]))));
+    }
+
+    /// Space of all the face moves, as a step defined outside of the crate
+    /// would declare it.
+    #[derive(Serialize, Deserialize, Clone)]
+    struct FaceMoves(Vec<Move>);
+
+    impl Default for FaceMoves {
+        fn default() -> Self {
+            Self(cube::algorithms::ALL_MOVES.to_vec())
+        }
+    }
+
+    impl SearchSpace for FaceMoves {
+        const NAME: &'static str = "face_moves";
+        type State = Cube;
+
+        fn moves(&self) -> &[Move] {
+            &self.0
+        }
+    }
+
+    #[test]
+    fn custom_steps_solve_optimally() {
+        let step = IdaStep::<FaceMoves, PatternDatabase<Cross>>::generate();
+        let table = crate::solvers::steps::cross::Solver::generate();
+        for scramble in ["R F B'", "D2 L' U F2 R B' D", "F2 U' R2 B L2 D' F' R"] {
+            let mut cube = Cube::default();
+            cube.execute_algorithm(&parse_algorithm(scramble));
+            let solution = step.solve(&cube).unwrap();
+            assert_eq!(solution.len(), table.solve(&cube).unwrap().len());
+            cube.execute_algorithm(&solution);
+            assert_eq!(step.lower_bound(&cube), 0);
         }
     }
 }
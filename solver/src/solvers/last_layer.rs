@@ -0,0 +1,629 @@
+//! # Generate and audit the algorithms of the last layer steps.
+//!
+//! The OLL and PLL steps look their algorithms up in hardcoded lists, and the
+//! F2L pair by pair may insert its pairs with a list of F2L algorithms. This
+//! module finds the shortest algorithm of every case with a given set of
+//! moves, and checks a list of algorithms against the cases: each algorithm
+//! must keep the pieces of its step solved, solve a case that no other
+//! algorithm solves, and all the cases must be solved.
+//!
+//! Algorithms are found with the IDA* engine of the steps, whose heuristic
+//! is a table of the states around the solved state, filled with a
+//! breadth-first search. States out of the table are at least one move
+//! further than its depth.
+
+use std::{collections::HashMap, fmt::Display};
+
+use cube::{
+    algorithms::{invert_algorithm, parse_algorithm, Move},
+    subcases::{place_corner, place_edge, CubeSubset},
+    Cube,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    cube_subsets::{Oll, Pll},
+    ida_solver::IdaSearch,
+    options::{Budget, SolveOptions},
+    solver::SolveError,
+    steps::{f2l_pairs, oll, pll},
+};
+
+const AUFS: [Move; 4] = [Move::None, Move::U, Move::U2, Move::Up];
+
+/// Step of the last layer, or last pair of the F2L, whose algorithms are
+/// generated or audited.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LastLayerStep {
+    /// Insert the front right pair, keeping the cross and the other slots
+    /// solved
+    F2l,
+    /// Orient the last layer, keeping the first two layers solved
+    Oll,
+    /// Permute the last layer, keeping the rest of the cube solved
+    Pll,
+}
+
+impl Display for LastLayerStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LastLayerStep::F2l => "F2L",
+            LastLayerStep::Oll => "OLL",
+            LastLayerStep::Pll => "PLL",
+        })
+    }
+}
+
+impl LastLayerStep {
+    /// One cube of each case, up to the moves of the U face before and after
+    /// the algorithm, sorted by case.
+    #[must_use]
+    pub fn cases(self) -> Vec<Cube> {
+        let cubes: Vec<Cube> = match self {
+            LastLayerStep::F2l => front_right_pairs(),
+            LastLayerStep::Oll => (0..<Oll as CubeSubset>::CASES)
+                .map(Oll::cube_from_index)
+                .filter(|cube| cube.validate().is_ok())
+                .collect(),
+            LastLayerStep::Pll => last_layer_permutations(),
+        };
+        let mut cases = HashMap::new();
+        for cube in cubes {
+            cases.entry(self.case(&cube)).or_insert(cube);
+        }
+        let mut cases = cases.into_iter().collect::<Vec<_>>();
+        cases.sort_by_key(|(case, _)| *case);
+        cases.into_iter().map(|(_, cube)| cube).collect()
+    }
+
+    /// Smallest index of the cubes of the case of the cube.
+    pub(crate) fn case(self, cube: &Cube) -> usize {
+        let mut case = usize::MAX;
+        for post_auf in &AUFS {
+            let mut cube = cube.clone();
+            cube.execute_move(post_auf);
+            case = case.min(match self {
+                LastLayerStep::F2l => front_right_pair_index(&cube),
+                // The orientations do not depend on the moves before
+                LastLayerStep::Oll => CubeSubset::index(&Oll::from_cube(&cube)),
+                LastLayerStep::Pll => AUFS
+                    .iter()
+                    .map(|pre_auf| CubeSubset::index(&Pll::from_cube(&after(pre_auf, &cube))))
+                    .min()
+                    .unwrap(),
+            });
+        }
+        case
+    }
+
+    /// Whether the cube only differs from the solved cube by pieces the step
+    /// solves.
+    pub(crate) fn is_case(self, cube: &Cube) -> bool {
+        let solved = Cube::default();
+        let mut kept_edges = cube.edges.iter().zip(&solved.edges).enumerate();
+        let mut kept_corners = cube.corners.iter().zip(&solved.corners).enumerate();
+        match self {
+            LastLayerStep::F2l => {
+                kept_edges
+                    .filter(|(slot, _)| !is_last_layer(*slot) && *slot != cube::FR)
+                    .all(|(_, (edge, home))| edge == home)
+                    && kept_corners
+                        .filter(|(slot, _)| !is_last_layer(*slot) && *slot != cube::DFR)
+                        .all(|(_, (corner, home))| corner == home)
+            }
+            LastLayerStep::Oll => {
+                kept_edges
+                    .filter(|(slot, _)| !is_last_layer(*slot))
+                    .all(|(_, (edge, home))| edge == home)
+                    && kept_corners
+                        .filter(|(slot, _)| !is_last_layer(*slot))
+                        .all(|(_, (corner, home))| corner == home)
+            }
+            LastLayerStep::Pll => {
+                kept_edges.all(|(slot, (edge, home))| {
+                    edge.orientation == 0 && (is_last_layer(slot) || edge == home)
+                }) && kept_corners.all(|(slot, (corner, home))| {
+                    corner.orientation == 0 && (is_last_layer(slot) || corner == home)
+                })
+            }
+        }
+    }
+
+    /// Algorithms of the hardcoded list of the step, as solutions of their
+    /// case.
+    #[must_use]
+    pub fn hardcoded_algorithms(self) -> Vec<Vec<Move>> {
+        match self {
+            LastLayerStep::F2l => f2l_pairs::F2L_ALGS
+                .iter()
+                .map(|alg| parse_algorithm(alg))
+                .collect(),
+            LastLayerStep::Oll => oll::OLL_ALGS
+                .iter()
+                .map(|alg| parse_algorithm(alg))
+                .collect(),
+            // The PLL list gives the algorithms leading to the cases
+            LastLayerStep::Pll => pll::PLL_ALGS
+                .iter()
+                .map(|alg| invert_algorithm(&parse_algorithm(alg)))
+                .collect(),
+        }
+    }
+
+    /// Key of the cube in the search. The pieces of the last layer are not
+    /// told apart when orienting them, and are ignored when inserting a pair.
+    fn key(self, cube: &Cube) -> u128 {
+        let mut key = 0;
+        for edge in &cube.edges {
+            let (piece, orientation) = match self {
+                LastLayerStep::F2l if is_last_layer(edge.piece.clone() as usize) => (0xF, 0),
+                LastLayerStep::Oll if is_last_layer(edge.piece.clone() as usize) => {
+                    (0xF, edge.orientation)
+                }
+                _ => (edge.piece.clone() as u128, edge.orientation),
+            };
+            key = key << 5 | piece << 1 | u128::from(orientation);
+        }
+        for corner in &cube.corners {
+            let (piece, orientation) = match self {
+                LastLayerStep::F2l if is_last_layer(corner.piece.clone() as usize) => (0xF, 0),
+                LastLayerStep::Oll if is_last_layer(corner.piece.clone() as usize) => {
+                    (0xF, corner.orientation)
+                }
+                _ => (corner.piece.clone() as u128, corner.orientation),
+            };
+            key = key << 6 | piece << 2 | u128::from(orientation);
+        }
+        key
+    }
+
+    /// States that end the step: the solved cube, up to the moves of the U
+    /// face after the algorithm.
+    fn goals(self) -> Vec<Cube> {
+        let auf_count = match self {
+            LastLayerStep::F2l | LastLayerStep::Oll => 1,
+            LastLayerStep::Pll => AUFS.len(),
+        };
+        AUFS[..auf_count]
+            .iter()
+            .map(|auf| {
+                let mut cube = Cube::default();
+                cube.execute_move(auf);
+                cube
+            })
+            .collect()
+    }
+}
+
+/// Slots and pieces of the last layer come first.
+fn is_last_layer(slot: usize) -> bool {
+    slot < 4
+}
+
+/// All the cubes whose front right pair is in the U layer or in its slot,
+/// with the rest of the first two layers solved. The other pieces of the U
+/// layer make up for the twist, flip and parity of the pair.
+fn front_right_pairs() -> Vec<Cube> {
+    let mut cubes = vec![];
+    for corner_slot in [cube::DFR, cube::URF, cube::UFL, cube::ULB, cube::UBR] {
+        for edge_slot in [cube::FR, cube::UR, cube::UF, cube::UL, cube::UB] {
+            for corner_orientation in 0..3 {
+                for edge_orientation in 0..2 {
+                    let mut cube = Cube::default();
+                    place_corner(&mut cube, cube::DFR, corner_slot, corner_orientation);
+                    place_edge(&mut cube, cube::FR, edge_slot, edge_orientation);
+                    let free_corner = (0..4).find(|slot| *slot != corner_slot).unwrap();
+                    cube.corners[free_corner].orientation = (3 - corner_orientation) % 3;
+                    let mut free_edges = (0..4).filter(|slot| *slot != edge_slot);
+                    let (first, second) = (free_edges.next().unwrap(), free_edges.next().unwrap());
+                    cube.edges[first].orientation = edge_orientation;
+                    if cube.validate().is_err() {
+                        cube.edges.swap(first, second);
+                    }
+                    cubes.push(cube);
+                }
+            }
+        }
+    }
+    cubes
+}
+
+/// Index of the slots and orientations of the front right pair. Slots are
+/// counted from the ones of the pair, so that the solved case comes first.
+fn front_right_pair_index(cube: &Cube) -> usize {
+    let corner = cube
+        .corners
+        .iter()
+        .position(|corner| corner.piece.clone() as usize == cube::DFR)
+        .unwrap();
+    let edge = cube
+        .edges
+        .iter()
+        .position(|edge| edge.piece.clone() as usize == cube::FR)
+        .unwrap();
+    let corner_rank = (corner + 4) % 8 * 3 + usize::from(cube.corners[corner].orientation);
+    let edge_rank = (edge + 4) % 12 * 2 + usize::from(cube.edges[edge].orientation);
+    corner_rank * 24 + edge_rank
+}
+
+/// All the cubes whose last layer is oriented but not permuted.
+fn last_layer_permutations() -> Vec<Cube> {
+    let solved = Cube::default();
+    let permutations = (0..24).map(|rank| {
+        let mut positions = [0; 4];
+        cube::subcases::unrank_positions(rank, 4, &mut positions);
+        positions
+    });
+    let mut cubes = vec![];
+    for edges in permutations.clone() {
+        for corners in permutations.clone() {
+            let mut cube = Cube::default();
+            for (slot, piece) in edges.iter().enumerate() {
+                cube.edges[slot] = solved.edges[usize::from(*piece)].clone();
+            }
+            for (slot, piece) in corners.iter().enumerate() {
+                cube.corners[slot] = solved.corners[usize::from(*piece)].clone();
+            }
+            if cube.validate().is_ok() {
+                cubes.push(cube);
+            }
+        }
+    }
+    cubes
+}
+
+/// The cube reached by the moves of the given cube, applied after the move.
+fn after(move_: &Move, cube: &Cube) -> Cube {
+    let mut moved = Cube::default();
+    moved.execute_move(move_);
+    let mut result = cube.clone();
+    for edge in &mut result.edges {
+        let home = &moved.edges[edge.piece.clone() as usize];
+        edge.piece = home.piece.clone();
+        edge.orientation = (edge.orientation + home.orientation) % 2;
+    }
+    for corner in &mut result.corners {
+        let home = &moved.corners[corner.piece.clone() as usize];
+        corner.piece = home.piece.clone();
+        corner.orientation = (corner.orientation + home.orientation) % 3;
+    }
+    result
+}
+
+/// Shortest algorithm found for a case.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GeneratedAlgorithm {
+    /// Cube of the case the algorithm solves, after the moves of the U face
+    /// that shorten the algorithm the most
+    pub case: Cube,
+    /// `None` when no algorithm of the maximum length solves the case with
+    /// the moves of the search
+    pub algorithm: Option<Vec<Move>>,
+}
+
+/// Search of the shortest algorithms of the cases of a step, with a given
+/// set of moves.
+#[derive(Clone, Debug)]
+pub struct AlgorithmSearch {
+    moves: Vec<Move>,
+    table_depth: usize,
+    max_length: usize,
+}
+
+impl AlgorithmSearch {
+    /// Search with the given moves, such as `<R, U, F>` or `<R, U, D>`.
+    #[must_use]
+    pub fn new(moves: &[Move]) -> Self {
+        Self {
+            moves: moves.to_vec(),
+            table_depth: 6,
+            max_length: 16,
+        }
+    }
+
+    /// Depth of the table of the states around the solved state. Deeper
+    /// tables speed the searches up, but grow exponentially.
+    #[must_use]
+    pub fn table_depth(mut self, table_depth: usize) -> Self {
+        self.table_depth = table_depth;
+        self
+    }
+
+    /// Give up on the cases that need longer algorithms.
+    #[must_use]
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Shortest algorithm of each case of the step, sorted as
+    /// [`LastLayerStep::cases`].
+    #[must_use]
+    pub fn generate(&self, step: LastLayerStep) -> Vec<GeneratedAlgorithm> {
+        self.generate_within(step, &Budget::new(&SolveOptions::new()))
+            .expect("Searches without limits run to completion")
+    }
+
+    /// Same as [`AlgorithmSearch::generate`], giving up once the budget
+    /// runs out.
+    pub fn generate_within(
+        &self,
+        step: LastLayerStep,
+        budget: &Budget,
+    ) -> Result<Vec<GeneratedAlgorithm>, SolveError> {
+        let table = self.table(step);
+        step.cases()
+            .into_iter()
+            .map(|case| {
+                Ok(match self.shortest(step, &table, &case, budget)? {
+                    Some((case, algorithm)) => GeneratedAlgorithm {
+                        case,
+                        algorithm: Some(algorithm),
+                    },
+                    None => GeneratedAlgorithm {
+                        case,
+                        algorithm: None,
+                    },
+                })
+            })
+            .collect()
+    }
+
+    /// Distances of the states around the goals of the step, with a
+    /// breadth-first search from the goals.
+    fn table(&self, step: LastLayerStep) -> HashMap<u128, u8> {
+        let mut frontier = step.goals();
+        let mut table = frontier
+            .iter()
+            .map(|cube| (step.key(cube), 0))
+            .collect::<HashMap<_, _>>();
+        for depth in 1..=self.table_depth {
+            let mut next = vec![];
+            for cube in &frontier {
+                for move_ in &self.moves {
+                    let mut cube = cube.clone();
+                    cube.execute_move(move_);
+                    if let std::collections::hash_map::Entry::Vacant(entry) =
+                        table.entry(step.key(&cube))
+                    {
+                        entry.insert(u8::try_from(depth).unwrap());
+                        next.push(cube);
+                    }
+                }
+            }
+            frontier = next;
+        }
+        table
+    }
+
+    /// Shortest algorithm solving the case after one of the moves of the U
+    /// face, with the cube it solves. Each search only looks for algorithms
+    /// shorter than the best one so far.
+    fn shortest(
+        &self,
+        step: LastLayerStep,
+        table: &HashMap<u128, u8>,
+        case: &Cube,
+        budget: &Budget,
+    ) -> Result<Option<(Cube, Vec<Move>)>, SolveError> {
+        let mut search = CaseSearch {
+            moves: &self.moves,
+            step,
+            table,
+            table_depth: self.table_depth,
+            max_length: self.max_length,
+        };
+        let mut best = None;
+        for pre_auf in &AUFS {
+            let mut cube = case.clone();
+            cube.execute_move(pre_auf);
+            match search.solve_path(&cube, budget) {
+                Ok(algorithm) => {
+                    let Some(max_length) = algorithm.len().checked_sub(1) else {
+                        return Ok(Some((cube, algorithm)));
+                    };
+                    search.max_length = max_length;
+                    best = Some((cube, algorithm));
+                }
+                Err(SolveError::SearchExhausted { .. }) => {}
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(best)
+    }
+}
+
+/// Search of the algorithms of one case, with the moves and table of an
+/// [`AlgorithmSearch`].
+struct CaseSearch<'a> {
+    moves: &'a [Move],
+    step: LastLayerStep,
+    table: &'a HashMap<u128, u8>,
+    table_depth: usize,
+    max_length: usize,
+}
+
+impl IdaSearch for CaseSearch<'_> {
+    const NAME: &'static str = "last_layer_algorithms";
+    type State = Cube;
+
+    fn moves(&self) -> &[Move] {
+        self.moves
+    }
+
+    fn estimate(&self, cube: &Cube) -> usize {
+        self.table
+            .get(&self.step.key(cube))
+            .map_or(self.table_depth + 1, |d| usize::from(*d))
+    }
+
+    fn max_depth(&self) -> usize {
+        self.max_length
+    }
+}
+
+/// Problem found in a list of algorithms.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AlgorithmIssue {
+    /// The algorithm does not keep the pieces the step does not solve
+    BreaksSolvedPieces { index: usize },
+    /// The algorithm solves the same case as an earlier one
+    DuplicateCase { index: usize, first: usize },
+    /// No algorithm solves the case
+    MissingCase { case: Cube },
+    /// The algorithm is longer than the one generated for its case
+    LongerThanGenerated {
+        index: usize,
+        length: usize,
+        generated: usize,
+    },
+}
+
+/// Check the algorithms of a step against its cases, and against the
+/// generated algorithms when given.
+#[must_use]
+pub fn audit(
+    step: LastLayerStep,
+    algorithms: &[Vec<Move>],
+    generated: &[GeneratedAlgorithm],
+) -> Vec<AlgorithmIssue> {
+    let mut issues = vec![];
+    let mut solved_cases = HashMap::new();
+    for (index, algorithm) in algorithms.iter().enumerate() {
+        let mut case = Cube::default();
+        case.execute_algorithm(&invert_algorithm(algorithm));
+        if !step.is_case(&case) {
+            issues.push(AlgorithmIssue::BreaksSolvedPieces { index });
+            continue;
+        }
+        let case = step.case(&case);
+        if let Some(first) = solved_cases.get(&case) {
+            issues.push(AlgorithmIssue::DuplicateCase {
+                index,
+                first: *first,
+            });
+            continue;
+        }
+        solved_cases.insert(case, index);
+    }
+
+    for cube in step.cases() {
+        if !solved_cases.contains_key(&step.case(&cube)) {
+            issues.push(AlgorithmIssue::MissingCase { case: cube });
+        }
+    }
+
+    for generated in generated {
+        let Some(generated_algorithm) = &generated.algorithm else {
+            continue;
+        };
+        let Some(index) = solved_cases.get(&step.case(&generated.case)) else {
+            continue;
+        };
+        if algorithms[*index].len() > generated_algorithm.len() {
+            issues.push(AlgorithmIssue::LongerThanGenerated {
+                index: *index,
+                length: algorithms[*index].len(),
+                generated: generated_algorithm.len(),
+            });
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_cases() {
+        assert_eq!(LastLayerStep::F2l.cases().len(), 42);
+        assert_eq!(LastLayerStep::Oll.cases().len(), 58);
+        assert_eq!(LastLayerStep::Pll.cases().len(), 22);
+    }
+
+    #[test]
+    fn hardcoded_algorithms_solve_every_case() {
+        for step in [LastLayerStep::F2l, LastLayerStep::Oll, LastLayerStep::Pll] {
+            assert_eq!(audit(step, &step.hardcoded_algorithms(), &[]), vec![]);
+        }
+    }
+
+    #[test]
+    fn detect_wrong_algorithms() {
+        let mut algorithms = LastLayerStep::Pll.hardcoded_algorithms();
+        // A T perm breaking the F2L, and a second T perm instead of a J perm
+        algorithms[3] = parse_algorithm("R U R' U' R' F R2 U' R' U' R U R'");
+        algorithms[15] = algorithms[5].clone();
+        let issues = audit(LastLayerStep::Pll, &algorithms, &[]);
+        assert_eq!(issues.len(), 4);
+        assert_eq!(issues[0], AlgorithmIssue::BreaksSolvedPieces { index: 3 });
+        assert_eq!(
+            issues[1],
+            AlgorithmIssue::DuplicateCase {
+                index: 15,
+                first: 5
+            }
+        );
+        assert!(matches!(issues[2], AlgorithmIssue::MissingCase { .. }));
+        assert!(matches!(issues[3], AlgorithmIssue::MissingCase { .. }));
+    }
+
+    #[test]
+    fn generate_two_generator_algorithms() {
+        let moves = [Move::U, Move::U2, Move::Up, Move::R, Move::R2, Move::Rp];
+        let generated = AlgorithmSearch::new(&moves)
+            .max_length(7)
+            .generate(LastLayerStep::Oll);
+
+        // The solved case and both Sunes, nothing that flips edges
+        let found = generated
+            .iter()
+            .filter_map(|generated| Some((&generated.case, generated.algorithm.as_ref()?)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found.iter().map(|(_, alg)| alg.len()).collect::<Vec<_>>(),
+            vec![0, 7, 7]
+        );
+        for (case, algorithm) in &found {
+            let mut cube = (*case).clone();
+            cube.execute_algorithm(algorithm);
+            assert_eq!(CubeSubset::index(&Oll::from_cube(&cube)), 0);
+            assert!(LastLayerStep::Oll.is_case(&cube));
+        }
+
+        // The hardcoded Sunes are as short as the generated ones, but the
+        // list is still checked against the generated algorithms
+        let mut algorithms = LastLayerStep::Oll.hardcoded_algorithms();
+        let sune = LastLayerStep::Oll.case(found[1].0);
+        let index = algorithms
+            .iter()
+            .position(|algorithm| {
+                let mut cube = Cube::default();
+                cube.execute_algorithm(&invert_algorithm(algorithm));
+                LastLayerStep::Oll.case(&cube) == sune
+            })
+            .unwrap();
+        algorithms[index].extend([Move::U, Move::Up]);
+        let issues = audit(LastLayerStep::Oll, &algorithms, &generated);
+        assert_eq!(
+            issues,
+            vec![AlgorithmIssue::LongerThanGenerated {
+                index,
+                length: algorithms[index].len(),
+                generated: 7,
+            }]
+        );
+    }
+
+    #[test]
+    fn generation_within_budget() {
+        let moves = [Move::U, Move::U2, Move::Up, Move::R, Move::R2, Move::Rp];
+        let options = SolveOptions::new().max_nodes(1000);
+        let generated = AlgorithmSearch::new(&moves)
+            .max_length(7)
+            .generate_within(LastLayerStep::Oll, &Budget::new(&options));
+        assert!(matches!(generated, Err(SolveError::Timeout { .. })));
+    }
+}
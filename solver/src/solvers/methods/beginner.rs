@@ -0,0 +1,460 @@
+//! # Layer by layer method, as taught to beginners.
+//!
+//! Each stage places its pieces one at a time with a short breadth-first
+//! search over a small set of named triggers: the sexy move for the first
+//! layer corners, the insertions for the second layer edges, then one
+//! algorithm per stage of the last layer. The solutions are much longer than
+//! CFOP ones, but each stage can be followed by hand.
+
+use std::collections::{HashSet, VecDeque};
+
+use crate::solvers::{
+    enumeration::{EnumerationOptions, MethodSolutions, StepSolutions},
+    options::{Budget, SolveOptions},
+    solver::{Method, Solution, SolveError, Stage},
+};
+use cube::{
+    algorithms::{parse_algorithm, Move, ALL_MOVES},
+    Cube,
+};
+
+/// Short algorithm a beginner knows by name.
+struct Trigger {
+    name: String,
+    moves: Vec<Move>,
+}
+
+impl Trigger {
+    fn new(name: impl Into<String>, moves: &str) -> Self {
+        Self {
+            name: name.into(),
+            moves: parse_algorithm(moves),
+        }
+    }
+
+    fn is_auf(&self) -> bool {
+        matches!(self.moves[..], [Move::U | Move::U2 | Move::Up])
+    }
+}
+
+/// Piece followed by a stage, identified by its home slot.
+#[derive(Clone, Copy)]
+enum Piece {
+    Edge(usize),
+    Corner(usize),
+    /// Only the orientation of the edge matters
+    EdgeOrientation(usize),
+    /// Only the orientation of the corner matters
+    CornerOrientation(usize),
+}
+
+impl Piece {
+    /// Slot and orientation of the piece.
+    fn locate(self, cube: &Cube) -> (usize, u8) {
+        let solved = Cube::default();
+        match self {
+            Piece::Edge(home) | Piece::EdgeOrientation(home) => {
+                let piece = &solved.edges[home].piece;
+                let slot = cube.edges.iter().position(|e| e.piece == *piece).unwrap();
+                (slot, cube.edges[slot].orientation)
+            }
+            Piece::Corner(home) | Piece::CornerOrientation(home) => {
+                let piece = &solved.corners[home].piece;
+                let slot = cube.corners.iter().position(|c| c.piece == *piece).unwrap();
+                (slot, cube.corners[slot].orientation)
+            }
+        }
+    }
+
+    fn is_solved(self, cube: &Cube) -> bool {
+        let (slot, orientation) = self.locate(cube);
+        match self {
+            Piece::Edge(home) | Piece::Corner(home) => slot == home && orientation == 0,
+            Piece::EdgeOrientation(_) | Piece::CornerOrientation(_) => orientation == 0,
+        }
+    }
+}
+
+const CROSS_EDGES: [usize; 4] = [cube::DF, cube::DR, cube::DB, cube::DL];
+const FIRST_LAYER_CORNERS: [usize; 4] = [cube::DFR, cube::DLF, cube::DBL, cube::DRB];
+const SECOND_LAYER_EDGES: [usize; 4] = [cube::FR, cube::FL, cube::BL, cube::BR];
+const LAST_LAYER_EDGES: [usize; 4] = [cube::UF, cube::UR, cube::UB, cube::UL];
+const LAST_LAYER_CORNERS: [usize; 4] = [cube::URF, cube::UFL, cube::ULB, cube::UBR];
+
+/// Slots of the first two layers, with the faces seen as front and right
+/// when facing the slot.
+const SLOTS: [(&str, char, char); 4] = [
+    ("FR", 'F', 'R'),
+    ("FL", 'L', 'F'),
+    ("BL", 'B', 'L'),
+    ("BR", 'R', 'B'),
+];
+
+/// Algorithm written for the FR slot, applied to the slot whose front and
+/// right faces are given.
+fn for_slot(algorithm: &str, front: char, right: char) -> String {
+    algorithm
+        .chars()
+        .map(|c| match c {
+            'F' => front,
+            'R' => right,
+            _ => c,
+        })
+        .collect()
+}
+
+fn auf_triggers() -> Vec<Trigger> {
+    ["U", "U2", "U'"]
+        .into_iter()
+        .map(|auf| Trigger::new(auf, auf))
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StageKind {
+    WhiteCross,
+    FirstLayerCorners,
+    SecondLayerEdges,
+    YellowCross,
+    YellowFace,
+    CornerPermutation,
+    EdgePermutation,
+}
+
+static STAGES: [StageKind; 7] = [
+    StageKind::WhiteCross,
+    StageKind::FirstLayerCorners,
+    StageKind::SecondLayerEdges,
+    StageKind::YellowCross,
+    StageKind::YellowFace,
+    StageKind::CornerPermutation,
+    StageKind::EdgePermutation,
+];
+
+impl StageKind {
+    fn name(self) -> &'static str {
+        match self {
+            StageKind::WhiteCross => "White cross",
+            StageKind::FirstLayerCorners => "First layer corners",
+            StageKind::SecondLayerEdges => "Second layer edges",
+            StageKind::YellowCross => "Yellow cross",
+            StageKind::YellowFace => "Yellow face",
+            StageKind::CornerPermutation => "Corner permutation",
+            StageKind::EdgePermutation => "Edge permutation",
+        }
+    }
+
+    fn triggers(self) -> Vec<Trigger> {
+        let mut triggers = match self {
+            // Any face turn, the cross being intuitive
+            StageKind::WhiteCross => {
+                return ALL_MOVES
+                    .iter()
+                    .map(|move_| Trigger {
+                        name: move_.to_string(),
+                        moves: vec![move_.clone()],
+                    })
+                    .collect()
+            }
+            StageKind::FirstLayerCorners => SLOTS
+                .iter()
+                .map(|(slot, front, right)| {
+                    Trigger::new(
+                        format!("Sexy move ({slot})"),
+                        &for_slot("R U R' U'", *front, *right),
+                    )
+                })
+                .collect(),
+            StageKind::SecondLayerEdges => SLOTS
+                .iter()
+                .flat_map(|(slot, front, right)| {
+                    [
+                        Trigger::new(
+                            format!("Right insert ({slot})"),
+                            &for_slot("U R U' R' U' F' U F", *front, *right),
+                        ),
+                        Trigger::new(
+                            format!("Left insert ({slot})"),
+                            &for_slot("U' F' U F U R U' R'", *front, *right),
+                        ),
+                    ]
+                })
+                .collect(),
+            StageKind::YellowCross => vec![Trigger::new("Yellow cross", "F R U R' U' F'")],
+            StageKind::YellowFace => vec![Trigger::new("Sune", "R U R' U R U2 R'")],
+            StageKind::CornerPermutation => {
+                vec![Trigger::new("Corner swap", "R' F R' B2 R F' R' B2 R2")]
+            }
+            StageKind::EdgePermutation => {
+                vec![Trigger::new("Edge cycle", "R2 U R U R' U' R' U' R' U R'")]
+            }
+        };
+        triggers.extend(auf_triggers());
+        triggers
+    }
+
+    /// Pieces placed by the stage, one group at a time. Each group also
+    /// keeps the pieces of the previous groups and stages solved.
+    fn groups(self) -> Vec<Vec<Piece>> {
+        let cross = CROSS_EDGES.map(Piece::Edge);
+        let first_layer = [
+            cross.to_vec(),
+            FIRST_LAYER_CORNERS.map(Piece::Corner).to_vec(),
+        ]
+        .concat();
+        let f2l = [
+            first_layer.clone(),
+            SECOND_LAYER_EDGES.map(Piece::Edge).to_vec(),
+        ]
+        .concat();
+        let one_at_a_time = |solved: &[Piece], pieces: &[Piece]| {
+            (1..=pieces.len())
+                .map(|count| [solved, &pieces[..count]].concat())
+                .collect()
+        };
+        match self {
+            StageKind::WhiteCross => one_at_a_time(&[], &cross),
+            StageKind::FirstLayerCorners => {
+                one_at_a_time(&cross, &FIRST_LAYER_CORNERS.map(Piece::Corner))
+            }
+            StageKind::SecondLayerEdges => {
+                one_at_a_time(&first_layer, &SECOND_LAYER_EDGES.map(Piece::Edge))
+            }
+            StageKind::YellowCross => {
+                vec![[f2l, LAST_LAYER_EDGES.map(Piece::EdgeOrientation).to_vec()].concat()]
+            }
+            StageKind::YellowFace => vec![[
+                f2l,
+                LAST_LAYER_EDGES.map(Piece::EdgeOrientation).to_vec(),
+                LAST_LAYER_CORNERS.map(Piece::CornerOrientation).to_vec(),
+            ]
+            .concat()],
+            StageKind::CornerPermutation => vec![[
+                f2l,
+                LAST_LAYER_EDGES.map(Piece::EdgeOrientation).to_vec(),
+                LAST_LAYER_CORNERS.map(Piece::Corner).to_vec(),
+            ]
+            .concat()],
+            StageKind::EdgePermutation => vec![[
+                f2l,
+                LAST_LAYER_EDGES.map(Piece::Edge).to_vec(),
+                LAST_LAYER_CORNERS.map(Piece::Corner).to_vec(),
+            ]
+            .concat()],
+        }
+    }
+
+    /// Solve the pieces of the stage group by group. The stage is labelled
+    /// with the triggers it is made of, except for the intuitive cross whose
+    /// triggers are single moves.
+    fn solve(self, cube: &Cube, budget: &Budget) -> Result<Stage, SolveError> {
+        let name = self.name();
+        let triggers = self.triggers();
+        let mut cube = cube.clone();
+        let mut stage = Stage::new(name, vec![]);
+        let mut names = vec![];
+        for pieces in self.groups() {
+            let found = budget
+                .check(name)
+                .and_then(|()| solve_with_triggers(&cube, &pieces, &triggers, budget, name))
+                .map_err(|error| error.after(&stage.moves))?;
+            for trigger in found {
+                cube.execute_algorithm(&trigger.moves);
+                names.push(trigger.name.as_str());
+                stage.moves.extend(trigger.moves.iter().cloned());
+            }
+        }
+        if self != StageKind::WhiteCross && !names.is_empty() {
+            stage.case = Some(names.join(", "));
+        }
+        Ok(stage)
+    }
+}
+
+/// Each stage has a single solution, the one of [`StageKind::solve`].
+impl StepSolutions for StageKind {
+    fn name(&self) -> &str {
+        StageKind::name(*self)
+    }
+
+    fn case_name(&self, cube: &Cube) -> Option<String> {
+        self.solve(cube, &Budget::new(&SolveOptions::new()))
+            .ok()?
+            .case
+    }
+
+    fn lower_bound(&self, _cube: &Cube) -> usize {
+        0
+    }
+
+    fn step_solutions<'a>(
+        &'a self,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Box<dyn Iterator<Item = Vec<Move>> + 'a> {
+        let moves = self
+            .solve(cube, &Budget::new(&SolveOptions::new()))
+            .ok()
+            .map(|stage| stage.moves)
+            .filter(|moves| options.allows_length(moves.len()));
+        Box::new(moves.into_iter())
+    }
+}
+
+/// Shortest sequence of triggers solving the pieces, found with a
+/// breadth-first search over the states of the pieces. Two AUFs never follow
+/// each other.
+fn solve_with_triggers<'a>(
+    cube: &Cube,
+    pieces: &[Piece],
+    triggers: &'a [Trigger],
+    budget: &Budget,
+    stage: &'static str,
+) -> Result<Vec<&'a Trigger>, SolveError> {
+    let key = |cube: &Cube| {
+        pieces
+            .iter()
+            .map(|piece| piece.locate(cube))
+            .collect::<Vec<_>>()
+    };
+    let mut visited = HashSet::from([key(cube)]);
+    let mut queue = VecDeque::from([(cube.clone(), vec![])]);
+    while let Some((cube, path)) = queue.pop_front() {
+        if pieces.iter().all(|piece| piece.is_solved(&cube)) {
+            return Ok(path.into_iter().map(|index| &triggers[index]).collect());
+        }
+        budget.visit(stage)?;
+        for (index, trigger) in triggers.iter().enumerate() {
+            if trigger.is_auf()
+                && path
+                    .last()
+                    .is_some_and(|last: &usize| triggers[*last].is_auf())
+            {
+                continue;
+            }
+            let mut next = cube.clone();
+            next.execute_algorithm(&trigger.moves);
+            if visited.insert(key(&next)) {
+                let mut path = path.clone();
+                path.push(index);
+                queue.push_back((next, path));
+            }
+        }
+    }
+    Err(SolveError::UnreachableCase { step: stage })
+}
+
+#[derive(Clone, Default)]
+pub struct Solver;
+
+impl Solver {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Labels of the stages, in order.
+    #[must_use]
+    pub fn stage_names() -> [&'static str; 7] {
+        STAGES.map(StageKind::name)
+    }
+}
+
+impl Method for Solver {
+    /// Solve the cube stage by stage.
+    fn try_solve_with(&self, cube: &Cube, options: &SolveOptions) -> Result<Solution, SolveError> {
+        cube.validate()?;
+        let budget = Budget::new(options);
+        let mut cube = cube.clone();
+        let mut solution = Solution::default();
+        for kind in STAGES {
+            let stage = kind
+                .solve(&cube, &budget)
+                .map_err(|error| error.after(&solution.moves()))?;
+            cube.execute_algorithm(&stage.moves);
+            solution.stages.push(stage);
+        }
+        Ok(solution)
+    }
+
+    fn solutions<'a>(
+        &'a self,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Result<MethodSolutions<'a>, SolveError> {
+        cube.validate()?;
+        let stages = STAGES
+            .iter()
+            .map(|kind| kind as &dyn StepSolutions)
+            .collect();
+        Ok(MethodSolutions::new(stages, cube, options))
+    }
+
+    fn memory_usage(&self) -> usize {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve_scramble_by_stages() {
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&parse_algorithm(
+            "D' R2 U' F2 D' L2 U2 B2 R2 U' L' B' D2 L' F' U2 R U' F' L",
+        ));
+
+        let solution = Solver::new().try_solve(&cube).unwrap();
+        assert_eq!(
+            solution
+                .stages
+                .iter()
+                .map(|stage| stage.name.as_str())
+                .collect::<Vec<_>>(),
+            STAGES.map(StageKind::name)
+        );
+        assert_eq!(solution.stages[0].case, None);
+        assert!(solution.stages[1]
+            .case
+            .as_ref()
+            .is_some_and(|case| case.contains("Sexy move")));
+        for stage in &solution.stages {
+            cube.execute_algorithm(&stage.moves);
+        }
+        assert_eq!(cube, Cube::default());
+    }
+
+    #[test]
+    fn enumerate_by_stages() {
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&parse_algorithm("R U R' F2 D L' B"));
+
+        let solver = Solver::new();
+        let mut solutions = solver.solutions(&cube, &EnumerationOptions::new()).unwrap();
+        let solution = solutions.next().unwrap();
+        assert_eq!(solution, solver.try_solve(&cube).unwrap());
+        assert!(solutions.next().is_none());
+    }
+
+    #[test]
+    fn reach_pattern() {
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&parse_algorithm("R U R' F2 D L' B"));
+        let checkerboard = cube::patterns::checkerboard();
+
+        let solution = Solver::new().try_solve_to(&cube, &checkerboard).unwrap();
+        cube.execute_algorithm(&solution.moves());
+        assert_eq!(cube, checkerboard);
+    }
+
+    #[test]
+    fn triggers_of_a_slot() {
+        assert_eq!(for_slot("R U R' U'", 'B', 'L'), "L U L' U'");
+        assert_eq!(
+            for_slot("U' F' U F U R U' R'", 'L', 'F'),
+            "U' L' U L U F U' F'"
+        );
+    }
+}
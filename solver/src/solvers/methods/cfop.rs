@@ -1,16 +1,57 @@
-use cube::{algorithms::Move, Cube};
+use cube::Cube;
 
 use crate::solvers::{
-    solver::{Method, Step},
-    steps::{cross, f2l, oll, pll},
+    algorithm_set::{AlgorithmSet, AlgorithmSetError},
+    enumeration::{EnumerationOptions, MethodSolutions, StepSolutions},
+    last_layer::LastLayerStep,
+    options::{Budget, SolveOptions},
+    rotation::Rotation,
+    solver::{Method, Solution, SolveError, Stage, Step},
+    steps::{cross, f2l, f2l_pairs, oll, pll},
+    table_store::{TableError, TableStore},
 };
 
+pub use crate::solvers::{
+    rotation::{Face, FACES},
+    steps::f2l_pairs::{PairInsertion, Slot},
+};
+
+/// Second step of the CFOP method, solving the four slots at once or pair by
+/// pair.
+#[derive(Clone)]
+enum F2l {
+    AllSlots(f2l::Solver),
+    Pairs(f2l_pairs::Solver),
+}
+
+impl F2l {
+    fn as_step(&self) -> &dyn StepSolutions {
+        match self {
+            F2l::AllSlots(solver) => solver,
+            F2l::Pairs(solver) => solver,
+        }
+    }
+
+    fn memory_usage(&self) -> usize {
+        match self {
+            F2l::AllSlots(solver) => solver.memory_usage(),
+            F2l::Pairs(solver) => solver.memory_usage(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Solver {
     cross_solver: cross::Solver,
-    f2l_solver: f2l::Solver,
+    f2l_solver: F2l,
     oll_solver: oll::Solver,
     pll_solver: pll::Solver,
+    /// Faces the cross may be solved on, the one with the shortest cross is
+    /// chosen
+    cross_faces: Vec<Face>,
+    /// Solve the cross along with the first pair, when solving the F2L pair
+    /// by pair
+    x_cross: bool,
 }
 
 impl Default for Solver {
@@ -22,35 +63,337 @@ impl Default for Solver {
 impl Solver {
     #[must_use]
     pub fn new() -> Self {
-        Self {
-            cross_solver: cross::Solver::new("/tmp/cross_solver.ron"),
-            f2l_solver: f2l::Solver::new("/tmp/f2l_solver.ron"),
-            oll_solver: oll::Solver::new("/tmp/oll_solver.ron"),
-            pll_solver: pll::Solver::new("/tmp/pll_solver.ron"),
+        Self::with_store(&TableStore::default())
+            .expect("Missing tables are generated by the default store")
+    }
+
+    /// Load the tables of the steps from the given store.
+    pub fn with_store(store: &TableStore) -> Result<Self, TableError> {
+        Self::load(F2l::AllSlots(f2l::Solver::load(store)?), store)
+    }
+
+    /// Load the tables of the steps from the given store, solving the F2L
+    /// pair by pair. With lookahead, each slot is chosen by the cheapest
+    /// sequence of two insertions rather than by the cheapest insertion.
+    pub fn with_f2l_pairs(store: &TableStore, lookahead: bool) -> Result<Self, TableError> {
+        let f2l_solver = f2l_pairs::Solver::load(store)?.with_lookahead(lookahead);
+        Self::load(F2l::Pairs(f2l_solver), store)
+    }
+
+    /// Load the tables of the steps from the given store, solving the cross
+    /// along with the cheapest pair, then the other pairs one by one.
+    pub fn with_x_cross(store: &TableStore, lookahead: bool) -> Result<Self, TableError> {
+        let mut solver = Self::with_f2l_pairs(store, lookahead)?;
+        solver.x_cross = true;
+        Ok(solver)
+    }
+
+    /// Solve the cross, or the X-cross, on the face among the given ones where
+    /// the cross is the shortest. The solutions are given in the orientation
+    /// of the scramble, without rotation, and the slots of the pairs are
+    /// named as if the cross was on D. The face is recorded as the rotation
+    /// of the solution, and in the case of the first stage, when it is not D.
+    /// Solves fail without any face.
+    #[must_use]
+    pub fn with_cross_faces(mut self, faces: &[Face]) -> Self {
+        self.cross_faces = faces.to_vec();
+        self
+    }
+
+    /// Face with the shortest cross among the faces of the solver. Its
+    /// rotation brings it to D.
+    pub fn cross_face(&self, cube: &Cube) -> Result<Face, SolveError> {
+        cube.validate()?;
+        let options = SolveOptions::default();
+        self.choose_cross_face(cube, &Budget::new(&options))
+    }
+
+    /// The X-cross is also solved on the face with the shortest cross, whose
+    /// length is a lookup rather than a search.
+    fn choose_cross_face(&self, cube: &Cube, budget: &Budget) -> Result<Face, SolveError> {
+        budget.check(cross::Solver::NAME)?;
+        if let [face] = self.cross_faces[..] {
+            return Ok(face);
+        }
+        self.cross_faces
+            .iter()
+            .copied()
+            .min_by_key(|face| {
+                Step::lower_bound(&self.cross_solver, &Rotation::new(*face).rotate(cube))
+            })
+            .ok_or(SolveError::SearchExhausted {
+                step: cross::Solver::NAME,
+            })
+    }
+
+    fn load(f2l_solver: F2l, store: &TableStore) -> Result<Self, TableError> {
+        Ok(Self {
+            cross_solver: cross::Solver::load(store)?,
+            f2l_solver,
+            oll_solver: oll::Solver::load(store)?,
+            pll_solver: pll::Solver::load(store)?,
+            cross_faces: vec![Face::D],
+            x_cross: false,
+        })
+    }
+
+    /// Solve the F2L pairs, the OLL or the PLL, depending on the step of the
+    /// set, with the preferred algorithms of the set. F2L sets need the F2L
+    /// to be solved pair by pair.
+    pub fn with_algorithm_set(mut self, set: &AlgorithmSet) -> Result<Self, AlgorithmSetError> {
+        match set.step {
+            LastLayerStep::F2l => {
+                let algorithms = set.preferred_algorithms(LastLayerStep::F2l)?;
+                let F2l::Pairs(f2l_solver) = &mut self.f2l_solver else {
+                    return Err(AlgorithmSetError::Unused(LastLayerStep::F2l));
+                };
+                *f2l_solver = f2l_solver.clone().with_algorithms(&algorithms);
+            }
+            LastLayerStep::Oll => {
+                self.oll_solver =
+                    oll::Solver::from_algorithms(&set.preferred_algorithms(LastLayerStep::Oll)?);
+            }
+            LastLayerStep::Pll => {
+                self.pll_solver =
+                    pll::Solver::from_algorithms(&set.preferred_algorithms(LastLayerStep::Pll)?);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Solve the cube within the limits of the options, along with the slot
+    /// inserted by each segment of the F2L. The segments are only known when
+    /// the F2L is solved pair by pair, and are empty otherwise.
+    pub fn try_solve_with_pairs(
+        &self,
+        cube: &Cube,
+        options: &SolveOptions,
+    ) -> Result<(Solution, Vec<PairInsertion>), SolveError> {
+        cube.validate()?;
+        let budget = Budget::new(options);
+        let face = self.choose_cross_face(cube, &budget)?;
+        let rotation = Rotation::new(face);
+        let mut cube = rotation.rotate(cube);
+        let mut solution = Solution::default();
+        let mut insertions = vec![];
+
+        if !self.x_cross {
+            self.cross_solver.apply(&mut cube, &mut solution, &budget)?;
+        }
+        match &self.f2l_solver {
+            F2l::AllSlots(solver) => solver.apply(&mut cube, &mut solution, &budget)?,
+            F2l::Pairs(solver) => {
+                insertions = solver
+                    .solve_pairs(&cube, &budget)
+                    .map_err(|error| error.after(&solution.moves()))?;
+                for (index, insertion) in insertions.iter().enumerate() {
+                    cube.execute_algorithm(&insertion.moves);
+                    let name = if self.x_cross && index == 0 {
+                        "x_cross"
+                    } else {
+                        f2l_pairs::Solver::NAME
+                    };
+                    solution.stages.push(Stage {
+                        name: name.to_string(),
+                        moves: insertion.moves.clone(),
+                        case: Some(format!("{} pair", insertion.slot)),
+                        stats: insertion.stats,
+                    });
+                }
+            }
         }
+        self.oll_solver.apply(&mut cube, &mut solution, &budget)?;
+        self.pll_solver.apply(&mut cube, &mut solution, &budget)?;
+
+        if let Some(first) = solution.stages.first_mut().filter(|_| face != Face::D) {
+            let on_face = format!("cross on {face} ({})", face.rotation());
+            first.case = Some(match first.case.take() {
+                Some(case) => format!("{case}, {on_face}"),
+                None => on_face,
+            });
+        }
+        for insertion in &mut insertions {
+            insertion.moves = rotation.original_moves(&insertion.moves);
+        }
+        solution.map_moves(|moves| rotation.original_moves(moves));
+        solution.rotation = (face != Face::D).then_some(face);
+        Ok((solution, insertions))
     }
 }
 
 impl Method for Solver {
-    fn solve(&self, cube: &Cube) -> Vec<Move> {
-        let mut cube = cube.clone();
+    fn try_solve_with(&self, cube: &Cube, options: &SolveOptions) -> Result<Solution, SolveError> {
+        self.try_solve_with_pairs(cube, options)
+            .map(|(solution, _)| solution)
+    }
+
+    fn solutions<'a>(
+        &'a self,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Result<MethodSolutions<'a>, SolveError> {
+        cube.validate()?;
+        let solve_options = SolveOptions::default();
+        let rotation = Rotation::new(self.choose_cross_face(cube, &Budget::new(&solve_options))?);
+        let mut steps = vec![
+            &self.cross_solver as &dyn StepSolutions,
+            self.f2l_solver.as_step(),
+            &self.oll_solver,
+            &self.pll_solver,
+        ];
+        if self.x_cross {
+            steps.remove(0);
+        }
+        Ok(MethodSolutions::new(steps, &rotation.rotate(cube), options).with_rotation(rotation))
+    }
 
-        let cross_solution = self.cross_solver.solve(&cube);
-        cube.execute_algorithm(&cross_solution);
+    fn memory_usage(&self) -> usize {
+        self.cross_solver.memory_usage()
+            + self.f2l_solver.memory_usage()
+            + self.oll_solver.memory_usage()
+            + self.pll_solver.memory_usage()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cube::{algorithms::parse_algorithm, subcases::CubeSubset};
+
+    use super::*;
+    use crate::solvers::cube_subsets::{
+        BackLeftBlock, BackRightBlock, Cross, FrontLeftBlock, FrontRightBlock,
+    };
+
+    fn is_slot_solved(slot: Slot, cube: &Cube) -> bool {
+        let solved = Cube::default();
+        match slot {
+            Slot::FrontRight => {
+                FrontRightBlock::from_cube(cube) == FrontRightBlock::from_cube(&solved)
+            }
+            Slot::FrontLeft => {
+                FrontLeftBlock::from_cube(cube) == FrontLeftBlock::from_cube(&solved)
+            }
+            Slot::BackLeft => BackLeftBlock::from_cube(cube) == BackLeftBlock::from_cube(&solved),
+            Slot::BackRight => {
+                BackRightBlock::from_cube(cube) == BackRightBlock::from_cube(&solved)
+            }
+        }
+    }
 
-        let f2l_solution = self.f2l_solver.solve(&cube);
-        cube.execute_algorithm(&f2l_solution);
+    #[test]
+    fn solve_scramble_pair_by_pair_on_any_face() {
+        let mut solver = Solver {
+            cross_solver: cross::Solver::generate(),
+            f2l_solver: F2l::Pairs(f2l_pairs::Solver::generate()),
+            oll_solver: oll::Solver::generate(),
+            pll_solver: pll::Solver::generate(),
+            cross_faces: vec![Face::D],
+            x_cross: false,
+        };
+        let mut scramble = Cube::default();
+        scramble.execute_algorithm(&parse_algorithm(
+            "D' R2 U' F2 D' L2 U2 B2 R2 U' L' B' D2 L' F' U2 R U' F' L",
+        ));
 
-        let oll_solution = self.oll_solver.solve(&cube);
-        cube.execute_algorithm(&oll_solution);
+        for lookahead in [false, true] {
+            if let F2l::Pairs(f2l_solver) = &mut solver.f2l_solver {
+                *f2l_solver = f2l_solver.clone().with_lookahead(lookahead);
+            }
+            let (solution, insertions) = solver
+                .try_solve_with_pairs(&scramble, &SolveOptions::default())
+                .unwrap();
+
+            let mut cube = scramble.clone();
+            cube.execute_algorithm(&solver.cross_solver.solve(&cube).unwrap());
+            let mut solved = vec![];
+            for insertion in &insertions {
+                cube.execute_algorithm(&insertion.moves);
+                solved.push(insertion.slot);
+                assert!(Cross::from_cube(&cube) == Cross::from_cube(&Cube::default()));
+                assert!(solved.iter().all(|slot| is_slot_solved(*slot, &cube)));
+            }
+
+            let mut cube = scramble.clone();
+            cube.execute_algorithm(&solution.moves());
+            assert_eq!(cube, Cube::default());
+
+            let stages = solution
+                .stages
+                .iter()
+                .map(|stage| stage.name.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                stages,
+                [
+                    "cross",
+                    "f2l_pairs",
+                    "f2l_pairs",
+                    "f2l_pairs",
+                    "f2l_pairs",
+                    "oll",
+                    "pll"
+                ]
+            );
+            assert!(solution.stages[5]
+                .case
+                .as_ref()
+                .is_some_and(|case| case.starts_with("OLL ")));
+            assert!(solution
+                .reconstruction()
+                .lines()
+                .nth(1)
+                .unwrap()
+                .ends_with(" pair"));
+        }
+
+        solver = solver.with_cross_faces(&FACES);
+        let face = solver.cross_face(&scramble).unwrap();
+        let cross_length = |face: Face| {
+            let cube = Rotation::new(face).rotate(&scramble);
+            solver.cross_solver.solve(&cube).unwrap().len()
+        };
+        assert!(FACES
+            .iter()
+            .all(|other| cross_length(face) <= cross_length(*other)));
+
+        for x_cross in [false, true] {
+            solver.x_cross = x_cross;
+            if x_cross {
+                solver = solver.with_cross_faces(&[Face::D, face]);
+            }
+            let solution = solver.try_solve(&scramble).unwrap();
+            let mut cube = scramble.clone();
+            cube.execute_algorithm(&solution.moves());
+            assert_eq!(cube, Cube::default());
+        }
 
-        let pll_solution = self.pll_solver.solve(&cube);
-        cube.execute_algorithm(&pll_solution);
+        // The X-cross on R is labelled with its slot and its face
+        let solution = solver
+            .clone()
+            .with_cross_faces(&[Face::R])
+            .try_solve(&scramble)
+            .unwrap();
+        let mut cube = scramble.clone();
+        cube.execute_algorithm(&solution.moves());
+        assert_eq!(cube, Cube::default());
+        assert!(solution.stages[0]
+            .label()
+            .ends_with(" pair, cross on R (z)"));
+        assert_eq!(solution.rotation, Some(Face::R));
+        let options = EnumerationOptions::new().max_count(1).max_step_solutions(1);
+        let solution = solver
+            .clone()
+            .with_cross_faces(&[Face::R])
+            .solutions(&scramble, &options)
+            .unwrap()
+            .next()
+            .unwrap();
+        assert_eq!(solution.rotation, Some(Face::R));
 
-        let mut solution = cross_solution;
-        solution.extend(f2l_solution);
-        solution.extend(oll_solution);
-        solution.extend(pll_solution);
-        solution
+        let solver = solver.with_cross_faces(&[]);
+        assert!(matches!(
+            solver.try_solve(&scramble),
+            Err(SolveError::SearchExhausted { step: "cross" })
+        ));
     }
 }
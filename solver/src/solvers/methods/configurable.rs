@@ -0,0 +1,385 @@
+//! # Methods described by a RON file instead of Rust code.
+//!
+//! A configured method is an ordered list of stages. Each stage solves a
+//! goal with the moves of a set, and keeps the goals of the stages before
+//! it solved:
+//!
+//! ```ron
+//! (
+//!     name: "corners first",
+//!     description: "Corners with a table, then edges with <R, U>",
+//!     stages: [
+//!         (
+//!             name: "corners",
+//!             goal: Pieces((placed: ["URF", "UFL", "ULB", "UBR", "DFR", "DRB"])),
+//!             moves: "<R, U>",
+//!             strategy: Lookup,
+//!             table: Some("corners_first_corners"),
+//!         ),
+//!         (
+//!             name: "edges",
+//!             goal: Solved,
+//!             moves: "<R, U>",
+//!             strategy: Ida(Depth(6)),
+//!         ),
+//!     ],
+//! )
+//! ```
+//!
+//! Goals are the targets of [`MoveSetStep`], either a named target such as
+//! `Cross` or `F2l`, or the `Pieces` of a [`PieceMask`].
+//! The `Lookup` strategy fills a table of all the states the moves reach, so
+//! that the search only follows the table, while `Ida` searches with a table
+//! of the given [`Pruning`]. Tables named by `table` are cached by the
+//! [`TableStore`], and regenerated when their stage changes.
+
+use std::{error::Error, fmt::Display, fs, io, path::Path, time::Instant};
+
+use cube::{algorithms::Move, Cube};
+use serde::{Deserialize, Serialize};
+
+use crate::solvers::{
+    enumeration::{EnumerationOptions, MethodSolutions, StepSolutions},
+    goal::PieceMask,
+    methods::registry::{MethodInfo, TableInfo},
+    move_set::{MoveSet, MoveSetStep, Pruning, Target},
+    options::{Budget, SolveOptions},
+    progress::{NoProgress, ProgressReporter},
+    solver::{Method, SearchStats, Solution, SolveError, Stage, Step},
+    table_store::{fingerprint, TableError, TableStore},
+};
+
+/// Method read from a RON file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MethodConfig {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub stages: Vec<StageConfig>,
+}
+
+/// Stage of a configured method.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageConfig {
+    pub name: String,
+    /// Pieces solved by the stage, along with those of the stages before it
+    pub goal: Target,
+    pub moves: MoveSet,
+    pub strategy: Strategy,
+    /// Name of the cached table of the stage, which is generated each time
+    /// the method is loaded when `None`
+    #[serde(default)]
+    pub table: Option<String>,
+}
+
+/// How a stage finds its solutions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Strategy {
+    /// Table of all the states the moves reach, only practical for small
+    /// goals
+    Lookup,
+    /// IDA* search with the given table
+    Ida(Pruning),
+}
+
+impl Strategy {
+    fn pruning(self) -> Pruning {
+        match self {
+            Strategy::Lookup => Pruning::Complete,
+            Strategy::Ida(pruning) => pruning,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum MethodConfigError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+    NoStages,
+    Tables(TableError),
+}
+
+impl Display for MethodConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MethodConfigError::Io(error) => write!(f, "I/O error: {error}"),
+            MethodConfigError::Parse(error) => write!(f, "parse error: {error}"),
+            MethodConfigError::NoStages => f.write_str("the method has no stage"),
+            MethodConfigError::Tables(error) => write!(f, "missing tables: {error}"),
+        }
+    }
+}
+
+impl Error for MethodConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MethodConfigError::Io(error) => Some(error),
+            MethodConfigError::Parse(error) => Some(error),
+            MethodConfigError::NoStages => None,
+            MethodConfigError::Tables(error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for MethodConfigError {
+    fn from(error: io::Error) -> Self {
+        MethodConfigError::Io(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for MethodConfigError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        MethodConfigError::Parse(error)
+    }
+}
+
+impl From<TableError> for MethodConfigError {
+    fn from(error: TableError) -> Self {
+        MethodConfigError::Tables(error)
+    }
+}
+
+impl MethodConfig {
+    pub fn from_ron(text: &str) -> Result<Self, MethodConfigError> {
+        Ok(ron::from_str(text)?)
+    }
+
+    pub fn read(path: &Path) -> Result<Self, MethodConfigError> {
+        Self::from_ron(&fs::read_to_string(path)?)
+    }
+
+    /// Description of the method, its stages and cached tables, without
+    /// generating them.
+    #[must_use]
+    pub fn info(&self) -> MethodInfo {
+        let mut info = MethodInfo::new(&self.name, &self.description);
+        for (stage, target) in self.stages.iter().zip(self.targets()) {
+            info = info.step(&stage.name);
+            if let Some(table) = &stage.table {
+                info = info.table(TableInfo::new(table, stage.revision(target)));
+            }
+        }
+        info
+    }
+
+    /// Target of each stage, keeping the goals of the stages before it.
+    fn targets(&self) -> Vec<Target> {
+        let mut mask = PieceMask::new();
+        self.stages
+            .iter()
+            .map(|stage| {
+                mask = mask.union(&stage.goal.mask());
+                Target::Pieces(mask)
+            })
+            .collect()
+    }
+}
+
+impl StageConfig {
+    /// Revision of the cached table of the stage, which changes along with
+    /// the stage.
+    fn revision(&self, target: Target) -> u64 {
+        let key =
+            ron::to_string(&(target, &self.moves, self.strategy)).expect("Stages are plain data");
+        fingerprint(&[&key])
+    }
+}
+
+/// Step of a stage, named after it.
+#[derive(Clone)]
+struct ConfiguredStep {
+    name: String,
+    step: MoveSetStep,
+}
+
+impl StepSolutions for ConfiguredStep {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn case_name(&self, _cube: &Cube) -> Option<String> {
+        None
+    }
+
+    fn lower_bound(&self, cube: &Cube) -> usize {
+        Step::lower_bound(&self.step, cube)
+    }
+
+    fn step_solutions<'a>(
+        &'a self,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Box<dyn Iterator<Item = Vec<Move>> + 'a> {
+        Step::solutions(&self.step, cube, options)
+    }
+}
+
+/// Method running the stages of a [`MethodConfig`].
+#[derive(Clone)]
+pub struct ConfigurableMethod {
+    config: MethodConfig,
+    steps: Vec<ConfiguredStep>,
+}
+
+impl ConfigurableMethod {
+    /// Build the steps of the stages, loading their tables from the store.
+    pub fn from_config(
+        config: MethodConfig,
+        store: &TableStore,
+    ) -> Result<Self, MethodConfigError> {
+        if config.stages.is_empty() {
+            return Err(MethodConfigError::NoStages);
+        }
+        let mut steps = vec![];
+        for (stage, target) in config.stages.iter().zip(config.targets()) {
+            let build = |progress: &dyn ProgressReporter| {
+                MoveSetStep::builder(stage.moves.clone())
+                    .target(target)
+                    .pruning(stage.strategy.pruning())
+                    .build_with_progress(progress)
+            };
+            let step = match &stage.table {
+                Some(table) => store.load_with(table, stage.revision(target), build)?,
+                None => build(&NoProgress),
+            };
+            steps.push(ConfiguredStep {
+                name: stage.name.clone(),
+                step,
+            });
+        }
+        Ok(Self { config, steps })
+    }
+
+    pub fn from_ron(text: &str, store: &TableStore) -> Result<Self, MethodConfigError> {
+        Self::from_config(MethodConfig::from_ron(text)?, store)
+    }
+
+    pub fn read(path: &Path, store: &TableStore) -> Result<Self, MethodConfigError> {
+        Self::from_config(MethodConfig::read(path)?, store)
+    }
+
+    #[must_use]
+    pub fn config(&self) -> &MethodConfig {
+        &self.config
+    }
+}
+
+impl Method for ConfigurableMethod {
+    fn try_solve_with(&self, cube: &Cube, options: &SolveOptions) -> Result<Solution, SolveError> {
+        cube.validate()?;
+        let budget = Budget::new(options);
+        let mut cube = cube.clone();
+        let mut solution = Solution::default();
+
+        for configured in &self.steps {
+            let start = Instant::now();
+            let nodes = budget.nodes();
+            let moves = configured
+                .step
+                .solve_within(&cube, &budget)
+                .map_err(|error| error.after(&solution.moves()))?;
+            cube.execute_algorithm(&moves);
+            let mut stage = Stage::new(configured.name.clone(), moves);
+            stage.stats = SearchStats {
+                nodes: budget.nodes() - nodes,
+                duration: start.elapsed(),
+            };
+            solution.stages.push(stage);
+        }
+
+        Ok(solution)
+    }
+
+    fn solutions<'a>(
+        &'a self,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Result<MethodSolutions<'a>, SolveError> {
+        cube.validate()?;
+        let steps = self
+            .steps
+            .iter()
+            .map(|step| step as &dyn StepSolutions)
+            .collect();
+        Ok(MethodSolutions::new(steps, cube, options))
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.steps
+            .iter()
+            .map(|configured| configured.step.memory_usage())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cube::algorithms::parse_algorithm;
+
+    use super::*;
+
+    const CORNERS_FIRST: &str = r#"(
+        name: "corners first",
+        description: "Corners with a table, then edges with <R, U>",
+        stages: [
+            (
+                name: "corners",
+                goal: Pieces((placed: ["URF", "UFL", "ULB", "UBR", "DFR", "DRB"])),
+                moves: "<R, U>",
+                strategy: Lookup,
+                table: Some("corners_first_corners"),
+            ),
+            (
+                name: "edges",
+                goal: Solved,
+                moves: "<R, U>",
+                strategy: Ida(Depth(6)),
+            ),
+        ],
+    )"#;
+
+    #[test]
+    fn run_configured_stages() {
+        let directory =
+            std::env::temp_dir().join(format!("kubin-test-config-{}", std::process::id()));
+        let store = TableStore::new(&directory);
+        let info = MethodConfig::from_ron(CORNERS_FIRST).unwrap().info();
+        assert_eq!(info.steps, ["corners", "edges"]);
+        assert!(!info.is_cached(&store));
+        let method = ConfigurableMethod::from_ron(CORNERS_FIRST, &store).unwrap();
+        assert!(info.is_cached(&store));
+
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&parse_algorithm("R U R' U R U2 R' U2"));
+        let solution = method.try_solve(&cube).unwrap();
+        assert_eq!(
+            solution
+                .stages
+                .iter()
+                .map(|stage| stage.name.as_str())
+                .collect::<Vec<_>>(),
+            ["corners", "edges"]
+        );
+        cube.execute_algorithm(&solution.moves());
+        assert_eq!(cube, Cube::default());
+
+        // The cached table is read back
+        assert!(
+            ConfigurableMethod::from_ron(CORNERS_FIRST, &store.generate_missing(false)).is_ok()
+        );
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn reject_invalid_configs() {
+        let store = TableStore::new(std::env::temp_dir()).generate_missing(false);
+        assert!(matches!(
+            ConfigurableMethod::from_ron(r#"(name: "empty", stages: [])"#, &store),
+            Err(MethodConfigError::NoStages)
+        ));
+        assert!(matches!(
+            MethodConfig::from_ron(&CORNERS_FIRST.replace("<R, U>", "<R, X>")),
+            Err(MethodConfigError::Parse(_))
+        ));
+    }
+}
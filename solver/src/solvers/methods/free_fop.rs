@@ -1,8 +1,11 @@
-use cube::{algorithms::Move, Cube};
+use cube::Cube;
 
 use crate::solvers::{
-    solver::{Method, Step},
+    enumeration::{EnumerationOptions, MethodSolutions},
+    options::{Budget, SolveOptions},
+    solver::{Method, Solution, SolveError, Step},
     steps::{free_f2l, oll, pll},
+    table_store::{TableError, TableStore},
 };
 
 #[derive(Clone)]
@@ -21,30 +24,51 @@ impl Default for Solver {
 impl Solver {
     #[must_use]
     pub fn new() -> Self {
-        Self {
-            free_f2l_solver: free_f2l::Solver::new("/tmp/free_f2l_solver.ron"),
-            oll_solver: oll::Solver::new("/tmp/oll_solver.ron"),
-            pll_solver: pll::Solver::new("/tmp/pll_solver.ron"),
-        }
+        Self::with_store(&TableStore::default())
+            .expect("Missing tables are generated by the default store")
+    }
+
+    /// Load the tables of the steps from the given store.
+    pub fn with_store(store: &TableStore) -> Result<Self, TableError> {
+        Ok(Self {
+            free_f2l_solver: free_f2l::Solver::load(store)?,
+            oll_solver: oll::Solver::load(store)?,
+            pll_solver: pll::Solver::load(store)?,
+        })
     }
 }
 
 impl Method for Solver {
-    fn solve(&self, cube: &Cube) -> Vec<Move> {
+    fn try_solve_with(&self, cube: &Cube, options: &SolveOptions) -> Result<Solution, SolveError> {
+        cube.validate()?;
+        let budget = Budget::new(options);
         let mut cube = cube.clone();
+        let mut solution = Solution::default();
 
-        let free_f2l_solution = self.free_f2l_solver.solve(&cube);
-        cube.execute_algorithm(&free_f2l_solution);
+        self.free_f2l_solver
+            .apply(&mut cube, &mut solution, &budget)?;
+        self.oll_solver.apply(&mut cube, &mut solution, &budget)?;
+        self.pll_solver.apply(&mut cube, &mut solution, &budget)?;
 
-        let oll_solution = self.oll_solver.solve(&cube);
-        cube.execute_algorithm(&oll_solution);
+        Ok(solution)
+    }
 
-        let pll_solution = self.pll_solver.solve(&cube);
-        cube.execute_algorithm(&pll_solution);
+    fn solutions<'a>(
+        &'a self,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Result<MethodSolutions<'a>, SolveError> {
+        cube.validate()?;
+        Ok(MethodSolutions::new(
+            vec![&self.free_f2l_solver, &self.oll_solver, &self.pll_solver],
+            cube,
+            options,
+        ))
+    }
 
-        let mut solution = free_f2l_solution;
-        solution.extend(oll_solution);
-        solution.extend(pll_solution);
-        solution
+    fn memory_usage(&self) -> usize {
+        self.free_f2l_solver.memory_usage()
+            + self.oll_solver.memory_usage()
+            + self.pll_solver.memory_usage()
     }
 }
@@ -1,35 +1,100 @@
-use super::solver::Method;
+use std::sync::Arc;
 
+use cube::Cube;
+
+use super::{
+    enumeration::{EnumerationOptions, MethodSolutions},
+    options::SolveOptions,
+    solver::{Method, Solution, SolveError},
+    table_store::TableStore,
+};
+
+pub mod beginner;
 pub mod cfop;
+pub mod configurable;
 pub mod free_fop;
 pub mod one_phase;
+pub mod petrus;
+pub mod registry;
+pub mod roux;
+pub mod thistlethwaite;
 pub mod two_phase;
+pub mod zz;
 
-pub fn from_method_name(name: &str) -> Result<Methods, &'static str> {
-    match name {
-        "cfop" => Ok(Methods::Cfop(cfop::Solver::new())),
-        "free_fop" => Ok(Methods::FreeFop(free_fop::Solver::new())),
-        "one_phase" => Ok(Methods::OnePhase(one_phase::Solver::new())),
-        "two_phase" => Ok(Methods::TwoPhase(two_phase::Solver::new())),
-        _ => Err("Unknown method"),
-    }
+/// Build the method of the given name, listed by [`registry::methods`], or of
+/// the configuration file of the given path when it ends with `.ron`, with
+/// the default table store.
+pub fn from_method_name(name: &str) -> Result<Methods, registry::MethodError> {
+    registry::build_method(name, &TableStore::default())
 }
 
 #[derive(Clone)]
 pub enum Methods {
+    Beginner(beginner::Solver),
     Cfop(cfop::Solver),
+    /// Method read from a RON file, whose path ends with `.ron`
+    Configurable(configurable::ConfigurableMethod),
+    /// Method added with [`registry::register_method`]
+    Custom(Arc<dyn registry::DynMethod>),
     FreeFop(free_fop::Solver),
     OnePhase(one_phase::Solver),
+    Petrus(petrus::Solver),
+    Roux(roux::Solver),
+    Thistlethwaite(thistlethwaite::Solver),
     TwoPhase(two_phase::Solver),
+    Zz(zz::Solver),
 }
 
 impl Method for Methods {
-    fn solve(&self, cube: &cube::Cube) -> Vec<cube::algorithms::Move> {
+    fn try_solve_with(&self, cube: &Cube, options: &SolveOptions) -> Result<Solution, SolveError> {
+        match self {
+            Methods::Beginner(solver) => solver.try_solve_with(cube, options),
+            Methods::Cfop(solver) => solver.try_solve_with(cube, options),
+            Methods::Configurable(solver) => solver.try_solve_with(cube, options),
+            Methods::Custom(solver) => solver.try_solve_with(cube, options),
+            Methods::FreeFop(solver) => solver.try_solve_with(cube, options),
+            Methods::OnePhase(solver) => solver.try_solve_with(cube, options),
+            Methods::Petrus(solver) => solver.try_solve_with(cube, options),
+            Methods::Roux(solver) => solver.try_solve_with(cube, options),
+            Methods::Thistlethwaite(solver) => solver.try_solve_with(cube, options),
+            Methods::TwoPhase(solver) => solver.try_solve_with(cube, options),
+            Methods::Zz(solver) => solver.try_solve_with(cube, options),
+        }
+    }
+
+    fn solutions<'a>(
+        &'a self,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Result<MethodSolutions<'a>, SolveError> {
+        match self {
+            Methods::Beginner(solver) => solver.solutions(cube, options),
+            Methods::Cfop(solver) => solver.solutions(cube, options),
+            Methods::Configurable(solver) => solver.solutions(cube, options),
+            Methods::Custom(solver) => solver.solutions(cube, options),
+            Methods::FreeFop(solver) => solver.solutions(cube, options),
+            Methods::OnePhase(solver) => solver.solutions(cube, options),
+            Methods::Petrus(solver) => solver.solutions(cube, options),
+            Methods::Roux(solver) => solver.solutions(cube, options),
+            Methods::Thistlethwaite(solver) => solver.solutions(cube, options),
+            Methods::TwoPhase(solver) => solver.solutions(cube, options),
+            Methods::Zz(solver) => solver.solutions(cube, options),
+        }
+    }
+
+    fn memory_usage(&self) -> usize {
         match self {
-            Methods::Cfop(solver) => solver.solve(cube),
-            Methods::FreeFop(solver) => solver.solve(cube),
-            Methods::OnePhase(solver) => solver.solve(cube),
-            Methods::TwoPhase(solver) => solver.solve(cube),
+            Methods::Beginner(solver) => solver.memory_usage(),
+            Methods::Cfop(solver) => solver.memory_usage(),
+            Methods::Configurable(solver) => solver.memory_usage(),
+            Methods::Custom(solver) => solver.memory_usage(),
+            Methods::FreeFop(solver) => solver.memory_usage(),
+            Methods::OnePhase(solver) => solver.memory_usage(),
+            Methods::Petrus(solver) => solver.memory_usage(),
+            Methods::Roux(solver) => solver.memory_usage(),
+            Methods::Thistlethwaite(solver) => solver.memory_usage(),
+            Methods::TwoPhase(solver) => solver.memory_usage(),
+            Methods::Zz(solver) => solver.memory_usage(),
         }
     }
 }
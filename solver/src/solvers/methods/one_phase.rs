@@ -1,8 +1,11 @@
-use cube::{algorithms::Move, Cube};
+use cube::Cube;
 
 use crate::solvers::{
-    solver::{Method, Step},
+    enumeration::{EnumerationOptions, MethodSolutions},
+    options::{Budget, SolveOptions},
+    solver::{Method, Solution, SolveError, Step},
     steps::all,
+    table_store::{TableError, TableStore},
 };
 
 #[derive(Clone)]
@@ -19,22 +22,46 @@ impl Default for Solver {
 impl Solver {
     #[must_use]
     pub fn new() -> Self {
-        Self {
-            all_solver: all::Solver::new("/tmp/all_solver.ron"),
-        }
+        Self::with_store(&TableStore::default())
+            .expect("Missing tables are generated by the default store")
+    }
+
+    /// Load the tables of the steps from the given store.
+    pub fn with_store(store: &TableStore) -> Result<Self, TableError> {
+        Ok(Self {
+            all_solver: all::Solver::load(store)?,
+        })
     }
 }
 
 impl Method for Solver {
-    fn solve(&self, cube: &Cube) -> Vec<Move> {
+    fn try_solve_with(&self, cube: &Cube, options: &SolveOptions) -> Result<Solution, SolveError> {
+        cube.validate()?;
+        let budget = Budget::new(options);
         let mut cube = cube.clone();
+        let mut solution = Solution::default();
 
-        let solution = self.all_solver.solve(&cube);
+        self.all_solver.apply(&mut cube, &mut solution, &budget)?;
 
-        cube.execute_algorithm(&solution);
+        if cube != Cube::default() {
+            return Err(SolveError::UnreachableCase {
+                step: all::Solver::NAME,
+            });
+        }
 
-        assert_eq!(cube, Cube::default());
+        Ok(solution)
+    }
+
+    fn solutions<'a>(
+        &'a self,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Result<MethodSolutions<'a>, SolveError> {
+        cube.validate()?;
+        Ok(MethodSolutions::new(vec![&self.all_solver], cube, options))
+    }
 
-        solution
+    fn memory_usage(&self) -> usize {
+        self.all_solver.memory_usage()
     }
 }
@@ -0,0 +1,123 @@
+use cube::Cube;
+
+use crate::solvers::{
+    enumeration::{EnumerationOptions, MethodSolutions},
+    options::{Budget, SolveOptions},
+    solver::{Method, Solution, SolveError, Step},
+    steps::{oll, petrus_block, petrus_eo, petrus_expansion, pll, zz_right_block},
+    table_store::{TableError, TableStore},
+};
+
+#[derive(Clone)]
+pub struct Solver {
+    block_solver: petrus_block::Solver,
+    expansion_solver: petrus_expansion::Solver,
+    edge_orientation_solver: petrus_eo::Solver,
+    f2l_solver: zz_right_block::Solver,
+    oll_solver: oll::Solver,
+    pll_solver: pll::Solver,
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Solver {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_store(&TableStore::default())
+            .expect("Missing tables are generated by the default store")
+    }
+
+    /// Load the tables of the steps from the given store.
+    pub fn with_store(store: &TableStore) -> Result<Self, TableError> {
+        Ok(Self {
+            block_solver: petrus_block::Solver::load(store)?,
+            expansion_solver: petrus_expansion::Solver::load(store)?,
+            edge_orientation_solver: petrus_eo::Solver::load(store)?,
+            f2l_solver: zz_right_block::Solver::load(store)?,
+            oll_solver: oll::Solver::load(store)?,
+            pll_solver: pll::Solver::load(store)?,
+        })
+    }
+}
+
+impl Method for Solver {
+    fn try_solve_with(&self, cube: &Cube, options: &SolveOptions) -> Result<Solution, SolveError> {
+        cube.validate()?;
+        let budget = Budget::new(options);
+        let mut cube = cube.clone();
+        let mut solution = Solution::default();
+
+        self.block_solver.apply(&mut cube, &mut solution, &budget)?;
+        self.expansion_solver
+            .apply(&mut cube, &mut solution, &budget)?;
+        self.edge_orientation_solver
+            .apply(&mut cube, &mut solution, &budget)?;
+        // Two-gen completion of the F2L
+        self.f2l_solver.apply(&mut cube, &mut solution, &budget)?;
+        // The edges are oriented, so OLL is one of the OCLL cases
+        self.oll_solver.apply(&mut cube, &mut solution, &budget)?;
+        self.pll_solver.apply(&mut cube, &mut solution, &budget)?;
+
+        Ok(solution)
+    }
+
+    fn solutions<'a>(
+        &'a self,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Result<MethodSolutions<'a>, SolveError> {
+        cube.validate()?;
+        Ok(MethodSolutions::new(
+            vec![
+                &self.block_solver,
+                &self.expansion_solver,
+                &self.edge_orientation_solver,
+                &self.f2l_solver,
+                &self.oll_solver,
+                &self.pll_solver,
+            ],
+            cube,
+            options,
+        ))
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.block_solver.memory_usage()
+            + self.expansion_solver.memory_usage()
+            + self.edge_orientation_solver.memory_usage()
+            + self.f2l_solver.memory_usage()
+            + self.oll_solver.memory_usage()
+            + self.pll_solver.memory_usage()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cube::algorithms::parse_algorithm;
+
+    use super::*;
+
+    #[test]
+    fn solve_scramble() {
+        let solver = Solver {
+            block_solver: petrus_block::Solver::generate(),
+            expansion_solver: petrus_expansion::Solver::generate(),
+            edge_orientation_solver: petrus_eo::Solver::generate(),
+            f2l_solver: zz_right_block::Solver::generate(),
+            oll_solver: oll::Solver::generate(),
+            pll_solver: pll::Solver::generate(),
+        };
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&parse_algorithm(
+            "D' R2 U' F2 D' L2 U2 B2 R2 U' L' B' D2 L' F' U2 R U' F' L",
+        ));
+
+        let solution = solver.try_solve(&cube).unwrap();
+        cube.execute_algorithm(&solution.moves());
+        assert_eq!(cube, Cube::default());
+    }
+}
@@ -0,0 +1,481 @@
+//! # Methods selectable by name.
+//!
+//! The registry holds the built-in methods and those registered at runtime
+//! by other crates, along with a description of each: its steps and the
+//! tables they load. Methods are only built when they are selected, so that
+//! they can be listed without generating any table.
+
+use std::{
+    error::Error,
+    fmt::Display,
+    path::Path,
+    sync::{Arc, LazyLock, RwLock},
+    time::Duration,
+};
+
+use cube::Cube;
+
+use crate::solvers::{
+    enumeration::{EnumerationOptions, MethodSolutions},
+    methods::{
+        beginner, cfop,
+        configurable::{ConfigurableMethod, MethodConfig, MethodConfigError},
+        free_fop, one_phase, petrus, roux, thistlethwaite, two_phase, zz, Methods,
+    },
+    options::SolveOptions,
+    solver::{Method, Solution, SolveError, Step},
+    steps::{
+        all, cmll, cross, eo_line, f2l, first_block, free_f2l, lse, oll, orientation, permutation,
+        petrus_block, petrus_eo, petrus_expansion, pll, second_block, thistlethwaite_co,
+        thistlethwaite_eo, thistlethwaite_half_turns, thistlethwaite_tetrads, zz_left_block,
+        zz_right_block,
+    },
+    table_store::{TableError, TableStore},
+};
+
+/// Table loaded by a method from the [`TableStore`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableInfo {
+    pub kind: String,
+    pub revision: u64,
+    /// Approximate size of the cached table, in bytes
+    pub size: Option<u64>,
+    /// Approximate time to generate the table on a single core
+    pub generation_time: Option<Duration>,
+}
+
+impl TableInfo {
+    /// Table of unknown size and generation time.
+    #[must_use]
+    pub fn new(kind: &str, revision: u64) -> Self {
+        Self {
+            kind: kind.to_string(),
+            revision,
+            size: None,
+            generation_time: None,
+        }
+    }
+
+    /// Table of the step, sized from the number of cases of its tables, and
+    /// whose generation time follows from its size.
+    #[must_use]
+    pub fn of<S: Step>() -> Self {
+        Self {
+            size: S::table_size(),
+            generation_time: S::generation_time(),
+            ..Self::new(S::NAME, S::revision())
+        }
+    }
+
+    #[must_use]
+    pub fn size(mut self, size: u64) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    #[must_use]
+    pub fn generation_time(mut self, generation_time: Duration) -> Self {
+        self.generation_time = Some(generation_time);
+        self
+    }
+
+    /// Whether the table can be read from the store instead of being
+    /// generated.
+    #[must_use]
+    pub fn is_cached(&self, store: &TableStore) -> bool {
+        store.is_cached(&self.kind, self.revision)
+    }
+}
+
+/// Description of a method, available without building it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MethodInfo {
+    pub name: String,
+    pub description: String,
+    /// Names of the steps, in the order they are solved
+    pub steps: Vec<String>,
+    pub tables: Vec<TableInfo>,
+}
+
+impl MethodInfo {
+    #[must_use]
+    pub fn new(name: &str, description: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            steps: vec![],
+            tables: vec![],
+        }
+    }
+
+    /// Add a step without table.
+    #[must_use]
+    pub fn step(mut self, name: &str) -> Self {
+        self.steps.push(name.to_string());
+        self
+    }
+
+    #[must_use]
+    pub fn table(mut self, table: TableInfo) -> Self {
+        self.tables.push(table);
+        self
+    }
+
+    /// Add the step `S` along with its table.
+    #[must_use]
+    pub fn loaded_step<S: Step>(self) -> Self {
+        self.step(S::NAME).table(TableInfo::of::<S>())
+    }
+
+    /// Approximate size of the tables, in bytes, when known for all of them.
+    #[must_use]
+    pub fn tables_size(&self) -> Option<u64> {
+        self.tables.iter().map(|table| table.size).sum()
+    }
+
+    /// Approximate time to generate the tables on a single core, when known
+    /// for all of them.
+    #[must_use]
+    pub fn tables_generation_time(&self) -> Option<Duration> {
+        self.tables.iter().map(|table| table.generation_time).sum()
+    }
+
+    /// Whether all the tables can be read from the store, so that building
+    /// the method does not generate any.
+    #[must_use]
+    pub fn is_cached(&self, store: &TableStore) -> bool {
+        self.tables.iter().all(|table| table.is_cached(store))
+    }
+}
+
+#[derive(Debug)]
+pub enum MethodError {
+    /// No method is registered with the name
+    Unknown(String),
+    Tables(TableError),
+    Configuration(MethodConfigError),
+}
+
+impl Display for MethodError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MethodError::Unknown(name) => write!(f, "unknown method {name}"),
+            MethodError::Tables(error) => write!(f, "missing tables: {error}"),
+            MethodError::Configuration(error) => {
+                write!(f, "invalid method configuration: {error}")
+            }
+        }
+    }
+}
+
+impl Error for MethodError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MethodError::Unknown(_) => None,
+            MethodError::Tables(error) => Some(error),
+            MethodError::Configuration(error) => Some(error),
+        }
+    }
+}
+
+impl From<TableError> for MethodError {
+    fn from(error: TableError) -> Self {
+        MethodError::Tables(error)
+    }
+}
+
+impl From<MethodConfigError> for MethodError {
+    fn from(error: MethodConfigError) -> Self {
+        MethodError::Configuration(error)
+    }
+}
+
+/// Object-safe view of a [`Method`], for the methods registered at runtime.
+pub trait DynMethod: Send + Sync {
+    fn try_solve_with(&self, cube: &Cube, options: &SolveOptions) -> Result<Solution, SolveError>;
+    fn solutions<'a>(
+        &'a self,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Result<MethodSolutions<'a>, SolveError>;
+    fn memory_usage(&self) -> usize;
+}
+
+impl<M: Method + Send + Sync> DynMethod for M {
+    fn try_solve_with(&self, cube: &Cube, options: &SolveOptions) -> Result<Solution, SolveError> {
+        Method::try_solve_with(self, cube, options)
+    }
+
+    fn solutions<'a>(
+        &'a self,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Result<MethodSolutions<'a>, SolveError> {
+        Method::solutions(self, cube, options)
+    }
+
+    fn memory_usage(&self) -> usize {
+        Method::memory_usage(self)
+    }
+}
+
+type Constructor = Arc<dyn Fn(&TableStore) -> Result<Methods, MethodError> + Send + Sync>;
+
+struct Entry {
+    info: MethodInfo,
+    constructor: Constructor,
+}
+
+impl Entry {
+    fn new(
+        info: MethodInfo,
+        constructor: impl Fn(&TableStore) -> Result<Methods, MethodError> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            info,
+            constructor: Arc::new(constructor),
+        }
+    }
+}
+
+/// Methods selectable by name, the built-in ones first.
+static REGISTRY: LazyLock<RwLock<Vec<Entry>>> = LazyLock::new(|| RwLock::new(built_in_methods()));
+
+fn built_in_methods() -> Vec<Entry> {
+    let beginner = beginner::Solver::stage_names().iter().fold(
+        MethodInfo::new(
+            "beginner",
+            "Layer by layer, with the triggers taught to beginners",
+        ),
+        |info, stage| info.step(stage),
+    );
+    vec![
+        Entry::new(beginner, |_| Ok(Methods::Beginner(beginner::Solver::new()))),
+        Entry::new(
+            MethodInfo::new("cfop", "Cross, F2L, OLL and PLL")
+                .loaded_step::<cross::Solver>()
+                .loaded_step::<f2l::Solver>()
+                .loaded_step::<oll::Solver>()
+                .loaded_step::<pll::Solver>(),
+            |store| Ok(Methods::Cfop(cfop::Solver::with_store(store)?)),
+        ),
+        Entry::new(
+            MethodInfo::new(
+                "free_fop",
+                "F2L solved along with the cross, then OLL and PLL",
+            )
+            .loaded_step::<free_f2l::Solver>()
+            .loaded_step::<oll::Solver>()
+            .loaded_step::<pll::Solver>(),
+            |store| Ok(Methods::FreeFop(free_fop::Solver::with_store(store)?)),
+        ),
+        Entry::new(
+            MethodInfo::new("one_phase", "Optimal solutions with a single IDA* search")
+                .loaded_step::<all::Solver>(),
+            |store| Ok(Methods::OnePhase(one_phase::Solver::with_store(store)?)),
+        ),
+        Entry::new(
+            MethodInfo::new(
+                "petrus",
+                "2x2x2 block, 2x2x3 block, edge orientation, F2L, then OLL and PLL",
+            )
+            .loaded_step::<petrus_block::Solver>()
+            .loaded_step::<petrus_expansion::Solver>()
+            .loaded_step::<petrus_eo::Solver>()
+            .loaded_step::<zz_right_block::Solver>()
+            .loaded_step::<oll::Solver>()
+            .loaded_step::<pll::Solver>(),
+            |store| Ok(Methods::Petrus(petrus::Solver::with_store(store)?)),
+        ),
+        Entry::new(
+            MethodInfo::new("roux", "First block, second block, CMLL and LSE")
+                .loaded_step::<first_block::Solver>()
+                .loaded_step::<second_block::Solver>()
+                .loaded_step::<cmll::Solver>()
+                .loaded_step::<lse::Solver>(),
+            |store| Ok(Methods::Roux(roux::Solver::with_store(store)?)),
+        ),
+        Entry::new(
+            MethodInfo::new(
+                "thistlethwaite",
+                "Four phases, each in a smaller group, down to half turns",
+            )
+            .loaded_step::<thistlethwaite_eo::Solver>()
+            .loaded_step::<thistlethwaite_co::Solver>()
+            .loaded_step::<thistlethwaite_tetrads::Solver>()
+            .loaded_step::<thistlethwaite_half_turns::Solver>(),
+            |store| {
+                Ok(Methods::Thistlethwaite(thistlethwaite::Solver::with_store(
+                    store,
+                )?))
+            },
+        ),
+        Entry::new(
+            MethodInfo::new(
+                "two_phase",
+                "Kociemba's orientation then permutation phases",
+            )
+            .loaded_step::<orientation::Solver>()
+            .loaded_step::<permutation::Solver>(),
+            |store| Ok(Methods::TwoPhase(two_phase::Solver::with_store(store)?)),
+        ),
+        Entry::new(
+            MethodInfo::new("zz", "EOLine, left and right blocks, then OLL and PLL")
+                .loaded_step::<eo_line::Solver>()
+                .loaded_step::<zz_left_block::Solver>()
+                .loaded_step::<zz_right_block::Solver>()
+                .loaded_step::<oll::Solver>()
+                .loaded_step::<pll::Solver>(),
+            |store| Ok(Methods::Zz(zz::Solver::with_store(store)?)),
+        ),
+    ]
+}
+
+/// Make a method selectable by name, and so from the binaries. The method
+/// is built each time it is selected, loading its tables from the store.
+/// Registering a name again, built-in or not, replaces its method.
+pub fn register_method<M, F>(info: MethodInfo, constructor: F)
+where
+    M: Method + Send + Sync + 'static,
+    F: Fn(&TableStore) -> Result<M, MethodError> + Send + Sync + 'static,
+{
+    let entry = Entry::new(info, move |store| {
+        Ok(Methods::Custom(Arc::new(constructor(store)?)))
+    });
+    let mut registry = REGISTRY.write().unwrap_or_else(|error| error.into_inner());
+    match registry
+        .iter_mut()
+        .find(|registered| registered.info.name == entry.info.name)
+    {
+        Some(registered) => *registered = entry,
+        None => registry.push(entry),
+    }
+}
+
+/// Descriptions of the methods selectable by name, in registration order.
+#[must_use]
+pub fn methods() -> Vec<MethodInfo> {
+    REGISTRY
+        .read()
+        .unwrap_or_else(|error| error.into_inner())
+        .iter()
+        .map(|entry| entry.info.clone())
+        .collect()
+}
+
+/// Description of the method of the given name, or of the configuration
+/// file of the given path when it ends with `.ron`.
+pub fn method_info(name: &str) -> Result<MethodInfo, MethodError> {
+    if name.ends_with(".ron") {
+        return Ok(MethodConfig::read(Path::new(name))?.info());
+    }
+    REGISTRY
+        .read()
+        .unwrap_or_else(|error| error.into_inner())
+        .iter()
+        .find(|entry| entry.info.name == name)
+        .map(|entry| entry.info.clone())
+        .ok_or_else(|| MethodError::Unknown(name.to_string()))
+}
+
+/// Build the method of the given name, or of the configuration file of the
+/// given path when it ends with `.ron`, loading its tables from the store.
+pub fn build_method(name: &str, store: &TableStore) -> Result<Methods, MethodError> {
+    if name.ends_with(".ron") {
+        return Ok(Methods::Configurable(ConfigurableMethod::read(
+            Path::new(name),
+            store,
+        )?));
+    }
+    let constructor = REGISTRY
+        .read()
+        .unwrap_or_else(|error| error.into_inner())
+        .iter()
+        .find(|entry| entry.info.name == name)
+        .map(|entry| entry.constructor.clone())
+        .ok_or_else(|| MethodError::Unknown(name.to_string()))?;
+    // Built outside of the lock, so that constructors may register methods
+    constructor(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use cube::algorithms::parse_algorithm;
+
+    use super::*;
+    use crate::solvers::methods::from_method_name;
+
+    const TWO_GEN: &str = r#"(
+        name: "two gen",
+        stages: [(name: "all", goal: Solved, moves: "<R, U>", strategy: Ida(Depth(4)))],
+    )"#;
+
+    #[test]
+    fn select_registered_methods() {
+        assert!(matches!(
+            from_method_name("two_gen"),
+            Err(MethodError::Unknown(_))
+        ));
+        register_method(
+            MethodInfo::new("two_gen", "Any <R, U> state").step("all"),
+            |store| Ok(ConfigurableMethod::from_ron(TWO_GEN, store)?),
+        );
+        assert_eq!(method_info("two_gen").unwrap().steps, ["all"]);
+
+        let method = from_method_name("two_gen").unwrap();
+        assert!(matches!(method, Methods::Custom(_)));
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&parse_algorithm("R U R' U'"));
+        cube.execute_algorithm(&method.solve(&cube).unwrap());
+        assert_eq!(cube, Cube::default());
+    }
+
+    #[test]
+    fn describe_without_building() {
+        let directory =
+            std::env::temp_dir().join(format!("kubin-test-registry-{}", std::process::id()));
+        let store = TableStore::new(&directory).generate_missing(false);
+        let names = methods()
+            .into_iter()
+            .map(|info| info.name)
+            .collect::<Vec<_>>();
+        for name in ["beginner", "cfop", "roux", "thistlethwaite", "zz"] {
+            assert!(names.iter().any(|registered| registered == name));
+            let info = method_info(name).unwrap();
+            assert!(info.tables_size().is_some());
+            assert!(info.tables_generation_time().is_some());
+        }
+
+        let cfop = method_info("cfop").unwrap();
+        assert_eq!(cfop.steps, ["cross", "f2l", "oll", "pll"]);
+        assert!(cfop.tables_size().is_some());
+        // The pattern databases take seconds, the tables of algorithms much
+        // less
+        let oll = cfop
+            .tables
+            .iter()
+            .find(|table| table.kind == "oll")
+            .unwrap();
+        assert!(oll.generation_time.unwrap() < Duration::from_millis(1));
+        assert!(cfop.tables_generation_time().unwrap() > Duration::from_secs(1));
+        assert!(!cfop.is_cached(&store));
+        assert!(matches!(
+            build_method("cfop", &store),
+            Err(MethodError::Tables(TableError::Missing(_)))
+        ));
+        assert!(!directory.exists());
+
+        let thistlethwaite = method_info("thistlethwaite").unwrap();
+        build_method("thistlethwaite", &store.clone().generate_missing(true)).unwrap();
+        assert!(thistlethwaite.is_cached(&store));
+        // The sizes only miss the header of the cached files and the moves
+        // of the searches
+        for table in &thistlethwaite.tables {
+            let cached = std::fs::metadata(store.path(&table.kind)).unwrap().len();
+            assert!(cached.abs_diff(table.size.unwrap()) < 256);
+        }
+        std::fs::remove_dir_all(directory).unwrap();
+
+        assert!(method_info("beginner").unwrap().is_cached(&store));
+    }
+}
@@ -0,0 +1,116 @@
+use cube::Cube;
+
+use crate::solvers::{
+    enumeration::{EnumerationOptions, MethodSolutions},
+    options::{Budget, SolveOptions},
+    solver::{Method, Solution, SolveError, Step},
+    steps::{cmll, first_block, lse, second_block},
+    table_store::{TableError, TableStore},
+};
+
+#[derive(Clone)]
+pub struct Solver {
+    first_block_solver: first_block::Solver,
+    second_block_solver: second_block::Solver,
+    cmll_solver: cmll::Solver,
+    lse_solver: lse::Solver,
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Solver {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_store(&TableStore::default())
+            .expect("Missing tables are generated by the default store")
+    }
+
+    /// Load the tables of the steps from the given store.
+    pub fn with_store(store: &TableStore) -> Result<Self, TableError> {
+        Ok(Self {
+            first_block_solver: first_block::Solver::load(store)?,
+            second_block_solver: second_block::Solver::load(store)?,
+            cmll_solver: cmll::Solver::load(store)?,
+            lse_solver: lse::Solver::load(store)?,
+        })
+    }
+}
+
+impl Method for Solver {
+    fn try_solve_with(&self, cube: &Cube, options: &SolveOptions) -> Result<Solution, SolveError> {
+        cube.validate()?;
+        let budget = Budget::new(options);
+        let mut cube = cube.clone();
+        let mut solution = Solution::default();
+
+        self.first_block_solver
+            .apply(&mut cube, &mut solution, &budget)?;
+        self.second_block_solver
+            .apply(&mut cube, &mut solution, &budget)?;
+        self.cmll_solver.apply(&mut cube, &mut solution, &budget)?;
+        self.lse_solver
+            .apply_in_parts(&mut cube, &mut solution, &budget)?;
+
+        Ok(solution)
+    }
+
+    fn solutions<'a>(
+        &'a self,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Result<MethodSolutions<'a>, SolveError> {
+        cube.validate()?;
+        Ok(MethodSolutions::new(
+            vec![
+                &self.first_block_solver,
+                &self.second_block_solver,
+                &self.cmll_solver,
+                &self.lse_solver,
+            ],
+            cube,
+            options,
+        ))
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.first_block_solver.memory_usage()
+            + self.second_block_solver.memory_usage()
+            + self.cmll_solver.memory_usage()
+            + self.lse_solver.memory_usage()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cube::algorithms::parse_algorithm;
+
+    use super::*;
+
+    #[test]
+    fn solve_scramble() {
+        let solver = Solver {
+            first_block_solver: first_block::Solver::generate(),
+            second_block_solver: second_block::Solver::generate(),
+            cmll_solver: cmll::Solver::generate(),
+            lse_solver: lse::Solver::generate(),
+        };
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&parse_algorithm(
+            "D' R2 U' F2 D' L2 U2 B2 R2 U' L' B' D2 L' F' U2 R U' F' L",
+        ));
+
+        let solution = solver.try_solve(&cube).unwrap();
+        let labels = solution
+            .stages
+            .iter()
+            .map(|stage| stage.label())
+            .collect::<Vec<_>>();
+        assert_eq!(labels[3..], ["EO", "UL/UR", "M slice"]);
+        cube.execute_algorithm(&solution.moves());
+        assert_eq!(cube, Cube::default());
+    }
+}
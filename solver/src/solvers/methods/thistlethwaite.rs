@@ -0,0 +1,119 @@
+//! # Thistlethwaite's algorithm.
+//!
+//! Four phases, each bringing the cube into a smaller group generated by
+//! fewer moves, until only half turns are left. The solutions are long, but
+//! each phase only needs a small table.
+
+use cube::Cube;
+
+use crate::solvers::{
+    enumeration::{EnumerationOptions, MethodSolutions},
+    options::{Budget, SolveOptions},
+    solver::{Method, Solution, SolveError, Step},
+    steps::{
+        thistlethwaite_co, thistlethwaite_eo, thistlethwaite_half_turns, thistlethwaite_tetrads,
+    },
+    table_store::{TableError, TableStore},
+};
+
+#[derive(Clone)]
+pub struct Solver {
+    edge_orientation_solver: thistlethwaite_eo::Solver,
+    corner_orientation_solver: thistlethwaite_co::Solver,
+    tetrads_solver: thistlethwaite_tetrads::Solver,
+    half_turns_solver: thistlethwaite_half_turns::Solver,
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Solver {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_store(&TableStore::default())
+            .expect("Missing tables are generated by the default store")
+    }
+
+    /// Load the tables of the steps from the given store.
+    pub fn with_store(store: &TableStore) -> Result<Self, TableError> {
+        Ok(Self {
+            edge_orientation_solver: thistlethwaite_eo::Solver::load(store)?,
+            corner_orientation_solver: thistlethwaite_co::Solver::load(store)?,
+            tetrads_solver: thistlethwaite_tetrads::Solver::load(store)?,
+            half_turns_solver: thistlethwaite_half_turns::Solver::load(store)?,
+        })
+    }
+}
+
+impl Method for Solver {
+    fn try_solve_with(&self, cube: &Cube, options: &SolveOptions) -> Result<Solution, SolveError> {
+        cube.validate()?;
+        let budget = Budget::new(options);
+        let mut cube = cube.clone();
+        let mut solution = Solution::default();
+
+        self.edge_orientation_solver
+            .apply(&mut cube, &mut solution, &budget)?;
+        self.corner_orientation_solver
+            .apply(&mut cube, &mut solution, &budget)?;
+        self.tetrads_solver
+            .apply(&mut cube, &mut solution, &budget)?;
+        self.half_turns_solver
+            .apply(&mut cube, &mut solution, &budget)?;
+
+        Ok(solution)
+    }
+
+    fn solutions<'a>(
+        &'a self,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Result<MethodSolutions<'a>, SolveError> {
+        cube.validate()?;
+        Ok(MethodSolutions::new(
+            vec![
+                &self.edge_orientation_solver,
+                &self.corner_orientation_solver,
+                &self.tetrads_solver,
+                &self.half_turns_solver,
+            ],
+            cube,
+            options,
+        ))
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.edge_orientation_solver.memory_usage()
+            + self.corner_orientation_solver.memory_usage()
+            + self.tetrads_solver.memory_usage()
+            + self.half_turns_solver.memory_usage()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cube::algorithms::parse_algorithm;
+
+    use super::*;
+
+    #[test]
+    fn solve_scramble() {
+        let solver = Solver {
+            edge_orientation_solver: thistlethwaite_eo::Solver::generate(),
+            corner_orientation_solver: thistlethwaite_co::Solver::generate(),
+            tetrads_solver: thistlethwaite_tetrads::Solver::generate(),
+            half_turns_solver: thistlethwaite_half_turns::Solver::generate(),
+        };
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&parse_algorithm(
+            "D' R2 U' F2 D' L2 U2 B2 R2 U' L' B' D2 L' F' U2 R U' F' L",
+        ));
+
+        let solution = solver.try_solve(&cube).unwrap();
+        cube.execute_algorithm(&solution.moves());
+        assert_eq!(cube, Cube::default());
+    }
+}
@@ -1,8 +1,11 @@
-use cube::{algorithms::Move, Cube};
+use cube::Cube;
 
 use crate::solvers::{
-    solver::{Method, Step},
+    enumeration::{EnumerationOptions, MethodSolutions},
+    options::{Budget, SolveOptions},
+    solver::{Method, Solution, SolveError, Step},
     steps::{orientation, permutation},
+    table_store::{TableError, TableStore},
 };
 
 #[derive(Clone)]
@@ -20,27 +23,54 @@ impl Default for Solver {
 impl Solver {
     #[must_use]
     pub fn new() -> Self {
-        Self {
-            orientation_solver: orientation::Solver::new("/tmp/orientation_solver.ron"),
-            permutation_solver: permutation::Solver::new("/tmp/permutation_solver.ron"),
-        }
+        Self::with_store(&TableStore::default())
+            .expect("Missing tables are generated by the default store")
+    }
+
+    /// Load the tables of the steps from the given store.
+    pub fn with_store(store: &TableStore) -> Result<Self, TableError> {
+        Ok(Self {
+            orientation_solver: orientation::Solver::load(store)?,
+            permutation_solver: permutation::Solver::load(store)?,
+        })
     }
 }
 
 impl Method for Solver {
-    fn solve(&self, cube: &Cube) -> Vec<Move> {
+    fn try_solve_with(&self, cube: &Cube, options: &SolveOptions) -> Result<Solution, SolveError> {
+        cube.validate()?;
+        let budget = Budget::new(options);
         let mut cube = cube.clone();
+        let mut solution = Solution::default();
 
-        let orientation_solution = self.orientation_solver.solve(&cube);
-        cube.execute_algorithm(&orientation_solution);
+        self.orientation_solver
+            .apply(&mut cube, &mut solution, &budget)?;
+        self.permutation_solver
+            .apply(&mut cube, &mut solution, &budget)?;
 
-        let permutation_solution = self.permutation_solver.solve(&cube);
-        cube.execute_algorithm(&permutation_solution);
+        if cube != Cube::default() {
+            return Err(SolveError::UnreachableCase {
+                step: permutation::Solver::NAME,
+            });
+        }
 
-        assert_eq!(cube, Cube::default());
+        Ok(solution)
+    }
+
+    fn solutions<'a>(
+        &'a self,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Result<MethodSolutions<'a>, SolveError> {
+        cube.validate()?;
+        Ok(MethodSolutions::new(
+            vec![&self.orientation_solver, &self.permutation_solver],
+            cube,
+            options,
+        ))
+    }
 
-        let mut solution = orientation_solution;
-        solution.extend(permutation_solution);
-        solution
+    fn memory_usage(&self) -> usize {
+        self.orientation_solver.memory_usage() + self.permutation_solver.memory_usage()
     }
 }
@@ -0,0 +1,271 @@
+use cube::Cube;
+
+use crate::solvers::{
+    enumeration::{EnumerationOptions, MethodSolutions, StepSolutions},
+    options::{Budget, SolveOptions},
+    solver::{Method, Solution, SolveError, Step},
+    steps::{
+        coll, eo_cross, eo_line, oll, one_look_last_layer, pll, zbll, zz_left_block, zz_right_block,
+    },
+    table_store::{TableError, TableStore},
+};
+
+/// First step of the ZZ method, orienting all the edges.
+#[derive(Clone)]
+enum EdgeOrientation {
+    Line(eo_line::Solver),
+    Cross(eo_cross::Solver),
+}
+
+impl EdgeOrientation {
+    fn as_step(&self) -> &dyn StepSolutions {
+        match self {
+            EdgeOrientation::Line(solver) => solver,
+            EdgeOrientation::Cross(solver) => solver,
+        }
+    }
+}
+
+/// Steps finishing the last layer, whose edges the ZZ method orients first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LastLayer {
+    /// OLL, which is one of the OCLL cases, then PLL
+    #[default]
+    OllPll,
+    /// COLL, then the PLL of the edges
+    CollEpll,
+    Zbll,
+    /// The algorithms of any last layer, with the edges oriented or not
+    OneLook,
+}
+
+#[derive(Clone)]
+enum LastLayerSolver {
+    OllPll(oll::Solver, pll::Solver),
+    CollEpll(coll::Solver, pll::Solver),
+    Zbll(zbll::Solver),
+    OneLook(one_look_last_layer::Solver),
+}
+
+impl LastLayerSolver {
+    fn load(last_layer: LastLayer, store: &TableStore) -> Result<Self, TableError> {
+        Ok(match last_layer {
+            LastLayer::OllPll => {
+                LastLayerSolver::OllPll(oll::Solver::load(store)?, pll::Solver::load(store)?)
+            }
+            LastLayer::CollEpll => {
+                LastLayerSolver::CollEpll(coll::Solver::load(store)?, pll::Solver::load(store)?)
+            }
+            LastLayer::Zbll => LastLayerSolver::Zbll(zbll::Solver::load(store)?),
+            LastLayer::OneLook => {
+                LastLayerSolver::OneLook(one_look_last_layer::Solver::load(store)?)
+            }
+        })
+    }
+
+    fn as_steps(&self) -> Vec<&dyn StepSolutions> {
+        match self {
+            LastLayerSolver::OllPll(oll_solver, pll_solver) => vec![oll_solver, pll_solver],
+            LastLayerSolver::CollEpll(coll_solver, pll_solver) => vec![coll_solver, pll_solver],
+            LastLayerSolver::Zbll(solver) => vec![solver],
+            LastLayerSolver::OneLook(solver) => vec![solver],
+        }
+    }
+
+    fn apply(
+        &self,
+        cube: &mut Cube,
+        solution: &mut Solution,
+        budget: &Budget,
+    ) -> Result<(), SolveError> {
+        match self {
+            LastLayerSolver::OllPll(oll_solver, pll_solver) => {
+                oll_solver.apply(cube, solution, budget)?;
+                pll_solver.apply(cube, solution, budget)
+            }
+            LastLayerSolver::CollEpll(coll_solver, pll_solver) => {
+                coll_solver.apply(cube, solution, budget)?;
+                pll_solver.apply(cube, solution, budget)
+            }
+            LastLayerSolver::Zbll(solver) => solver.apply(cube, solution, budget),
+            LastLayerSolver::OneLook(solver) => solver.apply(cube, solution, budget),
+        }
+    }
+
+    fn memory_usage(&self) -> usize {
+        match self {
+            LastLayerSolver::OllPll(oll_solver, pll_solver) => {
+                oll_solver.memory_usage() + pll_solver.memory_usage()
+            }
+            LastLayerSolver::CollEpll(coll_solver, pll_solver) => {
+                coll_solver.memory_usage() + pll_solver.memory_usage()
+            }
+            LastLayerSolver::Zbll(solver) => solver.memory_usage(),
+            LastLayerSolver::OneLook(solver) => solver.memory_usage(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Solver {
+    edge_orientation_solver: EdgeOrientation,
+    left_block_solver: zz_left_block::Solver,
+    right_block_solver: zz_right_block::Solver,
+    last_layer_solver: LastLayerSolver,
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Solver {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_store(&TableStore::default())
+            .expect("Missing tables are generated by the default store")
+    }
+
+    /// Load the tables of the steps from the given store, starting with
+    /// EOLine.
+    pub fn with_store(store: &TableStore) -> Result<Self, TableError> {
+        Self::load(EdgeOrientation::Line(eo_line::Solver::load(store)?), store)
+    }
+
+    /// Load the tables of the steps from the given store, starting with
+    /// EOCross instead of EOLine.
+    pub fn with_eo_cross(store: &TableStore) -> Result<Self, TableError> {
+        Self::load(
+            EdgeOrientation::Cross(eo_cross::Solver::load(store)?),
+            store,
+        )
+    }
+
+    fn load(
+        edge_orientation_solver: EdgeOrientation,
+        store: &TableStore,
+    ) -> Result<Self, TableError> {
+        Ok(Self {
+            edge_orientation_solver,
+            left_block_solver: zz_left_block::Solver::load(store)?,
+            right_block_solver: zz_right_block::Solver::load(store)?,
+            last_layer_solver: LastLayerSolver::load(LastLayer::OllPll, store)?,
+        })
+    }
+
+    /// Finish the last layer with the given steps, loading their tables from
+    /// the store.
+    pub fn with_last_layer(
+        mut self,
+        last_layer: LastLayer,
+        store: &TableStore,
+    ) -> Result<Self, TableError> {
+        self.last_layer_solver = LastLayerSolver::load(last_layer, store)?;
+        Ok(self)
+    }
+}
+
+impl Method for Solver {
+    fn try_solve_with(&self, cube: &Cube, options: &SolveOptions) -> Result<Solution, SolveError> {
+        cube.validate()?;
+        let budget = Budget::new(options);
+        let mut cube = cube.clone();
+        let mut solution = Solution::default();
+
+        match &self.edge_orientation_solver {
+            EdgeOrientation::Line(solver) => solver.apply(&mut cube, &mut solution, &budget)?,
+            EdgeOrientation::Cross(solver) => solver.apply(&mut cube, &mut solution, &budget)?,
+        }
+        self.left_block_solver
+            .apply(&mut cube, &mut solution, &budget)?;
+        self.right_block_solver
+            .apply(&mut cube, &mut solution, &budget)?;
+        self.last_layer_solver
+            .apply(&mut cube, &mut solution, &budget)?;
+
+        Ok(solution)
+    }
+
+    fn solutions<'a>(
+        &'a self,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Result<MethodSolutions<'a>, SolveError> {
+        cube.validate()?;
+        let mut steps: Vec<&dyn StepSolutions> = vec![
+            self.edge_orientation_solver.as_step(),
+            &self.left_block_solver,
+            &self.right_block_solver,
+        ];
+        steps.extend(self.last_layer_solver.as_steps());
+        Ok(MethodSolutions::new(steps, cube, options))
+    }
+
+    fn memory_usage(&self) -> usize {
+        let edge_orientation_memory = match &self.edge_orientation_solver {
+            EdgeOrientation::Line(solver) => solver.memory_usage(),
+            EdgeOrientation::Cross(solver) => solver.memory_usage(),
+        };
+        edge_orientation_memory
+            + self.left_block_solver.memory_usage()
+            + self.right_block_solver.memory_usage()
+            + self.last_layer_solver.memory_usage()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cube::algorithms::parse_algorithm;
+
+    use super::*;
+
+    #[test]
+    fn solve_scramble_from_eo_cross() {
+        let solver = Solver {
+            edge_orientation_solver: EdgeOrientation::Cross(eo_cross::Solver::generate()),
+            left_block_solver: zz_left_block::Solver::generate(),
+            right_block_solver: zz_right_block::Solver::generate(),
+            last_layer_solver: LastLayerSolver::OllPll(
+                oll::Solver::generate(),
+                pll::Solver::generate(),
+            ),
+        };
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&parse_algorithm(
+            "D' R2 U' F2 D' L2 U2 B2 R2 U' L' B' D2 L' F' U2 R U' F' L",
+        ));
+
+        let solution = solver.try_solve(&cube).unwrap();
+        cube.execute_algorithm(&solution.moves());
+        assert_eq!(cube, Cube::default());
+    }
+
+    #[test]
+    fn solve_scramble_with_one_look_last_layers() {
+        let edge_orientation_solver = EdgeOrientation::Line(eo_line::Solver::generate());
+        let left_block_solver = zz_left_block::Solver::generate();
+        let right_block_solver = zz_right_block::Solver::generate();
+        let mut scrambled = Cube::default();
+        scrambled.execute_algorithm(&parse_algorithm(
+            "R2 D2 B' U2 F' L2 F D2 B2 L2 U2 F' R' U B L' U' F2 L2 U' F'",
+        ));
+
+        let last_layers = [
+            LastLayerSolver::Zbll(zbll::Solver::generate()),
+            LastLayerSolver::CollEpll(coll::Solver::generate(), pll::Solver::generate()),
+        ];
+        for last_layer_solver in last_layers {
+            let solver = Solver {
+                edge_orientation_solver: edge_orientation_solver.clone(),
+                left_block_solver: left_block_solver.clone(),
+                right_block_solver: right_block_solver.clone(),
+                last_layer_solver,
+            };
+            let solution = solver.try_solve(&scrambled).unwrap();
+            let mut cube = scrambled.clone();
+            cube.execute_algorithm(&solution.moves());
+            assert_eq!(cube, Cube::default());
+        }
+    }
+}
@@ -1,6 +1,20 @@
-mod cube_subsets;
+pub mod algorithm_set;
+pub mod cube_subsets;
+pub mod enumeration;
+pub mod goal;
 mod ida_solver;
+pub mod last_layer;
 pub mod methods;
+pub mod move_set;
+pub mod options;
+mod pattern_database;
+pub mod progress;
+mod rotation;
+mod search_space;
+mod slice_cube;
+pub mod solution;
 pub mod solver;
-mod steps;
+pub mod steps;
+mod subgroup;
+pub mod table_store;
 mod utils;
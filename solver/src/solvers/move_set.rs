@@ -0,0 +1,896 @@
+//! # Solve a target with a restricted set of moves.
+//!
+//! Steps search with all the face moves, or with a list hardcoded for their
+//! method. [`MoveSetStep`] solves a [`Target`] with the moves of any
+//! [`MoveSet`], such as `<R, U>`, `<U, D, R2, L2, F2, B2>` or `<M, U>`, so
+//! that every solution stays in the subgroup they generate.
+//!
+//! Targets are [`PieceMask`]s, whose tracked pieces alone are solved. The
+//! search is IDA*, whose heuristic is a table of the states of the target's
+//! pieces around the states matching it, filled with a breadth-first search
+//! with the moves of the set. When the search goes through all the states
+//! the moves can reach, the table is complete and tells the cubes whose
+//! target cannot be reached before searching. Otherwise, the pieces of the
+//! target are still checked one by one against the slots and orientations
+//! the moves can bring them to, and the pieces it solves are checked all at
+//! once against the group the moves generate.
+
+use std::{collections::HashMap, fmt::Display, marker::PhantomData, str::FromStr};
+
+use cube::{
+    algorithms::{Move, ALL_MOVES},
+    Cube,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::solvers::{
+    cube_subsets::{BackLeftBlock, BackRightBlock, Cross, FrontLeftBlock, FrontRightBlock},
+    enumeration::EnumerationOptions,
+    goal::{PieceMask, Tracked},
+    ida_solver::IDAStepSolver,
+    options::{Budget, SolveOptions},
+    progress::{NoProgress, ProgressReporter, ProgressTracker},
+    search_space::{SearchMove, SearchState},
+    slice_cube::{SliceCube, SliceMove},
+    solver::{SolveError, Step},
+    subgroup::{self, Subgroup},
+    table_store::{fingerprint, TableError, TableStore},
+};
+
+/// Moves generated by a set of generators, such as `<R, U>`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct MoveSet {
+    generators: Vec<String>,
+    moves: Vec<SliceMove>,
+}
+
+/// Generator that a [`MoveSet`] cannot be made of.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MoveSetError {
+    /// Neither a face, `M` nor `r`, with an optional `2` or `'`
+    UnknownGenerator(String),
+    /// `M` and `r` are searched relative to the L layer, which must not move
+    SliceWithL,
+    Empty,
+}
+
+impl Display for MoveSetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveSetError::UnknownGenerator(generator) => {
+                write!(f, "unknown generator {generator}")
+            }
+            MoveSetError::SliceWithL => f.write_str("M and r cannot be combined with L"),
+            MoveSetError::Empty => f.write_str("no generator"),
+        }
+    }
+}
+
+impl std::error::Error for MoveSetError {}
+
+impl MoveSet {
+    /// All the face moves.
+    #[must_use]
+    pub fn all() -> Self {
+        "<U, D, F, B, R, L>".parse().expect("Faces are generators")
+    }
+
+    /// Parse generators written as `<R, U, F2>`, the angle brackets being
+    /// optional. A face or a slice generates its quarter and half turns, and
+    /// only its half turn when followed by `2`.
+    pub fn parse(text: &str) -> Result<Self, MoveSetError> {
+        let text = text.trim();
+        let text = text
+            .strip_prefix('<')
+            .and_then(|text| text.strip_suffix('>'))
+            .unwrap_or(text);
+        let mut set = Self {
+            generators: vec![],
+            moves: vec![],
+        };
+        for generator in text.split([',', ' ']).filter(|g| !g.is_empty()) {
+            let moves = generator_moves(generator)
+                .ok_or_else(|| MoveSetError::UnknownGenerator(generator.to_string()))?;
+            for move_ in moves {
+                if !set.moves.contains(&move_) {
+                    set.moves.push(move_);
+                }
+            }
+            set.generators.push(generator.to_string());
+        }
+        if set.moves.is_empty() {
+            return Err(MoveSetError::Empty);
+        }
+        if set.has_slice_moves()
+            && set
+                .moves
+                .iter()
+                .any(|move_| move_.layer() == Move::L.layer())
+        {
+            return Err(MoveSetError::SliceWithL);
+        }
+        Ok(set)
+    }
+
+    fn has_slice_moves(&self) -> bool {
+        self.moves
+            .iter()
+            .any(|move_| !matches!(move_, SliceMove::Face(_)))
+    }
+
+    fn face_moves(&self) -> Vec<Move> {
+        self.moves
+            .iter()
+            .filter_map(|move_| match move_ {
+                SliceMove::Face(move_) => Some(move_.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Moves of a generator, quarter turns first.
+fn generator_moves(generator: &str) -> Option<Vec<SliceMove>> {
+    let (name, half_turn_only) = match generator.strip_suffix('2') {
+        Some(name) => (name, true),
+        None => (generator.strip_suffix('\'').unwrap_or(generator), false),
+    };
+    let [quarter, half, inverse] = match name {
+        "M" => [SliceMove::M, SliceMove::M2, SliceMove::Mp],
+        "r" => [SliceMove::Rw, SliceMove::Rw2, SliceMove::Rwp],
+        _ => {
+            let face = ["U", "D", "F", "B", "R", "L"]
+                .iter()
+                .position(|face| *face == name)?;
+            [0, 1, 2].map(|turn| SliceMove::Face(ALL_MOVES[face * 3 + turn].clone()))
+        }
+    };
+    Some(if half_turn_only {
+        vec![half]
+    } else {
+        vec![quarter, half, inverse]
+    })
+}
+
+impl FromStr for MoveSet {
+    type Err = MoveSetError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::parse(text)
+    }
+}
+
+impl TryFrom<String> for MoveSet {
+    type Error = MoveSetError;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        Self::parse(&text)
+    }
+}
+
+impl From<MoveSet> for String {
+    fn from(moves: MoveSet) -> Self {
+        moves.to_string()
+    }
+}
+
+impl Display for MoveSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<{}>", self.generators.join(", "))
+    }
+}
+
+/// Pieces a [`MoveSetStep`] solves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Target {
+    /// The whole cube
+    Solved,
+    /// The edges of the D face
+    Cross,
+    /// The first two layers, with the D face down
+    F2l,
+    /// The first two layers, and the orientation of the last layer
+    Oll,
+    /// The orientation of all the edges, wherever they are
+    EdgeOrientation,
+    /// The orientation of all the pieces, wherever they are
+    Orientation,
+    /// The pieces of the mask, ignoring the others
+    Pieces(PieceMask),
+}
+
+impl Target {
+    /// Pieces of the target.
+    #[must_use]
+    pub fn mask(&self) -> PieceMask {
+        let f2l = || {
+            PieceMask::from_subset::<Cross>()
+                .union(&PieceMask::from_subset::<FrontRightBlock>())
+                .union(&PieceMask::from_subset::<FrontLeftBlock>())
+                .union(&PieceMask::from_subset::<BackLeftBlock>())
+                .union(&PieceMask::from_subset::<BackRightBlock>())
+        };
+        let orientation = PieceMask {
+            edges: [Tracked::ORIENTATION; 12],
+            corners: [Tracked::ORIENTATION; 8],
+        };
+        match self {
+            Target::Solved => PieceMask::solved(),
+            Target::Cross => PieceMask::from_subset::<Cross>(),
+            Target::F2l => f2l(),
+            Target::Oll => f2l().union(&orientation),
+            Target::EdgeOrientation => PieceMask {
+                edges: [Tracked::ORIENTATION; 12],
+                ..PieceMask::new()
+            },
+            Target::Orientation => orientation,
+            Target::Pieces(mask) => *mask,
+        }
+    }
+}
+
+/// How the search of a [`MoveSetStep`] estimates the moves left.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Pruning {
+    /// No table: iterative deepening, only practical for short solutions
+    None,
+    /// Table of the states up to the given number of moves from the target
+    Depth(usize),
+    /// Table of all the states the moves can reach, which must fit in
+    /// memory. Unreachable targets are found out without searching.
+    Complete,
+}
+
+/// State of a search, whose pieces are compared to the target.
+trait TargetState: SearchState {
+    /// State of the given pieces, with the centers aligned
+    fn from_pieces(cube: Cube) -> Self;
+    fn pieces(&self) -> &Cube;
+    /// Quarter turns of the centers away from the pieces
+    fn centers(&self) -> u8 {
+        0
+    }
+}
+
+impl TargetState for Cube {
+    fn from_pieces(cube: Cube) -> Self {
+        cube
+    }
+
+    fn pieces(&self) -> &Cube {
+        self
+    }
+}
+
+impl TargetState for SliceCube {
+    fn from_pieces(cube: Cube) -> Self {
+        SliceCube { cube, centers: 0 }
+    }
+
+    fn pieces(&self) -> &Cube {
+        &self.cube
+    }
+
+    fn centers(&self) -> u8 {
+        self.centers
+    }
+}
+
+/// IDA* search of a target with the moves of a set, over the states of `S`.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(bound = "")]
+struct Search<S: TargetState> {
+    moves: Vec<S::Move>,
+    mask: PieceMask,
+    pruning: Pruning,
+    /// Distance of the keys of the states around the target
+    table: HashMap<u128, u8>,
+    /// Depth of the breadth-first search that filled the table
+    table_depth: usize,
+    /// Whether the table holds all the states the moves can reach
+    complete: bool,
+    /// Slots and orientations each edge can reach, one bit per pair
+    edge_orbits: [u32; 12],
+    corner_orbits: [u32; 8],
+    /// Group generated by the moves, whose base starts with the pieces the
+    /// target solves
+    subgroup: Subgroup,
+    #[serde(skip)]
+    state: PhantomData<S>,
+}
+
+impl<S: TargetState> Default for Search<S> {
+    fn default() -> Self {
+        Self {
+            moves: vec![],
+            mask: PieceMask::solved(),
+            pruning: Pruning::None,
+            table: HashMap::new(),
+            table_depth: 0,
+            complete: false,
+            edge_orbits: [0; 12],
+            corner_orbits: [0; 8],
+            subgroup: Subgroup::default(),
+            state: PhantomData,
+        }
+    }
+}
+
+impl<S: TargetState + 'static> Search<S> {
+    fn new(
+        moves: Vec<S::Move>,
+        target: Target,
+        pruning: Pruning,
+        progress: &dyn ProgressReporter,
+    ) -> Self {
+        let mut search = Self {
+            moves,
+            mask: target.mask(),
+            pruning,
+            ..Self::default()
+        };
+        search.populate_heuristics(progress);
+        search
+    }
+
+    fn key(&self, state: &S) -> u128 {
+        self.mask.key(state.pieces(), state.centers())
+    }
+
+    /// Whether each tracked piece is in a slot and orientation its orbit
+    /// holds. Otherwise, no sequence of the moves can solve it.
+    fn in_orbits(&self, state: &S) -> bool {
+        let cube = state.pieces();
+        let edges = cube.edges.iter().enumerate().all(|(slot, edge)| {
+            let piece = edge.piece.clone() as usize;
+            in_orbit(
+                self.edge_orbits[piece],
+                self.mask.edges[piece],
+                (slot, edge.orientation),
+                (12, 2),
+            )
+        });
+        let corners = cube.corners.iter().enumerate().all(|(slot, corner)| {
+            let piece = corner.piece.clone() as usize;
+            in_orbit(
+                self.corner_orbits[piece],
+                self.mask.corners[piece],
+                (slot, corner.orientation),
+                (8, 3),
+            )
+        });
+        edges && corners
+    }
+
+    /// Whether a sequence of the moves brings the pieces the target solves
+    /// where they are in the state, all at once. Unlike their orbits, this
+    /// tells the states the moves cannot reach, such as a swap of two edges
+    /// and two corners with half turns only.
+    fn in_subgroup(&self, state: &S) -> bool {
+        let permutation = subgroup::permutation(state.pieces());
+        let images = self
+            .solved_points()
+            .map(|point| usize::from(permutation[point]))
+            .collect::<Vec<_>>();
+        self.subgroup.sends_base_to(&images)
+    }
+
+    /// Home points of the pieces whose position and orientation the target
+    /// solves.
+    fn solved_points(&self) -> impl Iterator<Item = usize> + '_ {
+        let edges = (0..12)
+            .filter(|edge| self.mask.edges[*edge] == Tracked::SOLVED)
+            .map(|edge| subgroup::edge_point(edge, 0));
+        let corners = (0..8)
+            .filter(|corner| self.mask.corners[*corner] == Tracked::SOLVED)
+            .map(|corner| subgroup::corner_point(corner, 0));
+        edges.chain(corners)
+    }
+
+    /// Slots and orientations each piece reaches with the moves, and the
+    /// group they generate.
+    fn fill_orbits(&mut self) {
+        let start = S::from_cube(&Cube::default());
+        let mut edges = vec![];
+        let mut corners = vec![];
+        let mut permutations = vec![];
+        for move_ in &self.moves {
+            let mut state = start.clone();
+            state.execute_move(move_);
+            let cube = state.pieces();
+            permutations.push(subgroup::permutation(cube));
+            edges.push(
+                cube.edges
+                    .clone()
+                    .map(|e| (e.piece as usize, e.orientation)),
+            );
+            corners.push(
+                cube.corners
+                    .clone()
+                    .map(|c| (c.piece as usize, c.orientation)),
+            );
+        }
+        self.edge_orbits = std::array::from_fn(|piece| orbit(piece, &edges, 2));
+        self.corner_orbits = std::array::from_fn(|piece| orbit(piece, &corners, 3));
+        let base = self.solved_points().collect::<Vec<_>>();
+        self.subgroup = Subgroup::new(&permutations, &base);
+    }
+}
+
+/// Whether a piece in the given slot and orientation is in its orbit, as far
+/// as it is tracked. The orbit has one bit per slot and orientation, out of
+/// `(slots, orientations)`.
+fn in_orbit(
+    orbit: u32,
+    tracked: Tracked,
+    (slot, orientation): (usize, u8),
+    (slots, orientations): (usize, u8),
+) -> bool {
+    let reachable = |slot: usize, orientation: u8| {
+        orbit & (1 << (slot * usize::from(orientations) + usize::from(orientation))) != 0
+    };
+    match (tracked.position, tracked.orientation) {
+        (true, true) => reachable(slot, orientation),
+        (true, false) => (0..orientations).any(|o| reachable(slot, o)),
+        (false, true) => (0..slots).any(|s| reachable(s, orientation)),
+        (false, false) => true,
+    }
+}
+
+/// Slots and orientations of a piece reached from its home with the moves,
+/// given as the piece and orientation change each move brings to each slot.
+fn orbit<const N: usize>(piece: usize, moves: &[[(usize, u8); N]], orientations: u8) -> u32 {
+    let bit = |slot: usize, orientation: u8| {
+        1 << (slot * usize::from(orientations) + usize::from(orientation))
+    };
+    let mut orbit = bit(piece, 0);
+    let mut stack = vec![(piece, 0)];
+    while let Some((slot, orientation)) = stack.pop() {
+        for pieces in moves {
+            let (next_slot, twist) = pieces
+                .iter()
+                .enumerate()
+                .find_map(|(next, (source, twist))| (*source == slot).then_some((next, *twist)))
+                .unwrap();
+            let next_orientation = (orientation + twist) % orientations;
+            if orbit & bit(next_slot, next_orientation) == 0 {
+                orbit |= bit(next_slot, next_orientation);
+                stack.push((next_slot, next_orientation));
+            }
+        }
+    }
+    orbit
+}
+
+impl<S: TargetState + 'static> IDAStepSolver for Search<S> {
+    const NAME: &'static str = "move_set";
+    type State = S;
+
+    fn get_all_moves(&self) -> &[S::Move] {
+        &self.moves
+    }
+
+    fn assess_distance(&self, state: &S) -> usize {
+        match self.table.get(&self.key(state)) {
+            Some(distance) => usize::from(*distance),
+            None if self.complete => usize::MAX,
+            None => self.table_depth + 1,
+        }
+    }
+
+    fn heuristics_memory_usage(&self) -> usize {
+        self.table.capacity() * std::mem::size_of::<(u128, u8)>()
+    }
+
+    /// The moves are given when building the search.
+    fn populate_candidate_moves(&mut self) {}
+
+    fn populate_heuristics(&mut self, progress: &dyn ProgressReporter) {
+        self.fill_orbits();
+        let max_depth = match self.pruning {
+            Pruning::None => 0,
+            Pruning::Depth(depth) => depth.min(usize::from(u8::MAX - 1)),
+            Pruning::Complete => usize::from(u8::MAX - 1),
+        };
+        let tracker = ProgressTracker::new(progress, "Generating lookup table for move_set", None);
+        let mut table = HashMap::new();
+        let mut frontier = vec![];
+        for cube in self.mask.goal_cubes() {
+            let state = S::from_pieces(cube);
+            table.insert(self.key(&state), 0);
+            frontier.push(state);
+        }
+        let mut depth = 0;
+        while !frontier.is_empty() && depth < max_depth {
+            let mut next = vec![];
+            for state in &frontier {
+                for move_ in &self.moves {
+                    let mut state = state.clone();
+                    state.execute_move(move_);
+                    let key = self.key(&state);
+                    if let std::collections::hash_map::Entry::Vacant(entry) = table.entry(key) {
+                        entry.insert(depth as u8 + 1);
+                        next.push(state);
+                        tracker.advance();
+                    }
+                }
+            }
+            frontier = next;
+            depth += 1;
+        }
+        tracker.finish(table.len() as u64);
+        self.complete = frontier.is_empty();
+        self.table_depth = depth;
+        self.table = table;
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+enum MoveSetSearch {
+    Face(Search<Cube>),
+    Slice(Search<SliceCube>),
+}
+
+/// Step solving a target with the moves of a set only, built with
+/// [`MoveSetStep::builder`].
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MoveSetStep {
+    moves: MoveSet,
+    target: Target,
+    search: MoveSetSearch,
+}
+
+/// Options of a [`MoveSetStep`].
+#[derive(Clone, Debug)]
+pub struct MoveSetStepBuilder {
+    moves: MoveSet,
+    target: Target,
+    pruning: Pruning,
+}
+
+impl MoveSetStepBuilder {
+    /// Pieces to solve, the whole cube by default.
+    #[must_use]
+    pub fn target(mut self, target: Target) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Heuristic of the search, a table of depth 5 by default.
+    #[must_use]
+    pub fn pruning(mut self, pruning: Pruning) -> Self {
+        self.pruning = pruning;
+        self
+    }
+
+    /// Generate the table of the step.
+    #[must_use]
+    pub fn build(self) -> MoveSetStep {
+        self.build_with_progress(&NoProgress)
+    }
+
+    /// Read the table of the step from the store, generating it if needed.
+    /// Each set of moves, target and pruning has a table kind of its own.
+    pub fn load(self, store: &TableStore) -> Result<MoveSetStep, TableError> {
+        let revision = self.revision();
+        let kind = format!("{}_{revision:016x}", MoveSetStep::NAME);
+        store.load_with(&kind, revision, |progress| {
+            self.build_with_progress(progress)
+        })
+    }
+
+    /// Fingerprint of the options the table is generated from.
+    fn revision(&self) -> u64 {
+        let key = ron::to_string(&(&self.moves, self.target, self.pruning))
+            .expect("Options are plain data");
+        fingerprint(&[&key])
+    }
+
+    /// Generate the table of the step, reporting its progress.
+    #[must_use]
+    pub fn build_with_progress(self, progress: &dyn ProgressReporter) -> MoveSetStep {
+        let search = if self.moves.has_slice_moves() {
+            MoveSetSearch::Slice(Search::new(
+                self.moves.moves.clone(),
+                self.target,
+                self.pruning,
+                progress,
+            ))
+        } else {
+            MoveSetSearch::Face(Search::new(
+                self.moves.face_moves(),
+                self.target,
+                self.pruning,
+                progress,
+            ))
+        };
+        MoveSetStep {
+            moves: self.moves,
+            target: self.target,
+            search,
+        }
+    }
+}
+
+impl MoveSetStep {
+    /// Step solving the whole cube with the given moves, until another
+    /// target is set.
+    #[must_use]
+    pub fn builder(moves: MoveSet) -> MoveSetStepBuilder {
+        MoveSetStepBuilder {
+            moves,
+            target: Target::Solved,
+            pruning: Pruning::Depth(5),
+        }
+    }
+
+    #[must_use]
+    pub fn moves(&self) -> &MoveSet {
+        &self.moves
+    }
+
+    #[must_use]
+    pub fn target(&self) -> Target {
+        self.target
+    }
+
+    /// Shortest sequence of the moves of the set solving the target.
+    pub fn solve(&self, cube: &Cube) -> Result<Vec<Move>, SolveError> {
+        self.solve_with(cube, &SolveOptions::default())
+    }
+
+    /// Shortest sequence of the moves of the set solving the target, within
+    /// the limits of the options.
+    pub fn solve_with(&self, cube: &Cube, options: &SolveOptions) -> Result<Vec<Move>, SolveError> {
+        cube.validate()?;
+        self.solve_within(cube, &Budget::new(options))
+    }
+
+    /// Shortest sequence of the moves of the set bringing the pieces of the
+    /// target where they are in `to`.
+    pub fn solve_to(&self, from: &Cube, to: &Cube) -> Result<Vec<Move>, SolveError> {
+        Step::solve_to(self, from, to)
+    }
+
+    fn is_reachable(&self, cube: &Cube) -> bool {
+        fn reachable<S: TargetState + 'static>(search: &Search<S>, cube: &Cube) -> bool {
+            let state = S::from_cube(cube);
+            search.in_orbits(&state)
+                && search.in_subgroup(&state)
+                && search.assess_distance(&state) != usize::MAX
+        }
+        match &self.search {
+            MoveSetSearch::Face(search) => reachable(search, cube),
+            MoveSetSearch::Slice(search) => reachable(search, cube),
+        }
+    }
+}
+
+impl Step for MoveSetStep {
+    const NAME: &'static str = "move_set";
+
+    /// The step of the default builder, which solves the whole cube with all
+    /// the moves. Other steps are loaded with [`MoveSetStepBuilder::load`].
+    fn load(store: &TableStore) -> Result<Self, TableError> {
+        Self::builder(MoveSet::all()).load(store)
+    }
+
+    fn generate() -> Self {
+        Self::builder(MoveSet::all()).build()
+    }
+
+    fn generate_with_progress(progress: &dyn ProgressReporter) -> Self {
+        Self::builder(MoveSet::all()).build_with_progress(progress)
+    }
+
+    fn solve(&self, cube: &Cube) -> Result<Vec<Move>, SolveError> {
+        MoveSetStep::solve(self, cube)
+    }
+
+    fn solve_within(&self, cube: &Cube, budget: &Budget) -> Result<Vec<Move>, SolveError> {
+        if !self.is_reachable(cube) {
+            return Err(SolveError::UnreachableTarget {
+                step: Self::NAME,
+                moves: self.moves.to_string(),
+            });
+        }
+        match &self.search {
+            MoveSetSearch::Face(search) => search.solve_within(cube, budget),
+            MoveSetSearch::Slice(search) => search.solve_within(cube, budget),
+        }
+    }
+
+    fn lower_bound(&self, cube: &Cube) -> usize {
+        if !self.is_reachable(cube) {
+            return usize::MAX;
+        }
+        match &self.search {
+            MoveSetSearch::Face(search) => Step::lower_bound(search, cube),
+            MoveSetSearch::Slice(search) => Step::lower_bound(search, cube),
+        }
+    }
+
+    fn solutions<'a>(
+        &'a self,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Box<dyn Iterator<Item = Vec<Move>> + 'a> {
+        if !self.is_reachable(cube) {
+            return Box::new(std::iter::empty());
+        }
+        match &self.search {
+            MoveSetSearch::Face(search) => Step::solutions(search, cube, options),
+            MoveSetSearch::Slice(search) => Step::solutions(search, cube, options),
+        }
+    }
+
+    fn memory_usage(&self) -> usize {
+        match &self.search {
+            MoveSetSearch::Face(search) => search.heuristics_memory_usage(),
+            MoveSetSearch::Slice(search) => search.heuristics_memory_usage(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use cube::algorithms::parse_algorithm;
+
+    use super::*;
+
+    fn scrambled(algorithm: &str) -> Cube {
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&parse_algorithm(algorithm));
+        cube
+    }
+
+    #[test]
+    fn parse_move_sets() {
+        let set = MoveSet::parse("<R,U>").unwrap();
+        assert_eq!(set.to_string(), "<R, U>");
+        assert_eq!(set.moves.len(), 6);
+        assert_eq!(MoveSet::parse("U D R2 L2 F2 B2").unwrap().moves.len(), 10);
+        assert_eq!(MoveSet::parse("<M, U>").unwrap().moves.len(), 6);
+        assert_eq!(
+            MoveSet::parse("<R, X>"),
+            Err(MoveSetError::UnknownGenerator("X".to_string()))
+        );
+        assert_eq!(MoveSet::parse("<M, L>"), Err(MoveSetError::SliceWithL));
+        assert_eq!(MoveSet::parse("<>"), Err(MoveSetError::Empty));
+    }
+
+    #[test]
+    fn solve_in_subgroups() {
+        let two_gen = MoveSetStep::builder("<R, U>".parse().unwrap()).build();
+        let cube = scrambled("R U R' U R U2 R'");
+        let solution = two_gen.solve(&cube).unwrap();
+        assert_eq!(solution.len(), 7);
+        let mut solved = cube.clone();
+        solved.execute_algorithm(&solution);
+        assert_eq!(solved, Cube::default());
+
+        let lse = MoveSetStep::builder("<M, U>".parse().unwrap()).build();
+        let mut cube = Cube::default();
+        let scramble = [SliceMove::Mp, SliceMove::Face(Move::U), SliceMove::M2]
+            .into_iter()
+            .chain(MoveSet::parse("<U, M>").unwrap().moves)
+            .collect::<Vec<_>>();
+        cube.execute_algorithm(&SliceCube::default().face_moves(&scramble));
+        let solution = lse.solve(&cube).unwrap();
+        let mut solved = cube.clone();
+        solved.execute_algorithm(&solution);
+        assert_eq!(solved, Cube::default());
+    }
+
+    #[test]
+    fn solve_targets() {
+        let cross = MoveSetStep::builder("<R, U, F>".parse().unwrap())
+            .target(Target::Cross)
+            .pruning(Pruning::Complete)
+            .build();
+        let cube = scrambled("F R U' F2 R' U F' R2");
+        let solution = cross.solve(&cube).unwrap();
+        let mut solved = cube.clone();
+        solved.execute_algorithm(&solution);
+        for edge in [cube::DF, cube::DR, cube::DB, cube::DL] {
+            assert_eq!(solved.edges[edge], Cube::default().edges[edge]);
+        }
+
+        let eo = MoveSetStep::builder("<U, D, F, B, R, L>".parse().unwrap())
+            .target(Target::EdgeOrientation)
+            .pruning(Pruning::Complete)
+            .build();
+        let solution = eo.solve(&scrambled("U F")).unwrap();
+        assert_eq!(solution.len(), 1);
+
+        let solution = eo
+            .solve_to(&Cube::default(), &cube::patterns::superflip())
+            .unwrap();
+        assert_eq!(solution.len(), 7);
+    }
+
+    #[test]
+    fn solve_piece_masks() {
+        let block = PieceMask::new()
+            .edges(&[cube::DB, cube::DL, cube::BL], Tracked::SOLVED)
+            .corners(&[cube::DBL], Tracked::SOLVED);
+        let step = MoveSetStep::builder(MoveSet::all())
+            .target(Target::Pieces(block))
+            .build();
+        let mut cube = scrambled("D' R2 U' F2 D' L2 U2 B2 R2 U' L' B' D2 L' F' U2 R U' F' L");
+        let solution = step.solve(&cube).unwrap();
+        assert!(solution.len() <= 9);
+        cube.execute_algorithm(&solution);
+        assert!(block.matches(&cube));
+
+        let edges = PieceMask::new().edges(&[cube::UF, cube::UR], Tracked::ORIENTATION);
+        let step = MoveSetStep::builder(MoveSet::all())
+            .target(Target::Pieces(edges))
+            .pruning(Pruning::Complete)
+            .build();
+        let mut cube = scrambled("F R U' B2 L");
+        let solution = step.solve(&cube).unwrap();
+        assert!(solution.len() <= 2);
+        cube.execute_algorithm(&solution);
+        assert!(edges.matches(&cube));
+    }
+
+    #[test]
+    fn cache_each_builder_apart() {
+        let directory =
+            std::env::temp_dir().join(format!("kubin-test-move-set-{}", std::process::id()));
+        let store = TableStore::new(&directory);
+        let two_gen = || MoveSetStep::builder("<R, U>".parse().unwrap()).pruning(Pruning::Depth(3));
+        let cross = two_gen().target(Target::Cross);
+        let revisions = [two_gen().revision(), cross.revision()];
+        assert_ne!(revisions[0], revisions[1]);
+
+        two_gen().load(&store).unwrap();
+        let step = cross.clone().load(&store).unwrap();
+        assert_eq!(step.target(), Target::Cross);
+        for revision in revisions {
+            let kind = format!("move_set_{revision:016x}");
+            assert!(store.is_cached(&kind, revision));
+        }
+        // The cached table is read back
+        let step = cross.load(&store.clone().generate_missing(false)).unwrap();
+        assert_eq!(step.target(), Target::Cross);
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn report_unreachable_targets() {
+        let two_gen = MoveSetStep::builder("<R, U>".parse().unwrap())
+            .pruning(Pruning::None)
+            .build();
+        assert!(matches!(
+            two_gen.solve(&scrambled("R F")),
+            Err(SolveError::UnreachableTarget { moves, .. }) if moves == "<R, U>"
+        ));
+
+        let half_turns = MoveSetStep::builder("<U2, R2>".parse().unwrap())
+            .target(Target::Cross)
+            .pruning(Pruning::Complete)
+            .build();
+        assert!(half_turns.solve(&scrambled("R2 U2 R2")).is_ok());
+        assert!(matches!(
+            half_turns.solve(&scrambled("R2 U R2")),
+            Err(SolveError::UnreachableTarget { .. })
+        ));
+
+        // Every piece stays in its orbit, but the depth 5 table misses the
+        // state, which the half turns cannot reach
+        let half_turns = MoveSetStep::builder("<U2, R2>".parse().unwrap()).build();
+        let mut cube = Cube::default();
+        cube.edges.swap(cube::UF, cube::UB);
+        cube.corners.swap(cube::URF, cube::ULB);
+        assert!(matches!(
+            half_turns.solve(&cube),
+            Err(SolveError::UnreachableTarget { .. })
+        ));
+        assert!(half_turns.solve(&scrambled("U2 R2 U2 R2 U2 R2")).is_ok());
+    }
+}
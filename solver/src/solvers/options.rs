@@ -0,0 +1,260 @@
+//! # Limits on the time and work spent solving a cube.
+//!
+//! Searches can take minutes on hard states, so callers can bound them with a
+//! deadline, a number of visited nodes, a search depth, or cancel them from
+//! another thread. The limits are shared by all the steps of a method.
+//!
+//! A single search can also be spread across several threads, and report its
+//! progress while it goes on.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use super::{
+    progress::{estimate_eta, Progress, ProgressReporter},
+    solver::SolveError,
+};
+
+/// Number of nodes visited between two checks of the clock.
+const CLOCK_CHECK_INTERVAL: u64 = 1024;
+
+/// Flag shared between a search and the threads that may cancel it.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct SolveOptions {
+    deadline: Option<Instant>,
+    max_nodes: Option<u64>,
+    max_depth: Option<usize>,
+    cancellation: Option<CancellationToken>,
+    threads: Option<usize>,
+    progress: Option<Arc<dyn ProgressReporter>>,
+}
+
+impl std::fmt::Debug for SolveOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SolveOptions")
+            .field("deadline", &self.deadline)
+            .field("max_nodes", &self.max_nodes)
+            .field("max_depth", &self.max_depth)
+            .field("cancellation", &self.cancellation)
+            .field("threads", &self.threads)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SolveOptions {
+    /// Options without any limit.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Give up once the given instant is reached.
+    #[must_use]
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Give up once the given duration has elapsed, starting now.
+    #[must_use]
+    pub fn timeout(self, timeout: Duration) -> Self {
+        self.deadline(Instant::now() + timeout)
+    }
+
+    /// Give up after visiting the given number of nodes, over all the steps.
+    #[must_use]
+    pub fn max_nodes(mut self, max_nodes: u64) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Do not look for solutions longer than the given number of moves in a
+    /// single step.
+    #[must_use]
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Give up once the token is cancelled.
+    #[must_use]
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Spread each search across the given number of threads. Solutions have
+    /// the same length as with a single thread, but may use other moves.
+    #[must_use]
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Report the number of visited nodes while searching. The total is the
+    /// node limit, if any.
+    #[must_use]
+    pub fn progress(mut self, progress: impl ProgressReporter + 'static) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+}
+
+/// Work left for a solve, shared by all its steps.
+pub struct Budget<'a> {
+    options: &'a SolveOptions,
+    nodes: AtomicU64,
+    start: Instant,
+}
+
+impl<'a> Budget<'a> {
+    #[must_use]
+    pub fn new(options: &'a SolveOptions) -> Self {
+        Self {
+            options,
+            nodes: AtomicU64::new(0),
+            start: Instant::now(),
+        }
+    }
+
+    /// Nodes visited so far by all the steps.
+    #[must_use]
+    pub fn nodes(&self) -> u64 {
+        self.nodes.load(Ordering::Relaxed)
+    }
+
+    /// Whether a solution of the given length is allowed.
+    pub(crate) fn allows_depth(&self, depth: usize) -> bool {
+        self.options.max_depth.is_none_or(|max| depth <= max)
+    }
+
+    /// Number of threads a search may use.
+    pub(crate) fn threads(&self) -> usize {
+        self.options.threads.unwrap_or(1).max(1)
+    }
+
+    /// Check that the budget is not exhausted before starting a step.
+    pub fn check(&self, step: &'static str) -> Result<(), SolveError> {
+        if self.is_cancelled() {
+            return Err(SolveError::Cancelled {
+                step,
+                partial: vec![],
+            });
+        }
+        if self.options.deadline.is_some_and(|d| Instant::now() >= d) {
+            return Err(SolveError::Timeout {
+                step,
+                partial: vec![],
+            });
+        }
+        Ok(())
+    }
+
+    /// Count a visited node, checking the limits from time to time.
+    #[inline]
+    pub fn visit(&self, step: &'static str) -> Result<(), SolveError> {
+        let nodes = self.nodes.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.options.max_nodes.is_some_and(|max| nodes > max) {
+            return Err(SolveError::Timeout {
+                step,
+                partial: vec![],
+            });
+        }
+        if nodes.is_multiple_of(CLOCK_CHECK_INTERVAL) {
+            self.report(step, nodes);
+            self.check(step)?;
+        }
+        Ok(())
+    }
+
+    fn report(&self, step: &'static str, nodes: u64) {
+        let Some(progress) = &self.options.progress else {
+            return;
+        };
+        let eta = estimate_eta(self.start, nodes, self.options.max_nodes);
+        let deadline = self
+            .options
+            .deadline
+            .map(|d| d.saturating_duration_since(Instant::now()));
+        progress.report(&Progress {
+            phase: step,
+            current: nodes,
+            total: self.options.max_nodes,
+            eta: match (eta, deadline) {
+                (Some(eta), Some(deadline)) => Some(eta.min(deadline)),
+                (eta, deadline) => eta.or(deadline),
+            },
+        });
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.options
+            .cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn node_limit() {
+        let options = SolveOptions::new().max_nodes(10);
+        let budget = Budget::new(&options);
+        for _ in 0..10 {
+            assert!(budget.visit("test").is_ok());
+        }
+        assert!(matches!(
+            budget.visit("test"),
+            Err(SolveError::Timeout { step: "test", .. })
+        ));
+    }
+
+    #[test]
+    fn cancellation() {
+        let token = CancellationToken::new();
+        let options = SolveOptions::new().cancellation(token.clone());
+        let budget = Budget::new(&options);
+        assert!(budget.check("test").is_ok());
+        token.cancel();
+        assert!(matches!(
+            budget.check("test"),
+            Err(SolveError::Cancelled { .. })
+        ));
+    }
+
+    #[test]
+    fn expired_deadline() {
+        let options = SolveOptions::new().deadline(Instant::now());
+        assert!(matches!(
+            Budget::new(&options).check("test"),
+            Err(SolveError::Timeout { .. })
+        ));
+    }
+}
@@ -0,0 +1,266 @@
+//! # Dense pattern databases for IDA* heuristics.
+//!
+//! A pattern database stores the distance to the solved state of every value
+//! of a subset of the search state. Values are stored in a plain array
+//! indexed by the dense rank of the subset, so that a lookup is a single
+//! array access and each entry takes a single byte.
+//!
+//! Databases are generated with a breadth-first search whose levels are
+//! spread across threads. An entry is only ever set to the depth of the
+//! current level, so the database does not depend on the number of threads.
+
+use std::{
+    marker::PhantomData,
+    sync::atomic::{AtomicU8, AtomicUsize, Ordering},
+    thread,
+};
+
+use cube::Cube;
+use serde::{Deserialize, Serialize};
+
+use crate::solvers::{
+    ida_solver::Heuristic,
+    progress::{ProgressReporter, ProgressTracker},
+    search_space::{SearchSpace, SearchState, StateSubset},
+};
+
+/// Marker for the entries that cannot be reached from the solved state.
+const UNREACHABLE: u8 = u8::MAX;
+
+/// Distance to the solved state of every value of the subset `T`, a
+/// [`Heuristic`] for any search whose states `T` is a subset of.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PatternDatabase<T> {
+    distances: Vec<u8>,
+    #[serde(skip)]
+    subset: PhantomData<T>,
+}
+
+impl<T> Default for PatternDatabase<T> {
+    fn default() -> Self {
+        Self {
+            distances: vec![],
+            subset: PhantomData,
+        }
+    }
+}
+
+impl<T> PatternDatabase<T> {
+    /// Fill the database with a breadth-first search over indices, using the
+    /// given moves and all the available cores.
+    pub fn generate<S>(moves: &[S::Move], name: &str, progress: &dyn ProgressReporter) -> Self
+    where
+        S: SearchState,
+        T: StateSubset<S>,
+    {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        Self::generate_with_threads::<S>(moves, name, threads, progress)
+    }
+
+    /// Fill the database with a breadth-first search over indices. Each level
+    /// of the search splits the array in chunks, and each thread expands the
+    /// entries of its chunks found at the previous level.
+    pub(crate) fn generate_with_threads<S>(
+        moves: &[S::Move],
+        name: &str,
+        threads: usize,
+        progress: &dyn ProgressReporter,
+    ) -> Self
+    where
+        S: SearchState,
+        T: StateSubset<S>,
+    {
+        let case_count = T::CASES;
+        let distances: Vec<AtomicU8> = (0..case_count)
+            .map(|_| AtomicU8::new(UNREACHABLE))
+            .collect();
+        distances[T::from_state(&S::from_cube(&Cube::default())).index()]
+            .store(0, Ordering::Relaxed);
+        let phase = format!("Generating lookup table for {name}");
+        let tracker = ProgressTracker::new(progress, &phase, Some(case_count as u64));
+        tracker.advance();
+        let threads = threads.max(1);
+        let chunk_size = case_count.div_ceil(threads * 16).max(1);
+
+        for depth in 0..UNREACHABLE - 1 {
+            let next_chunk = AtomicUsize::new(0);
+            let reached = AtomicUsize::new(0);
+            thread::scope(|scope| {
+                for _ in 0..threads {
+                    scope.spawn(|| loop {
+                        let start = next_chunk.fetch_add(1, Ordering::Relaxed) * chunk_size;
+                        if start >= case_count {
+                            break;
+                        }
+                        for index in start..(start + chunk_size).min(case_count) {
+                            if distances[index].load(Ordering::Relaxed) != depth {
+                                continue;
+                            }
+                            let state = T::state_from_index(index);
+                            for move_ in moves {
+                                let mut state = state.clone();
+                                state.execute_move(move_);
+                                let next = T::from_state(&state).index();
+                                let is_new = distances[next]
+                                    .compare_exchange(
+                                        UNREACHABLE,
+                                        depth + 1,
+                                        Ordering::Relaxed,
+                                        Ordering::Relaxed,
+                                    )
+                                    .is_ok();
+                                if is_new {
+                                    reached.fetch_add(1, Ordering::Relaxed);
+                                    tracker.advance();
+                                }
+                            }
+                        }
+                    });
+                }
+            });
+            if reached.into_inner() == 0 {
+                break;
+            }
+        }
+
+        let database = Self {
+            distances: distances.into_iter().map(AtomicU8::into_inner).collect(),
+            subset: PhantomData,
+        };
+        tracker.finish(database.reachable_cases() as u64);
+        database
+    }
+
+    /// Distance of the state's subset to the solved state, or `usize::MAX` if
+    /// the solved state cannot be reached from it.
+    #[inline]
+    pub fn distance<S>(&self, state: &S) -> usize
+    where
+        T: StateSubset<S>,
+    {
+        match self.distances.get(T::from_state(state).index()) {
+            Some(&distance) if distance != UNREACHABLE => usize::from(distance),
+            _ => usize::MAX,
+        }
+    }
+
+    /// Number of entries that were reached while generating the database.
+    pub(crate) fn reachable_cases(&self) -> usize {
+        self.distances.iter().filter(|d| **d != UNREACHABLE).count()
+    }
+
+    /// Memory used by the database, in bytes.
+    pub fn memory_usage(&self) -> usize {
+        std::mem::size_of::<Self>() + self.distances.capacity()
+    }
+
+    /// Size of the encoded database, in bytes: a byte per case of the
+    /// subset, after the length of the array.
+    pub(crate) fn encoded_size<S>() -> u64
+    where
+        T: StateSubset<S>,
+    {
+        (std::mem::size_of::<u64>() + T::CASES) as u64
+    }
+}
+
+impl<S, T> Heuristic<S> for PatternDatabase<T>
+where
+    S: SearchSpace,
+    T: StateSubset<S::State> + Clone + Send + Sync,
+{
+    fn generate(space: &S, progress: &dyn ProgressReporter) -> Self {
+        Self::generate::<S::State>(space.moves(), S::NAME, progress)
+    }
+
+    fn estimate(&self, state: &S::State) -> usize {
+        self.distance(state)
+    }
+
+    fn memory_usage(&self) -> usize {
+        PatternDatabase::memory_usage(self)
+    }
+
+    fn size() -> Option<u64> {
+        Some(Self::encoded_size::<S::State>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solvers::cube_subsets::{
+        CornerOrientation, CornerPermutation, Cross, EdgeInSlice, EdgeOrientation, EoLine,
+        HalfTurns, Orientation, Tetrads,
+    };
+    use crate::solvers::progress::NoProgress;
+    use cube::{algorithms::Move, subcases::CubeSubset};
+
+    fn assert_indices_round_trip<T: CubeSubset>() {
+        for index in (0..T::CASES).step_by(97) {
+            let cube = T::cube_from_index(index);
+            assert_eq!(CubeSubset::index(&T::from_cube(&cube)), index);
+        }
+    }
+
+    #[test]
+    fn indices_round_trip() {
+        assert_indices_round_trip::<CornerPermutation>();
+        assert_indices_round_trip::<Cross>();
+        assert_indices_round_trip::<EdgeInSlice>();
+        assert_indices_round_trip::<EoLine>();
+        assert_indices_round_trip::<Orientation>();
+        assert_indices_round_trip::<EdgeOrientation>();
+        assert_indices_round_trip::<CornerOrientation>();
+        assert_indices_round_trip::<Tetrads>();
+        assert_indices_round_trip::<HalfTurns>();
+    }
+
+    #[test]
+    fn generate_corner_permutation() {
+        let database = PatternDatabase::<CornerPermutation>::generate::<Cube>(
+            &cube::algorithms::ALL_MOVES,
+            "CP",
+            &NoProgress,
+        );
+        assert_eq!(
+            database.reachable_cases(),
+            <CornerPermutation as CubeSubset>::CASES
+        );
+        assert_eq!(database.distance(&Cube::default()), 0);
+        assert!(database.memory_usage() >= <CornerPermutation as CubeSubset>::CASES);
+
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&cube::algorithms::parse_algorithm("R U"));
+        assert_eq!(database.distance(&cube), 2);
+    }
+
+    #[test]
+    fn generation_does_not_depend_on_threads() {
+        let moves = cube::algorithms::ALL_MOVES;
+        let serial = PatternDatabase::<CornerPermutation>::generate_with_threads::<Cube>(
+            &moves,
+            "CP",
+            1,
+            &NoProgress,
+        );
+        let parallel = PatternDatabase::<CornerPermutation>::generate_with_threads::<Cube>(
+            &moves,
+            "CP",
+            4,
+            &NoProgress,
+        );
+        assert_eq!(serial.distances, parallel.distances);
+    }
+
+    #[test]
+    fn unreachable_entries() {
+        let moves = [Move::U, Move::U2, Move::Up, Move::D, Move::D2, Move::Dp];
+        let database = PatternDatabase::<EdgeInSlice>::generate::<Cube>(&moves, "EIS", &NoProgress);
+        assert_eq!(database.reachable_cases(), 1);
+
+        let mut cube = Cube::default();
+        cube.execute_move(&Move::R);
+        assert_eq!(database.distance(&cube), usize::MAX);
+    }
+}
@@ -0,0 +1,296 @@
+//! # Report the progress of table generation and long solves.
+//!
+//! Library code never writes to stdout: it hands its progress to a
+//! [`ProgressReporter`], which may draw a spinner on the terminal, forward it
+//! to the `log` facade or, with the `tracing` feature, to `tracing`
+//! subscribers, or drop it.
+
+use std::{
+    io::{IsTerminal, Write},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+/// Number of work items between two reports of a tracker.
+const REPORT_INTERVAL: u64 = 1000;
+
+/// Snapshot of the progress of a phase, such as generating a table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Progress<'a> {
+    pub phase: &'a str,
+    pub current: u64,
+    /// Total amount of work of the phase, when it is known
+    pub total: Option<u64>,
+    /// Estimated time left, when the total is known
+    pub eta: Option<Duration>,
+}
+
+/// Receives the progress of long operations. Reporters may be called from
+/// several threads at once.
+pub trait ProgressReporter: Send + Sync {
+    /// Called regularly while a phase goes on.
+    fn report(&self, progress: &Progress);
+
+    /// Called once when a phase is over.
+    fn finish(&self, progress: &Progress) {
+        self.report(progress);
+    }
+}
+
+/// Reporter ignoring the progress.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoProgress;
+
+impl ProgressReporter for NoProgress {
+    fn report(&self, _progress: &Progress) {}
+}
+
+/// Reporter drawing a spinner on stderr. When stderr is not a terminal, only
+/// the end of each phase is printed.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TerminalProgress;
+
+impl ProgressReporter for TerminalProgress {
+    fn report(&self, progress: &Progress) {
+        let mut stderr = std::io::stderr();
+        if !stderr.is_terminal() {
+            return;
+        }
+        let icon = ["◜", "◝", "◞", "◟"][(progress.current / REPORT_INTERVAL % 4) as usize];
+        // Errors are ignored: progress is not worth failing the operation.
+        let _ = write!(stderr, "\x1b[2K{} {icon}\r", describe(progress));
+        let _ = stderr.flush();
+    }
+
+    fn finish(&self, progress: &Progress) {
+        let mut stderr = std::io::stderr();
+        let clear = if stderr.is_terminal() { "\x1b[2K" } else { "" };
+        let _ = writeln!(stderr, "{clear}{} ✅", describe(progress));
+    }
+}
+
+/// Reporter forwarding the progress to the `log` facade: updates at the
+/// debug level, and the end of each phase at the info level.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LogProgress;
+
+impl ProgressReporter for LogProgress {
+    fn report(&self, progress: &Progress) {
+        log::debug!("{}", describe(progress));
+    }
+
+    fn finish(&self, progress: &Progress) {
+        log::info!("{}", describe(progress));
+    }
+}
+
+/// Reporter emitting the progress as `tracing` events, whose fields are the
+/// phase, the count, the total and the ETA in seconds: updates at the debug
+/// level, and the end of each phase at the info level.
+#[cfg(feature = "tracing")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TracingProgress;
+
+#[cfg(feature = "tracing")]
+impl ProgressReporter for TracingProgress {
+    fn report(&self, progress: &Progress) {
+        tracing::debug!(
+            phase = progress.phase,
+            current = progress.current,
+            total = progress.total,
+            eta = progress.eta.map(|eta| eta.as_secs_f64()),
+            "progress"
+        );
+    }
+
+    fn finish(&self, progress: &Progress) {
+        tracing::info!(
+            phase = progress.phase,
+            current = progress.current,
+            total = progress.total,
+            "finished"
+        );
+    }
+}
+
+/// Human readable description of the progress, such as `Cross: 10 / 100 (ETA 2s)`.
+fn describe(progress: &Progress) -> String {
+    let mut description = format!("{}: {}", progress.phase, progress.current);
+    if let Some(total) = progress.total {
+        description += &format!(" / {total}");
+    }
+    if let Some(eta) = progress.eta {
+        description += &format!(" (ETA {}s)", eta.as_secs());
+    }
+    description
+}
+
+/// Time left to reach `total`, extrapolated from the time spent so far.
+pub(crate) fn estimate_eta(start: Instant, current: u64, total: Option<u64>) -> Option<Duration> {
+    let total = total?;
+    if current == 0 {
+        return None;
+    }
+    let left = total.saturating_sub(current);
+    Some(start.elapsed().mul_f64(left as f64 / current as f64))
+}
+
+/// Count the work done in a phase, possibly from several threads, and report
+/// it every `REPORT_INTERVAL` items.
+pub(crate) struct ProgressTracker<'a> {
+    reporter: &'a dyn ProgressReporter,
+    phase: &'a str,
+    total: Option<u64>,
+    start: Instant,
+    current: AtomicU64,
+}
+
+impl<'a> ProgressTracker<'a> {
+    pub(crate) fn new(
+        reporter: &'a dyn ProgressReporter,
+        phase: &'a str,
+        total: Option<u64>,
+    ) -> Self {
+        Self {
+            reporter,
+            phase,
+            total,
+            start: Instant::now(),
+            current: AtomicU64::new(0),
+        }
+    }
+
+    /// Count one more work item.
+    #[inline]
+    pub(crate) fn advance(&self) {
+        let current = self.current.fetch_add(1, Ordering::Relaxed) + 1;
+        if current.is_multiple_of(REPORT_INTERVAL) {
+            self.reporter.report(&self.progress(current));
+        }
+    }
+
+    /// Report the end of the phase, with the final count of work items.
+    pub(crate) fn finish(&self, current: u64) {
+        self.reporter.finish(&Progress {
+            eta: None,
+            ..self.progress(current)
+        });
+    }
+
+    fn progress(&self, current: u64) -> Progress<'a> {
+        Progress {
+            phase: self.phase,
+            current,
+            total: self.total,
+            eta: estimate_eta(self.start, current, self.total),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingProgress {
+        reports: Mutex<Vec<(u64, bool)>>,
+    }
+
+    impl ProgressReporter for RecordingProgress {
+        fn report(&self, progress: &Progress) {
+            self.reports.lock().unwrap().push((progress.current, false));
+        }
+
+        fn finish(&self, progress: &Progress) {
+            self.reports.lock().unwrap().push((progress.current, true));
+        }
+    }
+
+    #[test]
+    fn report_at_intervals() {
+        let reporter = RecordingProgress::default();
+        let tracker = ProgressTracker::new(&reporter, "test", Some(2500));
+        for _ in 0..2500 {
+            tracker.advance();
+        }
+        tracker.finish(2500);
+        assert_eq!(
+            *reporter.reports.lock().unwrap(),
+            [(1000, false), (2000, false), (2500, true)]
+        );
+    }
+
+    #[test]
+    fn describe_progress() {
+        let progress = Progress {
+            phase: "Cross",
+            current: 10,
+            total: Some(100),
+            eta: Some(Duration::from_secs(2)),
+        };
+        assert_eq!(describe(&progress), "Cross: 10 / 100 (ETA 2s)");
+    }
+
+    /// Subscriber recording the fields of the events.
+    #[cfg(feature = "tracing")]
+    #[derive(Default)]
+    struct RecordingSubscriber {
+        fields: Mutex<Vec<String>>,
+    }
+
+    #[cfg(feature = "tracing")]
+    impl tracing::field::Visit for &RecordingSubscriber {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            let field = format!("{}={value:?}", field.name());
+            self.fields.lock().unwrap().push(field);
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    impl tracing::Subscriber for RecordingSubscriber {
+        fn enabled(&self, _metadata: &tracing::Metadata) -> bool {
+            true
+        }
+
+        fn new_span(&self, _span: &tracing::span::Attributes) -> tracing::span::Id {
+            tracing::span::Id::from_u64(1)
+        }
+
+        fn record(&self, _span: &tracing::span::Id, _values: &tracing::span::Record) {}
+
+        fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+
+        fn event(&self, event: &tracing::Event) {
+            event.record(&mut &*self);
+        }
+
+        fn enter(&self, _span: &tracing::span::Id) {}
+
+        fn exit(&self, _span: &tracing::span::Id) {}
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn emit_tracing_events() {
+        let subscriber = std::sync::Arc::new(RecordingSubscriber::default());
+        tracing::subscriber::with_default(subscriber.clone(), || {
+            TracingProgress.report(&Progress {
+                phase: "Cross",
+                current: 10,
+                total: Some(100),
+                eta: Some(Duration::from_secs(2)),
+            });
+        });
+        assert_eq!(
+            *subscriber.fields.lock().unwrap(),
+            [
+                "message=progress",
+                "phase=\"Cross\"",
+                "current=10",
+                "total=100",
+                "eta=2.0"
+            ]
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! # Hold the cube with another face on D.
+//!
+//! The cube has no centers, so a rotation of the whole cube relabels its
+//! slots and its moves. The relabelling of the slots is found from the one of
+//! the moves: a rotated cube must be reached by the rotated moves of the
+//! original cube.
+
+use std::fmt::Display;
+
+use cube::{
+    algorithms::{Move, ALL_MOVES},
+    Cube,
+};
+use serde::{Deserialize, Serialize};
+
+/// Face of the cube, which the steps solving the cross hold on D.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Face {
+    U,
+    D,
+    F,
+    B,
+    R,
+    L,
+}
+
+pub const FACES: [Face; 6] = [Face::U, Face::D, Face::F, Face::B, Face::R, Face::L];
+
+impl Display for Face {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl Face {
+    /// Rotation bringing the face to D, in the usual notation.
+    #[must_use]
+    pub fn rotation(self) -> &'static str {
+        match self {
+            Face::U => "x2",
+            Face::D => "",
+            Face::F => "x'",
+            Face::B => "x",
+            Face::R => "z",
+            Face::L => "z'",
+        }
+    }
+
+    /// Face where the rotation bringing this face to D moves each face, in
+    /// the order of the layers of the moves.
+    fn rotated_faces(self) -> [usize; 6] {
+        const U: usize = 0;
+        const D: usize = 1;
+        const F: usize = 2;
+        const B: usize = 3;
+        const R: usize = 4;
+        const L: usize = 5;
+        match self {
+            Face::U => [D, U, B, F, R, L],
+            Face::D => [U, D, F, B, R, L],
+            Face::F => [F, B, D, U, R, L],
+            Face::B => [B, F, U, D, R, L],
+            Face::R => [R, L, F, B, D, U],
+            Face::L => [L, R, F, B, U, D],
+        }
+    }
+}
+
+/// Pieces of a cube as the slot each one comes from, with its orientation.
+type Pieces<const N: usize> = [(usize, u8); N];
+
+#[derive(Clone, Debug)]
+pub(crate) struct Rotation {
+    face: Face,
+    /// Move of the original cube for each move of the rotated cube
+    original_moves: [Move; 18],
+    edges: Pieces<12>,
+    corners: Pieces<8>,
+}
+
+impl Rotation {
+    pub(crate) fn new(face: Face) -> Self {
+        let faces = face.rotated_faces();
+        let rotated_move = |move_: &Move| {
+            let index = move_.clone() as usize;
+            ALL_MOVES[faces[index / 3] * 3 + index % 3].clone()
+        };
+        let mut original_moves = ALL_MOVES;
+        for move_ in &ALL_MOVES {
+            original_moves[rotated_move(move_) as usize] = move_.clone();
+        }
+        let moves = ALL_MOVES
+            .iter()
+            .map(|move_| {
+                let mut original = Cube::default();
+                original.execute_move(move_);
+                let mut rotated = Cube::default();
+                rotated.execute_move(&rotated_move(move_));
+                (original, rotated)
+            })
+            .collect::<Vec<_>>();
+        Self {
+            face,
+            original_moves,
+            edges: relabelling(&moves, 2, edge_pieces),
+            corners: relabelling(&moves, 3, corner_pieces),
+        }
+    }
+
+    /// Face the rotation brings to D.
+    pub(crate) fn face(&self) -> Face {
+        self.face
+    }
+
+    /// The cube held with the face of the rotation on D.
+    pub(crate) fn rotate(&self, cube: &Cube) -> Cube {
+        let edges = conjugate(&self.edges, &edge_pieces(cube), 2);
+        let corners = conjugate(&self.corners, &corner_pieces(cube), 3);
+        let solved = Cube::default();
+        let mut rotated = solved.clone();
+        for (edge, (piece, orientation)) in rotated.edges.iter_mut().zip(edges) {
+            edge.piece = solved.edges[piece].piece.clone();
+            edge.orientation = orientation;
+        }
+        for (corner, (piece, orientation)) in rotated.corners.iter_mut().zip(corners) {
+            corner.piece = solved.corners[piece].piece.clone();
+            corner.orientation = orientation;
+        }
+        rotated
+    }
+
+    /// Moves of the original cube doing the given moves of the rotated cube.
+    pub(crate) fn original_moves(&self, moves: &[Move]) -> Vec<Move> {
+        moves
+            .iter()
+            .map(|move_| self.original_moves[move_.clone() as usize].clone())
+            .collect()
+    }
+}
+
+fn edge_pieces(cube: &Cube) -> Pieces<12> {
+    cube.edges
+        .clone()
+        .map(|edge| (edge.piece as usize, edge.orientation))
+}
+
+fn corner_pieces(cube: &Cube) -> Pieces<8> {
+    cube.corners
+        .clone()
+        .map(|corner| (corner.piece as usize, corner.orientation))
+}
+
+/// Pieces of `first` moved by the moves leading to `second`.
+fn then<const N: usize>(first: &Pieces<N>, second: &Pieces<N>, orientations: u8) -> Pieces<N> {
+    second.map(|(source, orientation)| {
+        let (piece, first_orientation) = first[source];
+        (piece, (first_orientation + orientation) % orientations)
+    })
+}
+
+fn inverse<const N: usize>(pieces: &Pieces<N>, orientations: u8) -> Pieces<N> {
+    let mut inverse = *pieces;
+    for (slot, (source, orientation)) in pieces.iter().enumerate() {
+        inverse[*source] = (slot, (orientations - orientation) % orientations);
+    }
+    inverse
+}
+
+/// `relabelling⁻¹ · pieces · relabelling`
+fn conjugate<const N: usize>(
+    relabelling: &Pieces<N>,
+    pieces: &Pieces<N>,
+    orientations: u8,
+) -> Pieces<N> {
+    then(
+        &then(&inverse(relabelling, orientations), pieces, orientations),
+        relabelling,
+        orientations,
+    )
+}
+
+/// Relabelling conjugating each original move into its rotated move. The
+/// relabelling of one slot gives the one of the slots each move sends it to,
+/// so each choice for the first slot is spread to the others, and checked.
+fn relabelling<const N: usize>(
+    moves: &[(Cube, Cube)],
+    orientations: u8,
+    pieces: impl Fn(&Cube) -> Pieces<N>,
+) -> Pieces<N> {
+    let moves = moves
+        .iter()
+        .map(|(original, rotated)| (pieces(original), pieces(rotated)))
+        .collect::<Vec<_>>();
+    for first in 0..N {
+        for first_orientation in 0..orientations {
+            let mut relabelling = [None; N];
+            relabelling[0] = Some((first, first_orientation));
+            let mut stack = vec![0];
+            while let Some(slot) = stack.pop() {
+                let (source, orientation) = relabelling[slot].unwrap();
+                for (original, rotated) in &moves {
+                    let (next_slot, rotated_orientation) = rotated[slot];
+                    let (next_source, original_orientation) = original[source];
+                    if relabelling[next_slot].is_none() {
+                        relabelling[next_slot] = Some((
+                            next_source,
+                            (original_orientation + orientation + orientations
+                                - rotated_orientation)
+                                % orientations,
+                        ));
+                        stack.push(next_slot);
+                    }
+                }
+            }
+            let relabelling = relabelling.map(Option::unwrap);
+            if moves.iter().all(|(original, rotated)| {
+                then(original, &relabelling, orientations)
+                    == then(&relabelling, rotated, orientations)
+            }) {
+                return relabelling;
+            }
+        }
+    }
+    unreachable!("Rotations relabel the slots of the moves")
+}
+
+#[cfg(test)]
+mod tests {
+    use cube::algorithms::parse_algorithm;
+
+    use super::*;
+
+    #[test]
+    fn rotate_scrambles() {
+        let scramble = parse_algorithm("D' R2 U' F2 D' L2 U2 B2 R2 U' L' B' D2 L' F' U2 R U' F' L");
+        for face in FACES {
+            let rotation = Rotation::new(face);
+            let mut cube = Cube::default();
+            cube.execute_algorithm(&scramble);
+            let mut rotated = Cube::default();
+            let rotated_scramble = scramble
+                .iter()
+                .map(|move_| {
+                    ALL_MOVES
+                        .iter()
+                        .find(|rotated_move| {
+                            rotation.original_moves(&[(*rotated_move).clone()]) == [move_.clone()]
+                        })
+                        .unwrap()
+                        .clone()
+                })
+                .collect::<Vec<_>>();
+            rotated.execute_algorithm(&rotated_scramble);
+            assert_eq!(rotation.rotate(&cube), rotated);
+            assert!(rotated.validate().is_ok());
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! # States and moves explored by the searches.
+//!
+//! Most steps search the cube with face moves, but some methods also turn
+//! slices or wide layers, which the [`Cube`] model does not know about. IDA*
+//! and pattern databases are written against these traits, so that such
+//! steps can bring their own state and moves while still taking and
+//! returning plain cubes and face moves.
+
+use cube::{algorithms::Move, subcases::CubeSubset, Cube};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Move of a search.
+pub trait SearchMove: Clone + PartialEq + Send + Sync + Serialize + DeserializeOwned {
+    #[must_use]
+    fn inverse(&self) -> Self;
+    /// Layer turned by the move. Consecutive moves of a layer can be merged.
+    fn layer(&self) -> usize;
+    /// Axis of the layer. Moves of the same axis commute.
+    fn axis(&self) -> usize;
+}
+
+/// Whether the move may follow the path, with the pruning of the IDA*
+/// steps: no two moves of a layer in a row, and no three moves of an axis in
+/// a row.
+pub(crate) fn is_candidate<M: SearchMove>(move_: &M, path: &[M]) -> bool {
+    match path {
+        [.., previous] if previous.layer() == move_.layer() => false,
+        [.., previous_previous, previous] => {
+            previous_previous.axis() != previous.axis() || previous.axis() != move_.axis()
+        }
+        _ => true,
+    }
+}
+
+/// State of a search.
+pub trait SearchState: Clone + Send + Sync {
+    type Move: SearchMove;
+
+    /// State of the given cube, as seen by the searches of a step.
+    fn from_cube(cube: &Cube) -> Self;
+    fn execute_move(&mut self, move_: &Self::Move);
+    /// Face moves performing the path from this state.
+    fn face_moves(&self, path: &[Self::Move]) -> Vec<Move>;
+}
+
+/// State and moves of an [`IdaStep`](super::ida_solver::IdaStep), for steps
+/// defined outside of this crate.
+pub trait SearchSpace: Clone + Default + Send + Sync + Serialize + DeserializeOwned {
+    /// Name of the step, also used as the kind of its cached table
+    const NAME: &'static str;
+    type State: SearchState;
+
+    /// Moves the search may turn. The default space holds them already.
+    fn moves(&self) -> &[<Self::State as SearchState>::Move];
+}
+
+/// Part of a search state with a dense rank, stored in pattern databases.
+pub trait StateSubset<S> {
+    /// Number of distinct values of the subset.
+    const CASES: usize;
+
+    fn from_state(state: &S) -> Self;
+    /// Dense rank of the subset, in `0..Self::CASES`.
+    fn index(&self) -> usize;
+    /// Build a state whose subset has the given index.
+    fn state_from_index(index: usize) -> S;
+}
+
+impl SearchMove for Move {
+    fn inverse(&self) -> Self {
+        Move::inverse(self)
+    }
+
+    fn layer(&self) -> usize {
+        // Moves are declared three by three, face by face.
+        self.clone() as usize / 3
+    }
+
+    fn axis(&self) -> usize {
+        // Opposite faces are declared next to each other.
+        self.layer() / 2
+    }
+}
+
+impl SearchState for Cube {
+    type Move = Move;
+
+    fn from_cube(cube: &Cube) -> Self {
+        cube.clone()
+    }
+
+    fn execute_move(&mut self, move_: &Move) {
+        Cube::execute_move(self, move_);
+    }
+
+    fn face_moves(&self, path: &[Move]) -> Vec<Move> {
+        path.iter().filter(|m| **m != Move::None).cloned().collect()
+    }
+}
+
+impl<T: CubeSubset> StateSubset<Cube> for T {
+    const CASES: usize = <T as CubeSubset>::CASES;
+
+    fn from_state(state: &Cube) -> Self {
+        T::from_cube(state)
+    }
+
+    fn index(&self) -> usize {
+        CubeSubset::index(self)
+    }
+
+    fn state_from_index(index: usize) -> Cube {
+        T::cube_from_index(index)
+    }
+}
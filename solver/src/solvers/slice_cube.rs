@@ -0,0 +1,306 @@
+//! # Slice and wide moves, for the steps of the Roux method.
+//!
+//! [`Cube`] describes the pieces relative to the centers, which face moves
+//! never move, so it has no room for `M` or `r`. The searches of the Roux
+//! steps rather describe the pieces relative to the L and R layers, and
+//! count the quarter turns of the M slice, which moved the U, F, D and B
+//! centers. Solutions go back to face moves as follows:
+//!
+//! - `M` moves the cube like `R L'` followed by an `x'` rotation,
+//! - `r` moves the cube like `L` followed by an `x` rotation,
+//!
+//! and the face moves that follow are renamed after the rotated centers.
+
+use std::fmt::Display;
+
+use cube::{algorithms::Move, subcases::CubeSubset, Cube, DB, DF, UB, UF};
+use serde::{Deserialize, Serialize};
+
+use super::search_space::{SearchMove, SearchState, StateSubset};
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub(crate) enum SliceMove {
+    Face(Move),
+    /// Middle slice, turning like L
+    M,
+    M2,
+    Mp,
+    /// R and the middle slice, turning like R
+    Rw,
+    Rw2,
+    Rwp,
+}
+
+/// Layer of the middle slice, after the layers of the six faces and the one
+/// of [`Move::None`].
+const M_LAYER: usize = Move::None as usize / 3 + 1;
+const RW_LAYER: usize = M_LAYER + 1;
+/// Axis of the L and R faces.
+const X_AXIS: usize = 2;
+
+impl SearchMove for SliceMove {
+    fn inverse(&self) -> Self {
+        match self {
+            SliceMove::Face(move_) => SliceMove::Face(move_.inverse()),
+            SliceMove::M => SliceMove::Mp,
+            SliceMove::M2 => SliceMove::M2,
+            SliceMove::Mp => SliceMove::M,
+            SliceMove::Rw => SliceMove::Rwp,
+            SliceMove::Rw2 => SliceMove::Rw2,
+            SliceMove::Rwp => SliceMove::Rw,
+        }
+    }
+
+    fn layer(&self) -> usize {
+        match self {
+            SliceMove::Face(move_) => move_.layer(),
+            SliceMove::M | SliceMove::M2 | SliceMove::Mp => M_LAYER,
+            SliceMove::Rw | SliceMove::Rw2 | SliceMove::Rwp => RW_LAYER,
+        }
+    }
+
+    fn axis(&self) -> usize {
+        match self {
+            SliceMove::Face(move_) => move_.axis(),
+            _ => X_AXIS,
+        }
+    }
+}
+
+impl Display for SliceMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SliceMove::Face(move_) => write!(f, "{move_}"),
+            SliceMove::M => f.write_str("M"),
+            SliceMove::M2 => f.write_str("M2"),
+            SliceMove::Mp => f.write_str("M'"),
+            SliceMove::Rw => f.write_str("r"),
+            SliceMove::Rw2 => f.write_str("r2"),
+            SliceMove::Rwp => f.write_str("r'"),
+        }
+    }
+}
+
+/// Cube described relative to the L and R layers.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub(crate) struct SliceCube {
+    pub(crate) cube: Cube,
+    /// Quarter turns of the M slice since the centers were aligned, modulo 4
+    pub(crate) centers: u8,
+}
+
+impl SliceCube {
+    fn m(&mut self) {
+        let edges = &mut self.cube.edges;
+        for slot in [UF, DF, DB, UB] {
+            edges[slot].orientation ^= 1;
+        }
+        edges.swap(UF, UB);
+        edges.swap(UB, DB);
+        edges.swap(DB, DF);
+        self.centers = (self.centers + 1) % 4;
+    }
+
+    /// Whether the corners of the left block, which neither slice nor wide
+    /// moves touch, are solved.
+    pub(crate) fn is_left_block_aligned(&self) -> bool {
+        [cube::DLF, cube::DBL].iter().all(|slot| {
+            let corner = &self.cube.corners[*slot];
+            corner.piece.clone() as usize == *slot && corner.orientation == 0
+        })
+    }
+}
+
+/// Face turned by a face move of the Roux steps, once the centers moved by
+/// the given number of M quarter turns.
+fn rename_face(move_: &Move, centers: u8) -> Move {
+    // The faces moved into the U, F, D and B positions by one M turn
+    const CYCLE: [[Move; 3]; 4] = [
+        [Move::U, Move::U2, Move::Up],
+        [Move::B, Move::B2, Move::Bp],
+        [Move::D, Move::D2, Move::Dp],
+        [Move::F, Move::F2, Move::Fp],
+    ];
+    let face = move_.clone() as usize / 3;
+    let turn = move_.clone() as usize % 3;
+    let Some(position) = CYCLE
+        .iter()
+        .position(|moves| moves[0].clone() as usize / 3 == face)
+    else {
+        return move_.clone();
+    };
+    CYCLE[(position + usize::from(centers)) % 4][turn].clone()
+}
+
+impl SearchState for SliceCube {
+    type Move = SliceMove;
+
+    /// The L and R layers are found from the corners of the left block, so
+    /// that the centers may have moved in the steps after the first block.
+    fn from_cube(cube: &Cube) -> Self {
+        for rotations in 0..4 {
+            let mut state = SliceCube {
+                cube: cube.clone(),
+                centers: 0,
+            };
+            // Undo the x rotations: x' is R' M L
+            for _ in 0..rotations {
+                state.execute_move(&SliceMove::Face(Move::Rp));
+                state.execute_move(&SliceMove::M);
+                state.execute_move(&SliceMove::Face(Move::L));
+            }
+            if state.is_left_block_aligned() {
+                return state;
+            }
+        }
+        SliceCube {
+            cube: cube.clone(),
+            centers: 0,
+        }
+    }
+
+    fn execute_move(&mut self, move_: &SliceMove) {
+        match move_ {
+            SliceMove::Face(move_) => self.cube.execute_move(move_),
+            SliceMove::M => self.m(),
+            SliceMove::M2 => {
+                self.m();
+                self.m();
+            }
+            SliceMove::Mp => {
+                self.m();
+                self.m();
+                self.m();
+            }
+            SliceMove::Rw => {
+                self.cube.execute_move(&Move::R);
+                self.execute_move(&SliceMove::Mp);
+            }
+            SliceMove::Rw2 => {
+                self.cube.execute_move(&Move::R2);
+                self.execute_move(&SliceMove::M2);
+            }
+            SliceMove::Rwp => {
+                self.cube.execute_move(&Move::Rp);
+                self.execute_move(&SliceMove::M);
+            }
+        }
+    }
+
+    fn face_moves(&self, path: &[SliceMove]) -> Vec<Move> {
+        let mut centers = self.centers;
+        let mut moves = Vec::with_capacity(path.len());
+        for move_ in path {
+            let (face_moves, turns): (&[Move], u8) = match move_ {
+                SliceMove::Face(Move::None) => (&[], 0),
+                SliceMove::Face(move_) => {
+                    moves.push(rename_face(move_, centers));
+                    continue;
+                }
+                SliceMove::M => (&[Move::R, Move::Lp], 1),
+                SliceMove::M2 => (&[Move::R2, Move::L2], 2),
+                SliceMove::Mp => (&[Move::Rp, Move::L], 3),
+                SliceMove::Rw => (&[Move::L], 3),
+                SliceMove::Rw2 => (&[Move::L2], 2),
+                SliceMove::Rwp => (&[Move::Lp], 1),
+            };
+            moves.extend_from_slice(face_moves);
+            centers = (centers + turns) % 4;
+        }
+        moves
+    }
+}
+
+impl<T: CubeSubset> StateSubset<SliceCube> for T {
+    const CASES: usize = <T as CubeSubset>::CASES;
+
+    fn from_state(state: &SliceCube) -> Self {
+        T::from_cube(&state.cube)
+    }
+
+    fn index(&self) -> usize {
+        CubeSubset::index(self)
+    }
+
+    fn state_from_index(index: usize) -> SliceCube {
+        SliceCube {
+            cube: T::cube_from_index(index),
+            centers: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse an algorithm made of face moves, `M` and `r` moves.
+    fn parse_slice_algorithm(algorithm: &str) -> Vec<SliceMove> {
+        algorithm
+            .split_whitespace()
+            .map(|token| match token {
+                "M" => SliceMove::M,
+                "M2" => SliceMove::M2,
+                "M'" => SliceMove::Mp,
+                "r" => SliceMove::Rw,
+                "r2" => SliceMove::Rw2,
+                "r'" => SliceMove::Rwp,
+                _ => SliceMove::Face(cube::algorithms::parse_algorithm(token)[0].clone()),
+            })
+            .collect()
+    }
+
+    /// Whether the slice cube and the cube describe the same pieces, once
+    /// the centers are aligned again.
+    fn assert_same_pieces(algorithm: &str) {
+        let algorithm = parse_slice_algorithm(algorithm);
+        let mut state = SliceCube::default();
+        for move_ in &algorithm {
+            state.execute_move(move_);
+        }
+        assert_eq!(state.centers, 0);
+
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&SliceCube::default().face_moves(&algorithm));
+        assert_eq!(state.cube, cube);
+        assert_eq!(SliceCube::from_cube(&cube), state);
+    }
+
+    #[test]
+    fn slice_moves_as_face_moves() {
+        assert_same_pieces("M M'");
+        assert_same_pieces("M U M'");
+        assert_same_pieces("M' U2 M U R M2 F' M2 D");
+        assert_same_pieces("r U R' U' r' F R F'");
+        assert_same_pieces("r2 B M' U r' F2 M' L r U");
+        assert_same_pieces("M U M U M U M U M2 U2 M2 U2");
+    }
+
+    #[test]
+    fn recover_moved_centers() {
+        let algorithm = parse_slice_algorithm("R U M' U' r U2 R'");
+        let mut state = SliceCube::default();
+        for move_ in &algorithm {
+            state.execute_move(move_);
+        }
+        assert_eq!(state.centers, 2);
+
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&SliceCube::default().face_moves(&algorithm));
+        assert_eq!(SliceCube::from_cube(&cube), state);
+    }
+
+    #[test]
+    fn distinct_layers() {
+        let layers = [
+            SliceMove::Face(Move::None).layer(),
+            SliceMove::M.layer(),
+            SliceMove::Rw.layer(),
+        ];
+        assert!(cube::algorithms::ALL_MOVES
+            .iter()
+            .all(|move_| !layers[1..].contains(&move_.layer())));
+        assert_ne!(layers[0], layers[1]);
+        assert_ne!(layers[1], layers[2]);
+    }
+}
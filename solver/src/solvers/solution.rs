@@ -0,0 +1,186 @@
+//! # Solutions of the methods, split into their stages.
+//!
+//! Each step of a method adds a stage to the solution, with the moves it
+//! found, the case it recognised and what its search cost. The stages can be
+//! flattened into a single algorithm, or written as a reconstruction with one
+//! commented line per stage:
+//!
+//! ```text
+//! D R' F D2 // cross
+//! R U R' U R U2 R' // OLL 27
+//! ```
+
+use std::{fmt::Display, time::Duration};
+
+use cube::algorithms::{algorithm_to_string, Move};
+use serde::{Deserialize, Serialize};
+
+use super::{rotation::Face, utils::append_cancelling};
+
+/// Cost of the search of a stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SearchStats {
+    /// Nodes visited by the search, zero for the steps solved with a lookup
+    pub nodes: u64,
+    pub duration: Duration,
+}
+
+/// Moves solving one step of a method.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stage {
+    /// Name of the step, as its tables are named
+    pub name: String,
+    pub moves: Vec<Move>,
+    /// Case recognised by the step, such as `OLL 27`, for the steps that
+    /// tell their cases apart
+    pub case: Option<String>,
+    pub stats: SearchStats,
+}
+
+impl Stage {
+    pub(crate) fn new(name: impl Into<String>, moves: Vec<Move>) -> Self {
+        Self {
+            name: name.into(),
+            moves,
+            case: None,
+            stats: SearchStats::default(),
+        }
+    }
+
+    /// Label of the stage in a reconstruction: its case, or its name.
+    #[must_use]
+    pub fn label(&self) -> &str {
+        self.case.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// Solution found by a method, stage by stage.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Solution {
+    pub stages: Vec<Stage>,
+    /// Face held on D while solving the stages, when the method rotates the
+    /// cube to another face than D, such as `R` for the rotation `z`. The
+    /// moves of the stages are still given in the orientation of the
+    /// scramble.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<Face>,
+}
+
+impl Solution {
+    /// Moves of all the stages, with the turns of the same face merged where
+    /// two stages meet.
+    #[must_use]
+    pub fn moves(&self) -> Vec<Move> {
+        let mut moves = vec![];
+        for stage in &self.stages {
+            append_cancelling(&mut moves, &stage.moves);
+        }
+        moves
+    }
+
+    /// Number of moves of the flattened solution.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.moves().len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Search statistics of all the stages.
+    #[must_use]
+    pub fn stats(&self) -> SearchStats {
+        self.stages
+            .iter()
+            .fold(SearchStats::default(), |total, stage| SearchStats {
+                nodes: total.nodes + stage.stats.nodes,
+                duration: total.duration + stage.stats.duration,
+            })
+    }
+
+    /// One line per stage, its moves then its label after `//`.
+    #[must_use]
+    pub fn reconstruction(&self) -> String {
+        self.stages
+            .iter()
+            .map(|stage| {
+                let moves = algorithm_to_string(&stage.moves);
+                if moves.is_empty() {
+                    format!("// {}", stage.label())
+                } else {
+                    format!("{moves} // {}", stage.label())
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Solutions are serialized to JSON")
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(text)
+    }
+
+    /// Translate the moves of every stage with `map`.
+    pub(crate) fn map_moves(&mut self, map: impl Fn(&[Move]) -> Vec<Move>) {
+        for stage in &mut self.stages {
+            stage.moves = map(&stage.moves);
+        }
+    }
+}
+
+impl Display for Solution {
+    /// The flattened solution.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&algorithm_to_string(&self.moves()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cube::algorithms::parse_algorithm;
+
+    use super::*;
+
+    fn solution() -> Solution {
+        let mut oll = Stage::new("oll", parse_algorithm("U R U2 R' U' R U' R'"));
+        oll.case = Some("OLL 26".to_string());
+        Solution {
+            stages: vec![
+                Stage::new("f2l", parse_algorithm("R U R' U'")),
+                oll,
+                Stage::new("pll", vec![]),
+            ],
+            rotation: None,
+        }
+    }
+
+    #[test]
+    fn flatten_with_cancellations() {
+        let solution = solution();
+        assert_eq!(solution.moves(), parse_algorithm("R U' R' U' R U' R'"));
+        assert_eq!(solution.len(), 7);
+    }
+
+    #[test]
+    fn write_reconstruction() {
+        assert_eq!(
+            solution().reconstruction(),
+            "R U R' U' // f2l\nU R U2 R' U' R U' R' // OLL 26\n// pll"
+        );
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let mut solution = solution();
+        assert_eq!(Solution::from_json(&solution.to_json()).unwrap(), solution);
+        solution.rotation = Some(Face::R);
+        assert!(solution.to_json().contains(r#""rotation": "R""#));
+        assert_eq!(Solution::from_json(&solution.to_json()).unwrap(), solution);
+    }
+}
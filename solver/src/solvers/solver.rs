@@ -1,44 +1,257 @@
 use std::{
     error::Error,
-    fs::File,
-    io::{BufReader, Read, Write},
+    fmt::Display,
+    time::{Duration, Instant},
 };
 
-use cube::{algorithms::Move, Cube};
+use cube::{algorithms::Move, validation::InvalidCube, Cube};
 use serde::{Deserialize, Serialize};
 
-pub(super) trait Step: Sized + Serialize + for<'de> Deserialize<'de> + Clone {
-    fn new(path: &str) -> Self {
-        if let Ok(solver) = Self::from_file(path) {
-            solver
-        } else {
-            let solver = Self::generate();
-            solver.save_to_file(path).unwrap();
-            solver
+use super::{
+    enumeration::{EnumerationOptions, MethodSolutions},
+    options::{Budget, SolveOptions},
+    progress::ProgressReporter,
+    table_store::{TableError, TableStore},
+    utils::{generation_time, SEARCH_TABLE_NANOS_PER_BYTE},
+};
+
+pub use super::{
+    ida_solver::{Heuristic, IdaStep},
+    pattern_database::PatternDatabase,
+    search_space::{SearchMove, SearchSpace, SearchState, StateSubset},
+    solution::{SearchStats, Solution, Stage},
+};
+
+#[derive(Debug)]
+pub enum SolveError {
+    /// The cube cannot be reached from the solved state
+    InvalidCube(InvalidCube),
+    /// The step has no solution for the state it was given
+    UnreachableCase { step: &'static str },
+    /// The target of the step cannot be reached with its moves
+    UnreachableTarget { step: &'static str, moves: String },
+    /// The tables of a step could not be loaded
+    MissingTables(TableError),
+    /// The deadline or the node limit was reached. `partial` holds the
+    /// solution of the steps completed so far.
+    Timeout {
+        step: &'static str,
+        partial: Vec<Move>,
+    },
+    /// The solve was cancelled. `partial` holds the solution of the steps
+    /// completed so far.
+    Cancelled {
+        step: &'static str,
+        partial: Vec<Move>,
+    },
+    /// The search went through the whole search space without finding a solution
+    SearchExhausted { step: &'static str },
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::InvalidCube(error) => write!(f, "invalid cube: {error}"),
+            SolveError::UnreachableCase { step } => {
+                write!(f, "{step} cannot be solved from this state")
+            }
+            SolveError::UnreachableTarget { step, moves } => {
+                write!(f, "{step} target cannot be reached with {moves}")
+            }
+            SolveError::MissingTables(error) => write!(f, "missing tables: {error}"),
+            SolveError::Timeout { step, .. } => write!(f, "{step} search timed out"),
+            SolveError::Cancelled { step, .. } => write!(f, "{step} search was cancelled"),
+            SolveError::SearchExhausted { step } => {
+                write!(f, "{step} search found no solution")
+            }
         }
     }
-    fn save_to_file(&self, path: &str) -> Result<(), Box<dyn Error>> {
-        let mut file = File::create(path)?;
-        let serialized = ron::to_string(self)?;
-        file.write_all(serialized.as_bytes())?;
-        Ok(())
+}
+
+impl SolveError {
+    /// Prepend the moves of the previous steps to the partial solution of an
+    /// interrupted search.
+    #[must_use]
+    pub(crate) fn after(mut self, moves: &[Move]) -> Self {
+        if let SolveError::Timeout { partial, .. } | SolveError::Cancelled { partial, .. } =
+            &mut self
+        {
+            partial.splice(0..0, moves.iter().cloned());
+        }
+        self
+    }
+}
+
+impl Error for SolveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SolveError::InvalidCube(error) => Some(error),
+            SolveError::MissingTables(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<InvalidCube> for SolveError {
+    fn from(error: InvalidCube) -> Self {
+        SolveError::InvalidCube(error)
+    }
+}
+
+impl From<TableError> for SolveError {
+    fn from(error: TableError) -> Self {
+        SolveError::MissingTables(error)
+    }
+}
+
+pub trait Step: Sized + Serialize + for<'de> Deserialize<'de> + Clone {
+    /// Name of the step, also used as the kind of its cached table
+    const NAME: &'static str;
+
+    /// Load the table of the step from the store, generating it if needed
+    fn load(store: &TableStore) -> Result<Self, TableError> {
+        store.load(Self::NAME)
     }
 
-    fn from_file(path: &str) -> Result<Self, Box<dyn Error>> {
-        let file = File::open(path)?;
-        let mut buf_reader = BufReader::new(file);
-        let mut contents = String::new();
-        buf_reader.read_to_string(&mut contents)?;
-        let deserialized: Self = ron::from_str(&contents)?;
-        Ok(deserialized)
+    /// Revision of the hard-coded data the table is generated from. Cached
+    /// tables with another revision are regenerated.
+    fn revision() -> u64 {
+        0
     }
 
     /// Generate heuristics programmatically
     fn generate() -> Self;
+    /// Generate heuristics programmatically, reporting the progress of the
+    /// long generations
+    fn generate_with_progress(progress: &dyn ProgressReporter) -> Self {
+        let _ = progress;
+        Self::generate()
+    }
     /// Solve the step for the given cube
-    fn solve(&self, cube: &Cube) -> Vec<Move>;
+    fn solve(&self, cube: &Cube) -> Result<Vec<Move>, SolveError>;
+    /// Solve the step for the given cube, within the budget of the solve
+    fn solve_within(&self, cube: &Cube, budget: &Budget) -> Result<Vec<Move>, SolveError> {
+        budget.check(Self::NAME)?;
+        self.solve(cube)
+    }
+    /// Solve the step so that its pieces end up as they are in `to`, rather
+    /// than solved
+    fn solve_to(&self, from: &Cube, to: &Cube) -> Result<Vec<Move>, SolveError> {
+        to.validate()?;
+        self.solve(&from.relative_to(to))
+    }
+    /// Lower bound (or estimate) of the length of the solutions of the step,
+    /// `usize::MAX` when the step cannot be solved. Defaults to the length of
+    /// the solution, for steps that are solved with a table lookup
+    fn lower_bound(&self, cube: &Cube) -> usize {
+        self.solve(cube)
+            .map_or(usize::MAX, |solution| solution.len())
+    }
+    /// Name of the case of the cube, for the steps that tell their cases
+    /// apart
+    fn case_name(&self, cube: &Cube) -> Option<String> {
+        let _ = cube;
+        None
+    }
+    /// Solutions of the step for the given cube, in non-decreasing length
+    fn solutions<'a>(
+        &'a self,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Box<dyn Iterator<Item = Vec<Move>> + 'a> {
+        let solution = self
+            .solve(cube)
+            .ok()
+            .filter(|solution| options.allows_length(solution.len()));
+        Box::new(solution.into_iter())
+    }
+    /// Solve the step from the current state of the cube, then apply its
+    /// solution and append it as a stage
+    fn apply(
+        &self,
+        cube: &mut Cube,
+        solution: &mut Solution,
+        budget: &Budget,
+    ) -> Result<(), SolveError> {
+        let start = Instant::now();
+        let nodes = budget.nodes();
+        let case = self.case_name(cube);
+        let moves = self
+            .solve_within(cube, budget)
+            .map_err(|error| error.after(&solution.moves()))?;
+        cube.execute_algorithm(&moves);
+        solution.stages.push(Stage {
+            name: Self::NAME.to_string(),
+            moves,
+            case,
+            stats: SearchStats {
+                nodes: budget.nodes() - nodes,
+                duration: start.elapsed(),
+            },
+        });
+        Ok(())
+    }
+    /// Approximate size of the cached table, in bytes, computed from the
+    /// number of cases of its tables. `None` when it is not known
+    fn table_size() -> Option<u64> {
+        None
+    }
+    /// Approximate time to generate the table on a single core, computed
+    /// from its size. `None` when the size is not known
+    fn generation_time() -> Option<Duration> {
+        Self::table_size().map(|size| generation_time(size, SEARCH_TABLE_NANOS_PER_BYTE))
+    }
+    /// Memory used by the lookup tables of the step, in bytes
+    fn memory_usage(&self) -> usize;
 }
 
 pub trait Method: Clone {
-    fn solve(&self, cube: &Cube) -> Vec<Move>;
+    /// Solve the cube within the limits of the options, or explain why it
+    /// could not be solved
+    fn try_solve_with(&self, cube: &Cube, options: &SolveOptions) -> Result<Solution, SolveError>;
+
+    /// Solve the cube without limits, or explain why it could not be solved
+    fn try_solve(&self, cube: &Cube) -> Result<Solution, SolveError> {
+        self.try_solve_with(cube, &SolveOptions::default())
+    }
+
+    /// Moves solving the cube, without the stages of the solution
+    fn solve(&self, cube: &Cube) -> Result<Vec<Move>, SolveError> {
+        self.try_solve(cube).map(|solution| solution.moves())
+    }
+
+    /// Turn the cube into `to` within the limits of the options, or explain
+    /// why it could not be done
+    fn try_solve_to_with(
+        &self,
+        from: &Cube,
+        to: &Cube,
+        options: &SolveOptions,
+    ) -> Result<Solution, SolveError> {
+        to.validate()?;
+        self.try_solve_with(&from.relative_to(to), options)
+    }
+
+    /// Turn the cube into `to` without limits, or explain why it could not
+    /// be done
+    fn try_solve_to(&self, from: &Cube, to: &Cube) -> Result<Solution, SolveError> {
+        self.try_solve_to_with(from, to, &SolveOptions::default())
+    }
+
+    /// Moves turning the cube into `to`, such as a pattern of
+    /// [`cube::patterns`]
+    fn solve_to(&self, from: &Cube, to: &Cube) -> Result<Vec<Move>, SolveError> {
+        self.try_solve_to(from, to).map(|solution| solution.moves())
+    }
+
+    /// Solutions of the cube in non-decreasing length, chaining several
+    /// solutions of each step
+    fn solutions<'a>(
+        &'a self,
+        cube: &Cube,
+        options: &EnumerationOptions,
+    ) -> Result<MethodSolutions<'a>, SolveError>;
+
+    /// Memory used by the lookup tables of all the steps, in bytes
+    fn memory_usage(&self) -> usize;
 }
@@ -1,58 +1,68 @@
-use std::collections::HashMap;
-
-use cube::{algorithms::Move, subcases::CubeSubset};
+use cube::{algorithms::Move, Cube};
 use serde::{Deserialize, Serialize};
 
 use crate::solvers::{
-    cube_subsets::{
-        CornerPermutation, Edge6_1Permutation, Edge6_2Permutation, Orientation, CP_CASES,
-        E6P_CASES, ORIENTATION_CASES,
-    },
+    cube_subsets::{CornerPermutation, Edge6_1Permutation, Edge6_2Permutation, Orientation},
     ida_solver::IDAStepSolver,
+    pattern_database::PatternDatabase,
+    progress::ProgressReporter,
 };
 
 #[derive(Serialize, Deserialize, Default, Clone)]
 pub struct Solver {
     candidate_moves: Vec<Move>,
-    orientation: HashMap<Orientation, usize>,
-    edge_permutation_1: HashMap<Edge6_1Permutation, usize>,
-    edge_permutation_2: HashMap<Edge6_2Permutation, usize>,
-    corner_permutation: HashMap<CornerPermutation, usize>,
+    orientation: PatternDatabase<Orientation>,
+    edge_permutation_1: PatternDatabase<Edge6_1Permutation>,
+    edge_permutation_2: PatternDatabase<Edge6_2Permutation>,
+    corner_permutation: PatternDatabase<CornerPermutation>,
 }
 
 impl IDAStepSolver for Solver {
+    const NAME: &'static str = "all";
+    type State = Cube;
+
     #[inline]
     fn get_all_moves(&self) -> &[Move] {
         &self.candidate_moves
     }
 
     fn assess_distance(&self, cube: &cube::Cube) -> usize {
-        let orientation = Orientation::from_cube(cube);
-        let corner_permutation = CornerPermutation::from_cube(cube);
-        let edge_permutation_1 = Edge6_1Permutation::from_cube(cube);
-        let edge_permutation_2 = Edge6_2Permutation::from_cube(cube);
-
-        let orientation_moves = self.orientation.get(&orientation).unwrap();
-        let corner_permutation_moves = self.corner_permutation.get(&corner_permutation).unwrap();
+        let orientation_moves = self.orientation.distance(cube);
+        let corner_permutation_moves = self.corner_permutation.distance(cube);
         let edge_permutation_moves = self
             .edge_permutation_1
-            .get(&edge_permutation_1)
-            .unwrap()
-            .max(self.edge_permutation_2.get(&edge_permutation_2).unwrap());
+            .distance(cube)
+            .max(self.edge_permutation_2.distance(cube));
 
-        *orientation_moves
+        orientation_moves
             .max(corner_permutation_moves)
             .max(edge_permutation_moves)
     }
 
+    fn heuristics_memory_usage(&self) -> usize {
+        self.orientation.memory_usage()
+            + self.corner_permutation.memory_usage()
+            + self.edge_permutation_1.memory_usage()
+            + self.edge_permutation_2.memory_usage()
+    }
+
+    fn heuristics_size() -> Option<u64> {
+        Some(
+            PatternDatabase::<Orientation>::encoded_size::<Self::State>()
+                + PatternDatabase::<CornerPermutation>::encoded_size::<Self::State>()
+                + PatternDatabase::<Edge6_1Permutation>::encoded_size::<Self::State>()
+                + PatternDatabase::<Edge6_2Permutation>::encoded_size::<Self::State>(),
+        )
+    }
+
     fn populate_candidate_moves(&mut self) {
         self.candidate_moves = cube::algorithms::ALL_MOVES.to_vec();
     }
 
-    fn populate_heuristics(&mut self) {
-        self.orientation = self.generate_heuristic(ORIENTATION_CASES, "Orientation");
-        self.corner_permutation = self.generate_heuristic(CP_CASES, "Corner Permutation");
-        self.edge_permutation_1 = self.generate_heuristic(E6P_CASES, "Edge Permutation/EP1");
-        self.edge_permutation_2 = self.generate_heuristic(E6P_CASES, "Edge Permutation/EP2");
+    fn populate_heuristics(&mut self, progress: &dyn ProgressReporter) {
+        self.orientation = self.generate_heuristic("Orientation", progress);
+        self.corner_permutation = self.generate_heuristic("Corner Permutation", progress);
+        self.edge_permutation_1 = self.generate_heuristic("Edge Permutation/EP1", progress);
+        self.edge_permutation_2 = self.generate_heuristic("Edge Permutation/EP2", progress);
     }
 }
@@ -0,0 +1,255 @@
+//! # Solve the corners of the U layer in the Roux method.
+//!
+//! There are 42 CMLL cases, named after the orientation of their corners, so
+//! like PLL the step is a single hash table lookup with pre and post AUFs.
+//!
+//! The algorithms keep both blocks solved and ignore the last six edges.
+//! They are applied relative to the blocks, which the M slice may have turned
+//! away from the centers.
+
+use std::{collections::HashMap, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::solvers::{
+    cube_subsets::{are_blocks_solved, Cmll},
+    search_space::SearchState,
+    slice_cube::{SliceCube, SliceMove},
+    solver::{SolveError, Step},
+    table_store::fingerprint,
+    utils::{
+        algorithm_table_memory_usage, algorithm_table_size, generation_time,
+        ALGORITHM_TABLE_NANOS_PER_BYTE,
+    },
+};
+
+use cube::{
+    algorithms::{invert_algorithm, invert_move, parse_algorithm, Move},
+    subcases::CubeSubset,
+    Cube,
+};
+
+const CMLL_ALGS: [(&str, &str); 42] = [
+    ("O 1", "F2 R2 F L F' R2 F L' F"),
+    ("O 2", "R2 U2 F2 U' R2 U2 F2 U R2 U2 F2"),
+    ("H 1", "F U2 F' U2 F' U2 F' U2 F"),
+    ("H 2", "R U2 R2 U' R U' R' U2 F R F'"),
+    ("H 3", "R' F R U2 F' L F L' F U2 F'"),
+    ("H 4", "F R2 F2 U2 F R2 F' U2 F2 R2 F'"),
+    ("Pi 1", "F R' F2 R U2 R U2 R' F"),
+    ("Pi 2", "R' F R U F U' R U R' U' F'"),
+    ("Pi 3", "R2 F2 R F' L' U2 L2 F L' U2 R"),
+    ("Pi 4", "R2 F2 R U2 L F' L2 U2 L F R"),
+    ("Pi 5", "F R' F R F2 U2 L F' L' F"),
+    ("Pi 6", "F U F' U F U' R U' R' F'"),
+    ("U 1", "F R2 F2 U R' U R U2 F2 R2 F'"),
+    ("U 2", "R' F R U F2 R' F' R U' F'"),
+    ("U 3", "R2 F2 R' U2 R F2 R' U2 R'"),
+    ("U 4", "F U2 R' F2 R F2 L' U2 L F'"),
+    ("U 5", "F U F' R2 U' R F' R' U R2"),
+    ("U 6", "F U R U' R' F'"),
+    ("T 1", "F U F' U' R2 F2 L F' L' F2 R2"),
+    ("T 2", "F' L' F R' F' L F R"),
+    ("T 3", "R F R' U2 R F' R' F U2 F'"),
+    ("T 4", "R U R2 F R F' U L F L'"),
+    ("T 5", "F R F' L F R' F' L'"),
+    ("T 6", "R2 F2 R U' F' U F R' F2 R2"),
+    ("L 1", "L U2 R2 F' R F' L F' L2 U2 R"),
+    ("L 2", "L F2 L U L' F2 L U' L2"),
+    ("L 3", "F U2 F' U2 R' F' R"),
+    ("L 4", "R' F2 R' U' R F2 R' U R2"),
+    ("L 5", "F' U2 F U2 L F L'"),
+    ("L 6", "R U2 R2 F R F' R U2 R'"),
+    ("S 1", "R' F2 R U L' U L"),
+    ("S 2", "R' U' F U F R U' R' F' R"),
+    ("S 3", "R' F2 R U2 L F' L' F"),
+    ("S 4", "R U R' F' U F U R U2 R'"),
+    ("S 5", "R F2 R2 F L2 F' R2 F L2 F R'"),
+    ("S 6", "R U R' U F' U F U' R U2 R'"),
+    ("AS 1", "R' U' R U' R' U2 R"),
+    ("AS 2", "R2 F2 R U L' U2 R2 U' L U R'"),
+    ("AS 3", "R' U L U' F2 U' F2 U L' R"),
+    ("AS 4", "R U2 R' U2 R' F R F'"),
+    ("AS 5", "R F R' F' L F R F' L' R'"),
+    ("AS 6", "R U2 R' F R' F' R U' R U' R'"),
+];
+
+const AUFS: [Move; 4] = [Move::None, Move::U, Move::U2, Move::Up];
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Solver {
+    cases: HashMap<Cmll, Vec<Move>>,
+}
+
+impl Solver {
+    fn get_cases() -> HashMap<Cmll, Vec<Move>> {
+        let mut cases = HashMap::with_capacity((CMLL_ALGS.len() + 1) * AUFS.len());
+        cases.insert(Cmll::from_cube(&Cube::default()), vec![]);
+
+        for (_, alg) in CMLL_ALGS {
+            let alg = parse_algorithm(alg);
+            for auf in &AUFS {
+                let mut cube = Cube::default();
+                cube.execute_move(auf);
+                cube.execute_algorithm(&invert_algorithm(&alg));
+                cases.insert(Cmll::from_cube(&cube), alg.clone());
+            }
+        }
+        cases
+    }
+
+    fn are_corners_solved(cube: &Cube) -> bool {
+        Cmll::from_cube(cube) == Cmll::from_cube(&Cube::default())
+    }
+}
+
+impl Step for Solver {
+    const NAME: &'static str = "cmll";
+
+    fn revision() -> u64 {
+        fingerprint(&CMLL_ALGS.map(|(_, alg)| alg))
+    }
+
+    fn generate() -> Self {
+        let cases = Self::get_cases();
+        Self { cases }
+    }
+
+    fn solve(&self, cube: &Cube) -> Result<Vec<Move>, SolveError> {
+        let start = SliceCube::from_cube(cube);
+        let mut cube = start.cube.clone();
+        if !are_blocks_solved(&cube) {
+            return Err(SolveError::UnreachableCase { step: Self::NAME });
+        }
+        for pre_u_move in &AUFS {
+            cube.execute_move(pre_u_move);
+            if let Some(alg) = self.cases.get(&Cmll::from_cube(&cube)) {
+                cube.execute_algorithm(alg);
+                // Adjust U face
+                for post_u_move in &AUFS {
+                    cube.execute_move(post_u_move);
+                    if Self::are_corners_solved(&cube) {
+                        let path = [pre_u_move]
+                            .into_iter()
+                            .chain(alg)
+                            .chain([post_u_move])
+                            .map(|move_| SliceMove::Face(move_.clone()))
+                            .collect::<Vec<_>>();
+                        return Ok(start.face_moves(&path));
+                    }
+                    cube.execute_move(&invert_move(post_u_move));
+                }
+                cube.execute_algorithm(&invert_algorithm(alg));
+            }
+            cube.execute_move(&invert_move(pre_u_move));
+        }
+        Err(SolveError::UnreachableCase { step: Self::NAME })
+    }
+
+    /// Name of the case, such as `CMLL Pi 3`, found from the algorithm the
+    /// table applies
+    fn case_name(&self, cube: &Cube) -> Option<String> {
+        let mut cube = SliceCube::from_cube(cube).cube;
+        if !are_blocks_solved(&cube) {
+            return None;
+        }
+        if Self::are_corners_solved(&cube) {
+            return Some("CMLL skip".to_string());
+        }
+        let alg = AUFS.iter().find_map(|auf| {
+            cube.execute_move(auf);
+            let alg = self.cases.get(&Cmll::from_cube(&cube));
+            cube.execute_move(&invert_move(auf));
+            alg
+        })?;
+        CMLL_ALGS
+            .iter()
+            .find(|(_, candidate)| parse_algorithm(candidate) == *alg)
+            .map(|(name, _)| format!("CMLL {name}"))
+    }
+
+    fn table_size() -> Option<u64> {
+        let moves: usize = CMLL_ALGS
+            .iter()
+            .map(|(_, alg)| parse_algorithm(alg).len())
+            .sum();
+        Some(algorithm_table_size::<Cmll>(
+            (CMLL_ALGS.len() + 1) * AUFS.len(),
+            moves * AUFS.len(),
+        ))
+    }
+
+    fn generation_time() -> Option<Duration> {
+        Self::table_size().map(|size| generation_time(size, ALGORITHM_TABLE_NANOS_PER_BYTE))
+    }
+
+    fn memory_usage(&self) -> usize {
+        algorithm_table_memory_usage(&self.cases)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve_every_case() {
+        let solver = Solver::generate();
+        for (name, alg) in CMLL_ALGS {
+            for auf in &AUFS {
+                let mut cube = Cube::default();
+                cube.execute_move(auf);
+                cube.execute_algorithm(&invert_algorithm(&parse_algorithm(alg)));
+                cube.execute_move(auf);
+
+                let solution = solver.solve(&cube).unwrap();
+                cube.execute_algorithm(&solution);
+                assert!(Solver::are_corners_solved(&cube), "{name}");
+                assert!(are_blocks_solved(&cube), "{name}");
+            }
+        }
+    }
+
+    #[test]
+    fn name_cases() {
+        let solver = Solver::generate();
+        for (name, alg) in CMLL_ALGS {
+            let mut cube = Cube::default();
+            cube.execute_move(&Move::U);
+            cube.execute_algorithm(&invert_algorithm(&parse_algorithm(alg)));
+            assert_eq!(solver.case_name(&cube).unwrap(), format!("CMLL {name}"));
+        }
+        assert_eq!(
+            solver.case_name(&Cube::default()).as_deref(),
+            Some("CMLL skip")
+        );
+        let mut cube = Cube::default();
+        cube.execute_move(&Move::F);
+        assert_eq!(solver.case_name(&cube), None);
+    }
+
+    #[test]
+    fn solve_with_moved_centers() {
+        let solver = Solver::generate();
+        // M' then a Sune, whose U turns are now turns of the F face
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&parse_algorithm("R' L R F R' F R F2 R'"));
+
+        let solution = solver.solve(&cube).unwrap();
+        cube.execute_algorithm(&solution);
+        let state = SliceCube::from_cube(&cube);
+        assert_eq!(state.centers, 3);
+        assert!(Solver::are_corners_solved(&state.cube));
+    }
+
+    #[test]
+    fn reject_unsolved_blocks() {
+        let solver = Solver::generate();
+        let mut cube = Cube::default();
+        cube.execute_move(&Move::F);
+        assert!(matches!(
+            solver.solve(&cube),
+            Err(SolveError::UnreachableCase { step: "cmll" })
+        ));
+    }
+}
@@ -0,0 +1,118 @@
+//! # Solve the corners of the last layer in one look, with the edges oriented.
+//!
+//! COLL ends with the edges of the last layer oriented and its corners
+//! solved, leaving the permutation of the edges to the PLL step. The edges
+//! must be oriented beforehand, as in the ZZ method. The algorithms are
+//! generated, see [`last_layer_table`].
+
+use std::{collections::HashMap, sync::OnceLock, time::Duration};
+
+use cube::{algorithms::Move, Cube};
+use serde::{Deserialize, Serialize};
+
+use crate::solvers::{
+    solver::{SolveError, Step},
+    steps::last_layer_table::{self, LastLayer},
+    utils::algorithm_table_memory_usage,
+};
+
+/// Average length of the algorithms, moves of the U face included, as
+/// measured on the generated table.
+const AVERAGE_LENGTH: usize = 12;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Solver {
+    cases: HashMap<u32, Vec<Move>>,
+    #[serde(skip)]
+    case_numbers: OnceLock<HashMap<u32, usize>>,
+}
+
+impl Step for Solver {
+    const NAME: &'static str = "coll";
+
+    fn revision() -> u64 {
+        last_layer_table::revision()
+    }
+
+    fn generate() -> Self {
+        Self {
+            cases: last_layer_table::generate(
+                |last_layer| last_layer.edges_oriented() && last_layer.corners_solved(),
+                LastLayer::edges_oriented,
+            ),
+            case_numbers: OnceLock::new(),
+        }
+    }
+
+    fn solve(&self, cube: &Cube) -> Result<Vec<Move>, SolveError> {
+        last_layer_table::lookup(&self.cases, cube, Self::NAME)
+    }
+
+    /// Name of the case, such as `COLL 12`, numbered by the corners in the
+    /// order of the keys of the cases rather than by the usual names
+    fn case_name(&self, cube: &Cube) -> Option<String> {
+        let numbers = self
+            .case_numbers
+            .get_or_init(|| last_layer_table::case_numbers(&self.cases, LastLayer::corners));
+        last_layer_table::case_name(&self.cases, numbers, LastLayer::corners, cube, "COLL")
+    }
+
+    fn table_size() -> Option<u64> {
+        Some(last_layer_table::table_size(
+            last_layer_table::STATES / 8,
+            AVERAGE_LENGTH,
+        ))
+    }
+
+    fn generation_time() -> Option<Duration> {
+        Some(last_layer_table::generation_time())
+    }
+
+    fn memory_usage(&self) -> usize {
+        algorithm_table_memory_usage(&self.cases)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cube::algorithms::{invert_algorithm, parse_algorithm};
+
+    use super::*;
+
+    fn case(algorithm: &str) -> Cube {
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&invert_algorithm(&parse_algorithm(algorithm)));
+        cube
+    }
+
+    #[test]
+    fn measure_average_length() {
+        let solver = Solver::generate();
+        let moves = solver.cases.values().map(Vec::len).sum::<usize>();
+        let average = (moves + solver.cases.len() / 2) / solver.cases.len();
+        assert_eq!(average, AVERAGE_LENGTH);
+    }
+
+    #[test]
+    fn name_cases() {
+        let solver = Solver::generate();
+        let sune = solver.case_name(&case("R U R' U R U2 R'")).unwrap();
+        let number = sune
+            .strip_prefix("COLL ")
+            .unwrap()
+            .parse::<usize>()
+            .unwrap();
+        assert!((1..=42).contains(&number));
+        let mut cube = case("R U R' U R U2 R'");
+        cube.execute_move(&Move::U);
+        assert_eq!(solver.case_name(&cube).unwrap(), sune);
+        // The edges do not tell the cases apart
+        let sune_and_u_perm = case("R U R' U R U2 R' R U' R U R U R U' R' U' R2");
+        assert_eq!(solver.case_name(&sune_and_u_perm).unwrap(), sune);
+        let u_perm = case("R U' R U R U R U' R' U' R2");
+        assert_eq!(solver.case_name(&u_perm).unwrap(), "COLL skip");
+
+        assert_eq!(solver.case_name(&case("F R U R' U' F'")), None);
+        assert_eq!(solver.case_name(&case("F")), None);
+    }
+}
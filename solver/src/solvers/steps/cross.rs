@@ -3,19 +3,22 @@
 //! Because there are only a few thousand possible states of the cross, we can
 //! generate a lookup table for all of them, using a breadth-first search.
 
-use std::{
-    collections::{HashMap, VecDeque},
-    io::Write,
-};
+use std::collections::{HashMap, VecDeque};
 
 use crate::solvers::{
-    cube_subsets::Cross, cube_subsets::CROSS_CASES, solver::Step, utils::print_progress,
+    cube_subsets::Cross,
+    cube_subsets::CROSS_CASES,
+    progress::{NoProgress, ProgressReporter, ProgressTracker},
+    solver::{SolveError, Step},
 };
 use cube::subcases::CubeSubset;
 use cube::{self, algorithms::Move, Cube};
 use serde::{Deserialize, Serialize};
 
-use crate::solvers::utils::print_terminated;
+use crate::solvers::utils::{algorithm_table_memory_usage, algorithm_table_size};
+
+/// Average length of the optimal crosses, which take at most 8 moves.
+const AVERAGE_LENGTH: usize = 6;
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Solver {
@@ -23,20 +26,17 @@ pub struct Solver {
 }
 
 impl Solver {
-    fn generate_solutions(&mut self) {
+    fn generate_solutions(&mut self, progress: &dyn ProgressReporter) {
         // Using a breadth-first search, generate a lookup table for all possible cross cases.
         let mut queue = VecDeque::with_capacity(CROSS_CASES);
         queue.push_back((cube::Cube::default(), Vec::new()));
+        let tracker = ProgressTracker::new(
+            progress,
+            "Generating lookup table for Cross",
+            Some(CROSS_CASES as u64),
+        );
 
         while let Some((cube, solution)) = queue.pop_front() {
-            let progress = self.cases.len();
-            print_progress!(
-                "Generating lookup table for",
-                "Cross",
-                progress,
-                CROSS_CASES
-            );
-
             let case = Cross::from_cube(&cube);
             if self.cases.contains_key(&case) {
                 continue;
@@ -44,6 +44,7 @@ impl Solver {
 
             self.cases
                 .insert(case, cube::algorithms::invert_algorithm(&solution));
+            tracker.advance();
 
             for move_ in cube::algorithms::ALL_MOVES {
                 let mut cube = cube.clone();
@@ -53,27 +54,42 @@ impl Solver {
                 queue.push_back((cube, solution));
             }
         }
-        print_terminated!(
-            "Generating lookup table for",
-            "Cross",
-            self.cases.len(),
-            CROSS_CASES
-        );
+        tracker.finish(self.cases.len() as u64);
     }
 }
 
 impl Step for Solver {
+    const NAME: &'static str = "cross";
+
     fn generate() -> Self {
+        Self::generate_with_progress(&NoProgress)
+    }
+
+    fn generate_with_progress(progress: &dyn ProgressReporter) -> Self {
         let mut cross_solver = Self {
             cases: HashMap::with_capacity(CROSS_CASES),
         };
-        cross_solver.generate_solutions();
+        cross_solver.generate_solutions(progress);
         cross_solver
     }
 
-    fn solve(&self, cube: &Cube) -> Vec<Move> {
+    fn solve(&self, cube: &Cube) -> Result<Vec<Move>, SolveError> {
         let case = Cross::from_cube(cube);
-        self.cases.get(&case).unwrap_or(&Vec::new()).clone()
+        self.cases
+            .get(&case)
+            .cloned()
+            .ok_or(SolveError::UnreachableCase { step: Self::NAME })
+    }
+
+    fn table_size() -> Option<u64> {
+        Some(algorithm_table_size::<Cross>(
+            CROSS_CASES,
+            CROSS_CASES * AVERAGE_LENGTH,
+        ))
+    }
+
+    fn memory_usage(&self) -> usize {
+        algorithm_table_memory_usage(&self.cases)
     }
 }
 
@@ -95,7 +111,24 @@ mod tests {
         let scramble = cube::algorithms::parse_algorithm("R F B'");
         cube.execute_algorithm(&scramble);
 
-        let solution = solver.solve(&cube);
+        let solution = solver.solve(&cube).unwrap();
         assert_eq!(solution.len(), 3);
     }
+
+    #[test]
+    fn solve_cross_to_target() {
+        let solver = Solver::generate();
+
+        let mut target = cube::Cube::default();
+        target.execute_algorithm(&cube::algorithms::parse_algorithm("R F B'"));
+        let mut cube = cube::Cube::default();
+        cube.execute_algorithm(&cube::algorithms::parse_algorithm("U L2"));
+
+        let solution = solver.solve_to(&cube, &target).unwrap();
+        cube.execute_algorithm(&solution);
+        assert!(
+            Cross::from_cube(&cube.relative_to(&target))
+                == Cross::from_cube(&cube::Cube::default())
+        );
+    }
 }
@@ -0,0 +1,55 @@
+//! # Orient the edges and solve the cross in the ZZ method.
+//!
+//! The heuristic is the largest distance of the EOLine and of the cross,
+//! both from pattern databases.
+
+use cube::{algorithms::Move, Cube};
+use serde::{Deserialize, Serialize};
+
+use crate::solvers::{
+    cube_subsets::{Cross, EoLine},
+    ida_solver::IDAStepSolver,
+    pattern_database::PatternDatabase,
+    progress::ProgressReporter,
+};
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Solver {
+    candidate_moves: Vec<Move>,
+    eo_line: PatternDatabase<EoLine>,
+    cross: PatternDatabase<Cross>,
+}
+
+impl IDAStepSolver for Solver {
+    const NAME: &'static str = "eo_cross";
+    type State = Cube;
+
+    #[inline]
+    fn get_all_moves(&self) -> &[Move] {
+        &self.candidate_moves
+    }
+
+    fn assess_distance(&self, cube: &Cube) -> usize {
+        self.eo_line.distance(cube).max(self.cross.distance(cube))
+    }
+
+    fn heuristics_memory_usage(&self) -> usize {
+        self.eo_line.memory_usage() + self.cross.memory_usage()
+    }
+
+    fn heuristics_size() -> Option<u64> {
+        Some(
+            PatternDatabase::<EoLine>::encoded_size::<Self::State>()
+                + PatternDatabase::<Cross>::encoded_size::<Self::State>(),
+        )
+    }
+
+    fn populate_candidate_moves(&mut self) {
+        self.candidate_moves = cube::algorithms::ALL_MOVES.to_vec();
+    }
+
+    fn populate_heuristics(&mut self, progress: &dyn ProgressReporter) {
+        self.eo_line = self.generate_heuristic("EOCross/EOLine", progress);
+        self.cross = self.generate_heuristic("EOCross/Cross", progress);
+    }
+}
@@ -0,0 +1,48 @@
+//! # Orient the edges and place the DF and DB edges in the ZZ method.
+//!
+//! The pattern database covers every EOLine case, so the IDA* search goes
+//! straight to an optimal solution.
+
+use cube::{algorithms::Move, Cube};
+use serde::{Deserialize, Serialize};
+
+use crate::solvers::{
+    cube_subsets::EoLine, ida_solver::IDAStepSolver, pattern_database::PatternDatabase,
+    progress::ProgressReporter,
+};
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Solver {
+    candidate_moves: Vec<Move>,
+    cases: PatternDatabase<EoLine>,
+}
+
+impl IDAStepSolver for Solver {
+    const NAME: &'static str = "eo_line";
+    type State = Cube;
+
+    #[inline]
+    fn get_all_moves(&self) -> &[Move] {
+        &self.candidate_moves
+    }
+
+    fn assess_distance(&self, cube: &Cube) -> usize {
+        self.cases.distance(cube)
+    }
+
+    fn heuristics_memory_usage(&self) -> usize {
+        self.cases.memory_usage()
+    }
+
+    fn heuristics_size() -> Option<u64> {
+        Some(PatternDatabase::<EoLine>::encoded_size::<Self::State>())
+    }
+
+    fn populate_candidate_moves(&mut self) {
+        self.candidate_moves = cube::algorithms::ALL_MOVES.to_vec();
+    }
+
+    fn populate_heuristics(&mut self, progress: &dyn ProgressReporter) {
+        self.cases = self.generate_heuristic("EOLine", progress);
+    }
+}
@@ -4,32 +4,31 @@
 //! solve the F2Ls. We restrict the moves to three move triggers separated by U
 //! moves, so that the cross is not disturbed.
 
-use std::{collections::HashMap, ops::Add};
-
 use crate::solvers::{
-    cube_subsets::{
-        BackLeftBlock, BackRightBlock, Cross, FrontLeftBlock, FrontRightBlock, BLOCK_CASES,
-        CROSS_CASES,
-    },
+    cube_subsets::{BackLeftBlock, BackRightBlock, Cross, FrontLeftBlock, FrontRightBlock},
     ida_solver::IDAStepSolver,
+    pattern_database::PatternDatabase,
+    progress::ProgressReporter,
 };
 
 use serde::{Deserialize, Serialize};
 
-use cube::subcases::CubeSubset;
 use cube::{self, algorithms::Move, Cube};
 
 #[derive(Serialize, Deserialize, Default, Clone)]
 pub struct Solver {
     candidate_moves: Vec<Move>,
-    cross_cases: HashMap<Cross, usize>,
-    front_left_block: HashMap<FrontLeftBlock, usize>,
-    front_right_block: HashMap<FrontRightBlock, usize>,
-    back_left_block: HashMap<BackLeftBlock, usize>,
-    back_right_block: HashMap<BackRightBlock, usize>,
+    cross_cases: PatternDatabase<Cross>,
+    front_left_block: PatternDatabase<FrontLeftBlock>,
+    front_right_block: PatternDatabase<FrontRightBlock>,
+    back_left_block: PatternDatabase<BackLeftBlock>,
+    back_right_block: PatternDatabase<BackRightBlock>,
 }
 
 impl IDAStepSolver for Solver {
+    const NAME: &'static str = "f2l";
+    type State = Cube;
+
     #[inline]
     fn get_all_moves(&self) -> &[Move] {
         &self.candidate_moves
@@ -39,32 +38,44 @@ impl IDAStepSolver for Solver {
         self.candidate_moves = cube::algorithms::ALL_MOVES.to_vec();
     }
 
-    fn populate_heuristics(&mut self) {
-        self.cross_cases = self.generate_heuristic(CROSS_CASES, "Cross");
-        self.front_left_block = self.generate_heuristic(BLOCK_CASES, "FreeF2L/FLB");
-        self.front_right_block = self.generate_heuristic(BLOCK_CASES, "FreeF2L/FRB");
-        self.back_left_block = self.generate_heuristic(BLOCK_CASES, "FreeF2L/BLB");
-        self.back_right_block = self.generate_heuristic(BLOCK_CASES, "FreeF2L/BRB");
+    fn populate_heuristics(&mut self, progress: &dyn ProgressReporter) {
+        self.cross_cases = self.generate_heuristic("Cross", progress);
+        self.front_left_block = self.generate_heuristic("FreeF2L/FLB", progress);
+        self.front_right_block = self.generate_heuristic("FreeF2L/FRB", progress);
+        self.back_left_block = self.generate_heuristic("FreeF2L/BLB", progress);
+        self.back_right_block = self.generate_heuristic("FreeF2L/BRB", progress);
     }
 
     fn assess_distance(&self, cube: &Cube) -> usize {
         // Assess the distance of the cube from the solved state.
-        let cross = Cross::from_cube(cube);
-        let front_left_block = FrontLeftBlock::from_cube(cube);
-        let front_right_block = FrontRightBlock::from_cube(cube);
-        let back_left_block = BackLeftBlock::from_cube(cube);
-        let back_right_block = BackRightBlock::from_cube(cube);
-
-        let cross_distance = self.cross_cases.get(&cross).unwrap();
-        let front_left_block_distance = self.front_left_block.get(&front_left_block).unwrap();
-        let front_right_block_distance = self.front_right_block.get(&front_right_block).unwrap();
-        let back_left_block_distance = self.back_left_block.get(&back_left_block).unwrap();
-        let back_right_block_distance = self.back_right_block.get(&back_right_block).unwrap();
+        let cross_distance = self.cross_cases.distance(cube);
+        let front_left_block_distance = self.front_left_block.distance(cube);
+        let front_right_block_distance = self.front_right_block.distance(cube);
+        let back_left_block_distance = self.back_left_block.distance(cube);
+        let back_right_block_distance = self.back_right_block.distance(cube);
 
         cross_distance
-            .add(front_left_block_distance)
-            .add(front_right_block_distance)
-            .add(back_left_block_distance)
-            .add(back_right_block_distance)
+            .saturating_add(front_left_block_distance)
+            .saturating_add(front_right_block_distance)
+            .saturating_add(back_left_block_distance)
+            .saturating_add(back_right_block_distance)
+    }
+
+    fn heuristics_memory_usage(&self) -> usize {
+        self.cross_cases.memory_usage()
+            + self.front_left_block.memory_usage()
+            + self.front_right_block.memory_usage()
+            + self.back_left_block.memory_usage()
+            + self.back_right_block.memory_usage()
+    }
+
+    fn heuristics_size() -> Option<u64> {
+        Some(
+            PatternDatabase::<Cross>::encoded_size::<Self::State>()
+                + PatternDatabase::<FrontLeftBlock>::encoded_size::<Self::State>()
+                + PatternDatabase::<FrontRightBlock>::encoded_size::<Self::State>()
+                + PatternDatabase::<BackLeftBlock>::encoded_size::<Self::State>()
+                + PatternDatabase::<BackRightBlock>::encoded_size::<Self::State>(),
+        )
     }
 }
@@ -0,0 +1,392 @@
+//! # Solve the F2L of the CFOP method pair by pair.
+//!
+//! Each pair is solved with its own IDA* search, run by the engine of the
+//! IDA* steps, whose heuristic is the max of the cross and of the blocks that
+//! must be solved at the end: the slots already solved and the slot being
+//! inserted. Every remaining slot is tried, and the cheapest one is
+//! inserted. With lookahead, the slot is chosen by the cheapest sequence of
+//! two insertions instead.
+//!
+//! Given the algorithms of an F2L set, a pair whose pieces are in the U layer
+//! or in its slot is inserted with the algorithm of its case instead of a
+//! search. The algorithms are given for the front right slot, and are turned
+//! towards the other slots.
+
+use std::{fmt::Display, time::Instant};
+
+use cube::{
+    algorithms::{Move, ALL_MOVES},
+    Cube,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::solvers::{
+    cube_subsets::{BackLeftBlock, BackRightBlock, Cross, FrontLeftBlock, FrontRightBlock},
+    ida_solver::IdaSearch,
+    options::{Budget, SolveOptions},
+    pattern_database::PatternDatabase,
+    progress::{NoProgress, ProgressReporter},
+    solution::SearchStats,
+    solver::{SolveError, Step},
+};
+
+/// Algorithms inserting the front right pair from each of its cases, the
+/// shortest ones with `<R, U, F>`. The solved case comes first.
+pub(crate) const F2L_ALGS: [&str; 42] = [
+    "",
+    "R U' R U2 F R2 F' U2 R2",
+    "F' U2 F U2 R U R'",
+    "R U2 R' U2 F' U' F",
+    "R U2 R U2 F R F' U2 R2",
+    "R F U R U' R' F' U' R'",
+    "R U' R2 F R F'",
+    "R' F R F2 U' F",
+    "R U2 R U R' U R U2 R2",
+    "R U F R U R' U' F' R'",
+    "F' U F R U R'",
+    "R U2 R' F' U2 F",
+    "R2 U R2 U R2 U2 R2",
+    "R U' R' F' U2 F",
+    "R U2 R' U' R U R'",
+    "F2 U2 F U F' U F2",
+    "R2 U2 R' U' R U' R2",
+    "F' U2 F U F' U' F",
+    "R U R2 F R F'",
+    "F' U2 F2 R' F' R",
+    "R U2 R2 F R F'",
+    "R U R' F' U' F",
+    "R U R' U2 R U R'",
+    "F' U' F U' R U R'",
+    "R2 U R' U R U2 R2",
+    "R U' R' U2 F' U' F",
+    "R' U2 R2 U R2 U R",
+    "F' U2 F",
+    "F' U F U' R U R'",
+    "F' U' F U F' U2 F",
+    "R U R'",
+    "R2 U2 F R2 F' U2 R2",
+    "F' U2 F U' F' U' F",
+    "R U R' U2 F' U2 F",
+    "R U' R'",
+    "F U2 F2 U' F2 U' F'",
+    "F' U F U2 R U R'",
+    "F' U F U' F' U' F",
+    "F2 U2 R' F2 R U2 F2",
+    "F' U' F",
+    "R2 U2 F R' F' U2 R2",
+    "R U R' U2 F' U' F",
+];
+
+const AUFS: [Move; 4] = [Move::None, Move::U, Move::U2, Move::Up];
+
+/// F2L slot, named after its edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Slot {
+    FrontRight,
+    FrontLeft,
+    BackLeft,
+    BackRight,
+}
+
+const SLOTS: [Slot; 4] = [
+    Slot::FrontRight,
+    Slot::FrontLeft,
+    Slot::BackLeft,
+    Slot::BackRight,
+];
+
+impl Display for Slot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Slot::FrontRight => "FR",
+            Slot::FrontLeft => "FL",
+            Slot::BackLeft => "BL",
+            Slot::BackRight => "BR",
+        })
+    }
+}
+
+/// Moves inserting the pair of a slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PairInsertion {
+    pub slot: Slot,
+    pub moves: Vec<Move>,
+    /// Cost of the searches choosing and inserting the pair
+    pub stats: SearchStats,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Solver {
+    cross: PatternDatabase<Cross>,
+    front_right_block: PatternDatabase<FrontRightBlock>,
+    front_left_block: PatternDatabase<FrontLeftBlock>,
+    back_left_block: PatternDatabase<BackLeftBlock>,
+    back_right_block: PatternDatabase<BackRightBlock>,
+    /// Choose each slot by the cheapest sequence of two insertions
+    #[serde(skip)]
+    lookahead: bool,
+    /// Algorithms of an F2L set, inserting the front right pair
+    #[serde(skip)]
+    algorithms: Vec<Vec<Move>>,
+}
+
+impl Solver {
+    /// Choose each slot by the cheapest sequence of two insertions, rather
+    /// than by the cheapest insertion.
+    #[must_use]
+    pub(crate) fn with_lookahead(mut self, lookahead: bool) -> Self {
+        self.lookahead = lookahead;
+        self
+    }
+
+    /// Insert the pairs whose pieces are in the U layer or in their slot
+    /// with the given algorithms of the front right pair, rather than with
+    /// a search.
+    #[must_use]
+    pub(crate) fn with_algorithms(mut self, algorithms: &[Vec<Move>]) -> Self {
+        self.algorithms = algorithms.to_vec();
+        self
+    }
+
+    /// Insert the pairs one at a time, keeping the cross solved.
+    pub(crate) fn solve_pairs(
+        &self,
+        cube: &Cube,
+        budget: &Budget,
+    ) -> Result<Vec<PairInsertion>, SolveError> {
+        budget.check(Self::NAME)?;
+        if self.cross.distance(cube) == usize::MAX {
+            return Err(SolveError::UnreachableCase { step: Self::NAME });
+        }
+        let mut cube = cube.clone();
+        let mut solved = SLOTS.map(|slot| self.block_distance(slot, &cube) == 0);
+        let mut insertions = vec![];
+        loop {
+            let start = Instant::now();
+            let nodes = budget.nodes();
+            let Some((slot, moves)) = self.next_pair(&cube, &solved, budget)? else {
+                break;
+            };
+            cube.execute_algorithm(&moves);
+            solved[slot_index(slot)] = true;
+            insertions.push(PairInsertion {
+                slot,
+                moves,
+                stats: SearchStats {
+                    nodes: budget.nodes() - nodes,
+                    duration: start.elapsed(),
+                },
+            });
+        }
+        Ok(insertions)
+    }
+
+    /// Cheapest pair to insert next, or `None` once all the slots are solved.
+    fn next_pair(
+        &self,
+        cube: &Cube,
+        solved: &[bool; 4],
+        budget: &Budget,
+    ) -> Result<Option<(Slot, Vec<Move>)>, SolveError> {
+        let mut best: Option<(usize, Slot, Vec<Move>)> = None;
+        for slot in remaining(solved) {
+            let mut goal = *solved;
+            goal[slot_index(slot)] = true;
+            let moves = self.insert(cube, &goal, slot, budget)?;
+            let mut cost = moves.len();
+            if self.lookahead && remaining(&goal).next().is_some() {
+                let mut next_cube = cube.clone();
+                next_cube.execute_algorithm(&moves);
+                let mut next_cost = usize::MAX;
+                for next_slot in remaining(&goal) {
+                    let mut next_goal = goal;
+                    next_goal[slot_index(next_slot)] = true;
+                    next_cost = next_cost.min(
+                        self.insert(&next_cube, &next_goal, next_slot, budget)?
+                            .len(),
+                    );
+                }
+                cost += next_cost;
+            }
+            if best
+                .as_ref()
+                .is_none_or(|(best_cost, _, _)| cost < *best_cost)
+            {
+                best = Some((cost, slot, moves));
+            }
+        }
+        Ok(best.map(|(_, slot, moves)| (slot, moves)))
+    }
+
+    /// Shortest sequence solving the cross and the slots of the goal, the
+    /// algorithm inserting the pair of the slot when there is one.
+    fn insert(
+        &self,
+        cube: &Cube,
+        goal: &[bool; 4],
+        slot: Slot,
+        budget: &Budget,
+    ) -> Result<Vec<Move>, SolveError> {
+        if let Some(moves) = self.insert_with_algorithm(cube, goal, slot) {
+            return Ok(moves);
+        }
+        PairSearch {
+            solver: self,
+            goal: *goal,
+        }
+        .solve_cube(cube, budget)
+    }
+
+    /// Algorithm inserting the pair of the slot after a move of the U face,
+    /// turned towards the slot.
+    fn insert_with_algorithm(
+        &self,
+        cube: &Cube,
+        goal: &[bool; 4],
+        slot: Slot,
+    ) -> Option<Vec<Move>> {
+        AUFS.iter().find_map(|auf| {
+            self.algorithms.iter().find_map(|algorithm| {
+                let moves = [auf.clone()]
+                    .into_iter()
+                    .filter(|move_| *move_ != Move::None)
+                    .chain(algorithm.iter().map(|move_| slot_move(slot, move_)))
+                    .collect::<Vec<_>>();
+                let mut cube = cube.clone();
+                cube.execute_algorithm(&moves);
+                (self.distance(&cube, goal) == 0).then_some(moves)
+            })
+        })
+    }
+
+    /// Max of the distances of the cross and of the blocks of the goal.
+    fn distance(&self, cube: &Cube, goal: &[bool; 4]) -> usize {
+        SLOTS
+            .iter()
+            .filter(|slot| goal[slot_index(**slot)])
+            .map(|slot| self.block_distance(*slot, cube))
+            .fold(self.cross.distance(cube), usize::max)
+    }
+
+    fn block_distance(&self, slot: Slot, cube: &Cube) -> usize {
+        match slot {
+            Slot::FrontRight => self.front_right_block.distance(cube),
+            Slot::FrontLeft => self.front_left_block.distance(cube),
+            Slot::BackLeft => self.back_left_block.distance(cube),
+            Slot::BackRight => self.back_right_block.distance(cube),
+        }
+    }
+}
+
+/// Search solving the cross and the slots of a goal, with the tables of the
+/// solver.
+struct PairSearch<'a> {
+    solver: &'a Solver,
+    goal: [bool; 4],
+}
+
+impl IdaSearch for PairSearch<'_> {
+    const NAME: &'static str = <Solver as Step>::NAME;
+    type State = Cube;
+
+    fn moves(&self) -> &[Move] {
+        &ALL_MOVES
+    }
+
+    fn estimate(&self, cube: &Cube) -> usize {
+        self.solver.distance(cube, &self.goal)
+    }
+}
+
+fn slot_index(slot: Slot) -> usize {
+    slot as usize
+}
+
+/// Move of an algorithm of the front right slot, turned towards the slot
+/// by a rotation of the cube around the U face.
+fn slot_move(slot: Slot, move_: &Move) -> Move {
+    // Faces whose moves are in the layers 4, 2, 5 and 3, each on the right
+    // once the previous one is
+    const SIDES: [usize; 4] = [4, 2, 5, 3];
+    let index = move_.clone() as usize;
+    match SIDES.iter().position(|side| *side == index / 3) {
+        Some(side) => ALL_MOVES[SIDES[(side + slot_index(slot)) % 4] * 3 + index % 3].clone(),
+        None => move_.clone(),
+    }
+}
+
+fn remaining(solved: &[bool; 4]) -> impl Iterator<Item = Slot> + '_ {
+    SLOTS.into_iter().filter(|slot| !solved[slot_index(*slot)])
+}
+
+impl Step for Solver {
+    const NAME: &'static str = "f2l_pairs";
+
+    fn generate() -> Self {
+        Self::generate_with_progress(&NoProgress)
+    }
+
+    fn generate_with_progress(progress: &dyn ProgressReporter) -> Self {
+        Self {
+            cross: PatternDatabase::generate::<Cube>(&ALL_MOVES, "Cross", progress),
+            front_right_block: PatternDatabase::generate::<Cube>(
+                &ALL_MOVES,
+                "F2LPairs/FRB",
+                progress,
+            ),
+            front_left_block: PatternDatabase::generate::<Cube>(
+                &ALL_MOVES,
+                "F2LPairs/FLB",
+                progress,
+            ),
+            back_left_block: PatternDatabase::generate::<Cube>(
+                &ALL_MOVES,
+                "F2LPairs/BLB",
+                progress,
+            ),
+            back_right_block: PatternDatabase::generate::<Cube>(
+                &ALL_MOVES,
+                "F2LPairs/BRB",
+                progress,
+            ),
+            lookahead: false,
+            algorithms: vec![],
+        }
+    }
+
+    fn solve(&self, cube: &Cube) -> Result<Vec<Move>, SolveError> {
+        self.solve_within(cube, &Budget::new(&SolveOptions::default()))
+    }
+
+    fn solve_within(&self, cube: &Cube, budget: &Budget) -> Result<Vec<Move>, SolveError> {
+        Ok(self
+            .solve_pairs(cube, budget)?
+            .into_iter()
+            .flat_map(|insertion| insertion.moves)
+            .collect())
+    }
+
+    /// Lower bound of the whole F2L, rather than the length of the pair by
+    /// pair solution which takes several searches to find.
+    fn lower_bound(&self, cube: &Cube) -> usize {
+        self.distance(cube, &[true; 4])
+    }
+
+    fn table_size() -> Option<u64> {
+        Some(
+            PatternDatabase::<Cross>::encoded_size::<Cube>()
+                + PatternDatabase::<FrontRightBlock>::encoded_size::<Cube>()
+                + PatternDatabase::<FrontLeftBlock>::encoded_size::<Cube>()
+                + PatternDatabase::<BackLeftBlock>::encoded_size::<Cube>()
+                + PatternDatabase::<BackRightBlock>::encoded_size::<Cube>(),
+        )
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.cross.memory_usage()
+            + self.front_right_block.memory_usage()
+            + self.front_left_block.memory_usage()
+            + self.back_left_block.memory_usage()
+            + self.back_right_block.memory_usage()
+    }
+}
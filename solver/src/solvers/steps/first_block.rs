@@ -0,0 +1,56 @@
+//! # Solve the first block of the Roux method using IDA* search.
+//!
+//! The first block is the 1x2x3 block on the L face. Its heuristic is the
+//! largest distance of its two halves, each made of the three edges and one
+//! of the corners.
+
+use cube::{algorithms::Move, Cube};
+use serde::{Deserialize, Serialize};
+
+use crate::solvers::{
+    cube_subsets::{FirstBlockBack, FirstBlockFront},
+    ida_solver::IDAStepSolver,
+    pattern_database::PatternDatabase,
+    progress::ProgressReporter,
+};
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Solver {
+    candidate_moves: Vec<Move>,
+    front: PatternDatabase<FirstBlockFront>,
+    back: PatternDatabase<FirstBlockBack>,
+}
+
+impl IDAStepSolver for Solver {
+    const NAME: &'static str = "first_block";
+    type State = Cube;
+
+    #[inline]
+    fn get_all_moves(&self) -> &[Move] {
+        &self.candidate_moves
+    }
+
+    fn assess_distance(&self, cube: &Cube) -> usize {
+        self.front.distance(cube).max(self.back.distance(cube))
+    }
+
+    fn heuristics_memory_usage(&self) -> usize {
+        self.front.memory_usage() + self.back.memory_usage()
+    }
+
+    fn heuristics_size() -> Option<u64> {
+        Some(
+            PatternDatabase::<FirstBlockFront>::encoded_size::<Self::State>()
+                + PatternDatabase::<FirstBlockBack>::encoded_size::<Self::State>(),
+        )
+    }
+
+    fn populate_candidate_moves(&mut self) {
+        self.candidate_moves = cube::algorithms::ALL_MOVES.to_vec();
+    }
+
+    fn populate_heuristics(&mut self, progress: &dyn ProgressReporter) {
+        self.front = self.generate_heuristic("FirstBlock/Front", progress);
+        self.back = self.generate_heuristic("FirstBlock/Back", progress);
+    }
+}
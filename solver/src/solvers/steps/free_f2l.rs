@@ -1,26 +1,26 @@
-use std::collections::HashMap;
-use std::ops::Add;
-
-use cube::algorithms::Move;
-use cube::subcases::CubeSubset;
+use cube::{algorithms::Move, Cube};
 use serde::{Deserialize, Serialize};
 
-use crate::solvers::cube_subsets::BLOCK_CASES;
 use crate::solvers::{
     cube_subsets::{BackLeftBlock, BackRightBlock, FrontLeftBlock, FrontRightBlock},
     ida_solver::IDAStepSolver,
+    pattern_database::PatternDatabase,
+    progress::ProgressReporter,
 };
 
 #[derive(Serialize, Deserialize, Default, Clone)]
 pub struct Solver {
     candidate_moves: Vec<Move>,
-    front_left_block: HashMap<FrontLeftBlock, usize>,
-    front_right_block: HashMap<FrontRightBlock, usize>,
-    back_left_block: HashMap<BackLeftBlock, usize>,
-    back_right_block: HashMap<BackRightBlock, usize>,
+    front_left_block: PatternDatabase<FrontLeftBlock>,
+    front_right_block: PatternDatabase<FrontRightBlock>,
+    back_left_block: PatternDatabase<BackLeftBlock>,
+    back_right_block: PatternDatabase<BackRightBlock>,
 }
 
 impl IDAStepSolver for Solver {
+    const NAME: &'static str = "free_f2l";
+    type State = Cube;
+
     #[inline]
     fn get_all_moves(&self) -> &[Move] {
         &self.candidate_moves
@@ -30,27 +30,38 @@ impl IDAStepSolver for Solver {
         self.candidate_moves = cube::algorithms::ALL_MOVES.to_vec();
     }
 
-    fn populate_heuristics(&mut self) {
-        self.front_left_block = self.generate_heuristic(BLOCK_CASES, "FreeF2L/FLB");
-        self.front_right_block = self.generate_heuristic(BLOCK_CASES, "FreeF2L/FRB");
-        self.back_left_block = self.generate_heuristic(BLOCK_CASES, "FreeF2L/BLB");
-        self.back_right_block = self.generate_heuristic(BLOCK_CASES, "FreeF2L/BRB");
+    fn populate_heuristics(&mut self, progress: &dyn ProgressReporter) {
+        self.front_left_block = self.generate_heuristic("FreeF2L/FLB", progress);
+        self.front_right_block = self.generate_heuristic("FreeF2L/FRB", progress);
+        self.back_left_block = self.generate_heuristic("FreeF2L/BLB", progress);
+        self.back_right_block = self.generate_heuristic("FreeF2L/BRB", progress);
     }
 
     fn assess_distance(&self, cube: &cube::Cube) -> usize {
-        let front_left_block = FrontLeftBlock::from_cube(cube);
-        let front_right_block = FrontRightBlock::from_cube(cube);
-        let back_left_block = BackLeftBlock::from_cube(cube);
-        let back_right_block = BackRightBlock::from_cube(cube);
-
-        let front_left_block_distance = self.front_left_block.get(&front_left_block).unwrap();
-        let front_right_block_distance = self.front_right_block.get(&front_right_block).unwrap();
-        let back_left_block_distance = self.back_left_block.get(&back_left_block).unwrap();
-        let back_right_block_distance = self.back_right_block.get(&back_right_block).unwrap();
+        let front_left_block_distance = self.front_left_block.distance(cube);
+        let front_right_block_distance = self.front_right_block.distance(cube);
+        let back_left_block_distance = self.back_left_block.distance(cube);
+        let back_right_block_distance = self.back_right_block.distance(cube);
 
         front_left_block_distance
-            .add(front_right_block_distance)
-            .add(back_left_block_distance)
-            .add(back_right_block_distance)
+            .saturating_add(front_right_block_distance)
+            .saturating_add(back_left_block_distance)
+            .saturating_add(back_right_block_distance)
+    }
+
+    fn heuristics_memory_usage(&self) -> usize {
+        self.front_left_block.memory_usage()
+            + self.front_right_block.memory_usage()
+            + self.back_left_block.memory_usage()
+            + self.back_right_block.memory_usage()
+    }
+
+    fn heuristics_size() -> Option<u64> {
+        Some(
+            PatternDatabase::<FrontLeftBlock>::encoded_size::<Self::State>()
+                + PatternDatabase::<FrontRightBlock>::encoded_size::<Self::State>()
+                + PatternDatabase::<BackLeftBlock>::encoded_size::<Self::State>()
+                + PatternDatabase::<BackRightBlock>::encoded_size::<Self::State>(),
+        )
     }
 }
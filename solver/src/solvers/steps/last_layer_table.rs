@@ -0,0 +1,524 @@
+//! # Tables of one-look algorithms of the last layer.
+//!
+//! With the first two layers solved, the last layer has 62208 states. The
+//! algorithms of a one-look step are generated with Dijkstra's algorithm
+//! over these states, from the states ending the step, with the algorithms
+//! keeping the first two layers solved as edges: the hardcoded OLL and PLL
+//! algorithms, their inverses, and the short algorithms found by pairing the
+//! sequences of up to [`HALF_LENGTH`] moves that leave the first two layers
+//! in the same state.
+//!
+//! Each state of the step gets the shortest chain of these algorithms, with
+//! the moves cancelled at the joins. The table gives the algorithm of each
+//! state, moves of the U face before and after included, so the step solves
+//! the cube with a single lookup.
+
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use cube::{
+    algorithms::{invert_algorithm, parse_algorithm, Move, ALL_MOVES},
+    subcases::{rank_orientations, rank_positions, unrank_orientations, unrank_positions},
+    Cube,
+};
+
+use crate::solvers::{
+    search_space::is_candidate,
+    solver::SolveError,
+    steps::{oll::OLL_ALGS, pll::PLL_ALGS},
+    table_store::fingerprint,
+    utils::{algorithm_table_size, append_cancelling, layer},
+};
+
+/// Length of the sequences paired into short algorithms.
+const HALF_LENGTH: usize = 4;
+
+/// States of the last layer, with the first two layers solved.
+pub(crate) const STATES: usize = 62208;
+
+/// Pieces of the last layer, each written as its home slot and its
+/// orientation, two bits each.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) struct LastLayer {
+    edges: [u8; 4],
+    corners: [u8; 4],
+}
+
+impl LastLayer {
+    const SOLVED: Self = Self {
+        edges: [0, 4, 8, 12],
+        corners: [0, 4, 8, 12],
+    };
+
+    /// Last layer of the cube, or `None` when the first two layers are not
+    /// solved.
+    pub(crate) fn from_cube(cube: &Cube) -> Option<Self> {
+        let solved = Cube::default();
+        if cube.edges[4..] != solved.edges[4..] || cube.corners[4..] != solved.corners[4..] {
+            return None;
+        }
+        let mut last_layer = Self::SOLVED;
+        for (slot, edge) in cube.edges[..4].iter().enumerate() {
+            last_layer.edges[slot] = (edge.piece.clone() as u8) << 2 | edge.orientation;
+        }
+        for (slot, corner) in cube.corners[..4].iter().enumerate() {
+            last_layer.corners[slot] = (corner.piece.clone() as u8) << 2 | corner.orientation;
+        }
+        Some(last_layer)
+    }
+
+    /// Last layer reached by the algorithm from the solved cube. The
+    /// algorithm must keep the first two layers solved.
+    fn of_algorithm(algorithm: &[Move]) -> Option<Self> {
+        let mut cube = Cube::default();
+        cube.execute_algorithm(algorithm);
+        Self::from_cube(&cube)
+    }
+
+    /// Apply the moves leading to `other` from the solved cube.
+    fn then(self, other: Self) -> Self {
+        let mut result = self;
+        for (slot, edge) in other.edges.iter().enumerate() {
+            let source = self.edges[usize::from(edge >> 2)];
+            result.edges[slot] = (source & !3) | (((source & 3) + (edge & 3)) % 2);
+        }
+        for (slot, corner) in other.corners.iter().enumerate() {
+            let source = self.corners[usize::from(corner >> 2)];
+            result.corners[slot] = (source & !3) | (((source & 3) + (corner & 3)) % 3);
+        }
+        result
+    }
+
+    /// Bounds of the coordinates of the edges and of the corners.
+    const EDGE_COORDINATES: usize = 24 * 16;
+    const CORNER_COORDINATES: usize = 24 * 81;
+
+    fn edge_coordinate(self) -> usize {
+        rank_positions(&self.edges.map(|edge| edge >> 2), 4) * 16
+            + rank_orientations(&self.edges.map(|edge| edge & 3), 2)
+    }
+
+    fn corner_coordinate(self) -> usize {
+        rank_positions(&self.corners.map(|corner| corner >> 2), 4) * 81
+            + rank_orientations(&self.corners.map(|corner| corner & 3), 3)
+    }
+
+    /// Dense index, which does not check that the state can be reached.
+    fn index(self) -> usize {
+        self.edge_coordinate() * Self::CORNER_COORDINATES + self.corner_coordinate()
+    }
+
+    fn from_coordinates(edges: usize, corners: usize) -> Self {
+        let mut last_layer = Self::SOLVED;
+        let mut positions = [0; 4];
+        let mut orientations = [0; 4];
+        unrank_positions(edges / 16, 4, &mut positions);
+        unrank_orientations(edges % 16, 2, &mut orientations);
+        for (slot, edge) in last_layer.edges.iter_mut().enumerate() {
+            *edge = positions[slot] << 2 | orientations[slot];
+        }
+        unrank_positions(corners / 81, 4, &mut positions);
+        unrank_orientations(corners % 81, 3, &mut orientations);
+        for (slot, corner) in last_layer.corners.iter_mut().enumerate() {
+            *corner = positions[slot] << 2 | orientations[slot];
+        }
+        last_layer
+    }
+
+    pub(crate) fn key(self) -> u32 {
+        self.edges
+            .iter()
+            .chain(&self.corners)
+            .fold(0, |key, piece| key << 4 | u32::from(*piece))
+    }
+
+    fn from_key(key: u32) -> Self {
+        let piece = |index: usize| (key >> (28 - 4 * index) & 0xF) as u8;
+        Self {
+            edges: [0, 1, 2, 3].map(piece),
+            corners: [4, 5, 6, 7].map(piece),
+        }
+    }
+
+    /// Smallest key of what `project` keeps of the state, up to the moves of
+    /// the U face before and after the algorithm.
+    fn case_key(self, project: impl Fn(Self) -> Self) -> u32 {
+        let u = Self::of_algorithm(&[Move::U]).unwrap();
+        let aufs = [Self::SOLVED, u, u.then(u), u.then(u).then(u)];
+        aufs.iter()
+            .flat_map(|pre_auf| aufs.iter().map(move |post_auf| (pre_auf, post_auf)))
+            .map(|(pre_auf, post_auf)| project(pre_auf.then(self).then(*post_auf)).key())
+            .min()
+            .unwrap()
+    }
+
+    /// Corners of the state, with the same edges for every state.
+    pub(crate) fn corners(self) -> Self {
+        Self {
+            edges: [0; 4],
+            corners: self.corners,
+        }
+    }
+
+    pub(crate) fn edges_oriented(self) -> bool {
+        self.edges.iter().all(|edge| edge & 3 == 0)
+    }
+
+    /// Whether the corners are solved, up to a move of the U face.
+    pub(crate) fn corners_solved(self) -> bool {
+        (0..4).any(|auf| {
+            self.corners
+                .iter()
+                .enumerate()
+                .all(|(slot, corner)| *corner == (((slot + auf) % 4) as u8) << 2)
+        })
+    }
+
+    pub(crate) fn is_solved(self) -> bool {
+        self == Self::SOLVED
+    }
+}
+
+/// Revision of the generated tables, which depend on the hardcoded
+/// algorithms.
+pub(crate) fn revision() -> u64 {
+    fingerprint(&[&OLL_ALGS[..], &PLL_ALGS[..]].concat())
+}
+
+/// Approximate size of the encoded table of the given number of states, in
+/// bytes, from the average length of their algorithms, moves of the U face
+/// included.
+pub(crate) fn table_size(states: usize, average_length: usize) -> u64 {
+    algorithm_table_size::<u32>(states, states * average_length)
+}
+
+/// Approximate time to generate a table on a single core. Dijkstra's
+/// algorithm goes through all the states whatever the states of the table,
+/// at about 20 µs a state.
+pub(crate) fn generation_time() -> Duration {
+    Duration::from_micros(20 * STATES as u64)
+}
+
+/// Algorithm of each state accepted by `keep`, leading to a state accepted
+/// by `goal`.
+pub(crate) fn generate(
+    goal: impl Fn(LastLayer) -> bool,
+    keep: impl Fn(LastLayer) -> bool,
+) -> HashMap<u32, Vec<Move>> {
+    let edges = generators();
+    // The edges and the corners of the last layer move independently
+    let edge_moves = edges
+        .iter()
+        .map(|(effect, _)| {
+            (0..LastLayer::EDGE_COORDINATES)
+                .map(|edges| {
+                    LastLayer::from_coordinates(edges, 0)
+                        .then(*effect)
+                        .edge_coordinate()
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let corner_moves = edges
+        .iter()
+        .map(|(effect, _)| {
+            (0..LastLayer::CORNER_COORDINATES)
+                .map(|corners| {
+                    LastLayer::from_coordinates(0, corners)
+                        .then(*effect)
+                        .corner_coordinate()
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let apply = |edge: usize, index: usize| {
+        edge_moves[edge][index / LastLayer::CORNER_COORDINATES] * LastLayer::CORNER_COORDINATES
+            + corner_moves[edge][index % LastLayer::CORNER_COORDINATES]
+    };
+
+    // Dijkstra's algorithm from the goals, through the inverse of the edges:
+    // the state before an algorithm is reached from the state after it. The
+    // costs are small, so the queue is a list of states for each cost.
+    let size = LastLayer::EDGE_COORDINATES * LastLayer::CORNER_COORDINATES;
+    let mut costs = vec![u8::MAX; size];
+    let mut next_edges = vec![None; size];
+    let mut queue: Vec<Vec<usize>> = vec![vec![]];
+    for state in all_states().into_iter().filter(|state| goal(*state)) {
+        costs[state.index()] = 0;
+        queue[0].push(state.index());
+    }
+    let mut cost = 0;
+    while cost < queue.len() {
+        while let Some(state) = queue[cost].pop() {
+            if usize::from(costs[state]) < cost {
+                continue;
+            }
+            for (edge, (_, algorithm)) in edges.iter().enumerate() {
+                let previous = apply(edge ^ 1, state);
+                let previous_cost = cost + algorithm.len();
+                if previous_cost < usize::from(costs[previous]) {
+                    costs[previous] = u8::try_from(previous_cost).unwrap();
+                    next_edges[previous] = Some(edge);
+                    if queue.len() <= previous_cost {
+                        queue.resize(previous_cost + 1, vec![]);
+                    }
+                    queue[previous_cost].push(previous);
+                }
+            }
+        }
+        cost += 1;
+    }
+
+    all_states()
+        .into_iter()
+        .filter(|state| keep(*state))
+        .map(|start| {
+            let mut algorithm = vec![];
+            let mut state = start.index();
+            while let Some(edge) = next_edges[state] {
+                append_cancelling(&mut algorithm, &edges[edge].1);
+                state = apply(edge, state);
+            }
+            (start.key(), algorithm)
+        })
+        .collect()
+}
+
+/// Every state of the last layer, with the first two layers solved.
+fn all_states() -> Vec<LastLayer> {
+    let mut states = vec![LastLayer::SOLVED];
+    let mut index = 0;
+    let mut seen = HashSet::from([LastLayer::SOLVED]);
+    let moves = [
+        parse_algorithm("U"),
+        parse_algorithm(PLL_ALGS[5]),
+        parse_algorithm(OLL_ALGS[27]),
+        parse_algorithm(OLL_ALGS[45]),
+    ]
+    .map(|algorithm| LastLayer::of_algorithm(&algorithm).unwrap());
+    while index < states.len() {
+        for effect in moves {
+            let state = states[index].then(effect);
+            if seen.insert(state) {
+                states.push(state);
+            }
+        }
+        index += 1;
+    }
+    states
+}
+
+/// Algorithms keeping the first two layers solved, the shortest of each
+/// effect on the last layer, each followed by its inverse.
+fn generators() -> Vec<(LastLayer, Vec<Move>)> {
+    let mut shortest: HashMap<LastLayer, Vec<Move>> = HashMap::new();
+    let mut add = |algorithm: Vec<Move>| {
+        let Some(effect) = LastLayer::of_algorithm(&algorithm) else {
+            return;
+        };
+        // Moves of the U face around an algorithm are edges of their own
+        let is_u_move = |move_: &Move| layer(move_) == layer(&Move::U);
+        if effect.is_solved()
+            || algorithm.len() > 1
+                && (algorithm.first().is_some_and(is_u_move)
+                    || algorithm.last().is_some_and(is_u_move))
+        {
+            return;
+        }
+        if shortest
+            .get(&effect)
+            .is_none_or(|known| algorithm.len() < known.len())
+        {
+            shortest.insert(effect, algorithm);
+        }
+    };
+
+    for algorithm in OLL_ALGS.iter().chain(&PLL_ALGS) {
+        let algorithm = parse_algorithm(algorithm);
+        add(invert_algorithm(&algorithm));
+        add(algorithm);
+    }
+
+    // Two sequences leaving the first two layers in the same state make an
+    // algorithm of the last layer
+    let mut sequences: HashMap<Vec<u8>, Vec<Vec<Move>>> = HashMap::new();
+    let mut path = vec![];
+    collect_sequences(&mut Cube::default(), &mut path, &mut sequences);
+    for group in sequences.values() {
+        for first in group {
+            for second in group {
+                let mut algorithm = first.clone();
+                append_cancelling(&mut algorithm, &invert_algorithm(second));
+                add(algorithm);
+            }
+        }
+    }
+
+    let mut generators = vec![];
+    let mut effects = shortest.into_iter().collect::<Vec<_>>();
+    effects.sort_by_key(|(effect, _)| *effect);
+    for (effect, algorithm) in effects {
+        let inverse = invert_algorithm(&algorithm);
+        let inverse_effect = LastLayer::of_algorithm(&inverse).unwrap();
+        generators.push((effect, algorithm));
+        generators.push((inverse_effect, inverse));
+    }
+    generators
+}
+
+/// Group the sequences of up to [`HALF_LENGTH`] moves by the state of the
+/// first two layers they lead to.
+fn collect_sequences(
+    cube: &mut Cube,
+    path: &mut Vec<Move>,
+    sequences: &mut HashMap<Vec<u8>, Vec<Vec<Move>>>,
+) {
+    let first_two_layers = cube
+        .edges
+        .iter()
+        .map(|edge| (edge.piece.clone() as u8) << 1 | edge.orientation)
+        .chain(
+            cube.corners
+                .iter()
+                .map(|corner| (corner.piece.clone() as u8) << 2 | corner.orientation),
+        )
+        .enumerate()
+        .map(|(index, piece)| {
+            // Pieces of the last layer are told apart from the others only
+            let home = if index < 12 { piece >> 1 } else { piece >> 2 };
+            if home < 4 {
+                u8::MAX
+            } else {
+                piece
+            }
+        })
+        .collect::<Vec<_>>();
+    sequences
+        .entry(first_two_layers)
+        .or_default()
+        .push(path.clone());
+    if path.len() == HALF_LENGTH {
+        return;
+    }
+    for move_ in &ALL_MOVES {
+        if !is_candidate(move_, path) {
+            continue;
+        }
+        cube.execute_move(move_);
+        path.push(move_.clone());
+        collect_sequences(cube, path, sequences);
+        path.pop();
+        cube.execute_move(&move_.inverse());
+    }
+}
+
+/// Number of the case of each state of the table, up to the moves of the U
+/// face before and after the algorithm, the cases being told apart by what
+/// `project` keeps of the states. The solved case is numbered 0, and the
+/// others from 1 in the order of their keys.
+pub(crate) fn case_numbers(
+    cases: &HashMap<u32, Vec<Move>>,
+    project: impl Fn(LastLayer) -> LastLayer,
+) -> HashMap<u32, usize> {
+    let solved = LastLayer::SOLVED.case_key(&project);
+    let mut case_keys = cases
+        .keys()
+        .map(|key| LastLayer::from_key(*key).case_key(&project))
+        .filter(|case_key| *case_key != solved)
+        .collect::<Vec<_>>();
+    case_keys.sort_unstable();
+    case_keys.dedup();
+    std::iter::once(solved)
+        .chain(case_keys)
+        .enumerate()
+        .map(|(number, case_key)| (case_key, number))
+        .collect()
+}
+
+/// Name of the case of the cube, such as `ZBLL 12` or `ZBLL skip`, with the
+/// numbers given by [`case_numbers`].
+pub(crate) fn case_name(
+    cases: &HashMap<u32, Vec<Move>>,
+    numbers: &HashMap<u32, usize>,
+    project: impl Fn(LastLayer) -> LastLayer,
+    cube: &Cube,
+    step: &str,
+) -> Option<String> {
+    let last_layer = LastLayer::from_cube(cube).filter(|state| cases.contains_key(&state.key()))?;
+    Some(match numbers.get(&last_layer.case_key(project))? {
+        0 => format!("{step} skip"),
+        number => format!("{step} {number}"),
+    })
+}
+
+/// Algorithm of the state of the last layer of the cube.
+pub(crate) fn lookup(
+    cases: &HashMap<u32, Vec<Move>>,
+    cube: &Cube,
+    step: &'static str,
+) -> Result<Vec<Move>, SolveError> {
+    LastLayer::from_cube(cube)
+        .and_then(|last_layer| cases.get(&last_layer.key()))
+        .cloned()
+        .ok_or(SolveError::UnreachableCase { step })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Number of cases of the states accepted by `keep`, up to the moves of
+    /// the U face before and after the algorithm, as told apart by
+    /// `project`.
+    fn count_cases(
+        keep: impl Fn(LastLayer) -> bool,
+        project: impl Fn(LastLayer) -> LastLayer,
+    ) -> usize {
+        all_states()
+            .into_iter()
+            .filter(|state| keep(*state))
+            .map(|state| state.case_key(&project))
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    #[test]
+    fn count_last_layer_cases() {
+        assert_eq!(all_states().len(), STATES);
+        // The edges are oriented in 1 of the 8 reachable orientations
+        let edges_oriented = all_states()
+            .into_iter()
+            .filter(|state| state.edges_oriented())
+            .count();
+        assert_eq!(edges_oriented, STATES / 8);
+        // The solved case included
+        let all = |state| state;
+        assert_eq!(count_cases(|_| true, all), 3916);
+        assert_eq!(count_cases(LastLayer::edges_oriented, all), 494);
+        // COLL tells the cases apart by their corners only, the solved
+        // corners excluded
+        let coll_cases = count_cases(
+            |state| state.edges_oriented() && !state.corners_solved(),
+            LastLayer::corners,
+        );
+        assert_eq!(coll_cases, 42);
+    }
+
+    #[test]
+    fn solve_every_state() {
+        let cases = generate(LastLayer::is_solved, |_| true);
+        assert_eq!(cases.len(), STATES);
+        let mut lengths = 0;
+        for state in all_states() {
+            let algorithm = &cases[&state.key()];
+            let mut cube = Cube::default();
+            cube.execute_algorithm(&invert_algorithm(algorithm));
+            assert!(LastLayer::from_cube(&cube) == Some(state));
+            lengths += algorithm.len();
+        }
+        // Shorter than OLL and PLL with their moves of the U face, on average
+        assert!(lengths < 17 * STATES);
+    }
+}
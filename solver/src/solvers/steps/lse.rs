@@ -0,0 +1,147 @@
+//! # Solve the last six edges of the Roux method using IDA* search.
+//!
+//! Once the blocks and the corners are solved, only the U and M moves are
+//! needed to orient the edges, place UL and UR, then solve the M slice. The
+//! pattern database covers every case of the step, so the search goes
+//! straight to an optimal solution. The Roux method splits it into these
+//! three parts, each ending where the solution first completes it.
+
+use serde::{Deserialize, Serialize};
+
+use cube::{algorithms::Move, Cube};
+
+use crate::solvers::{
+    cube_subsets::Lse,
+    ida_solver::IDAStepSolver,
+    options::Budget,
+    pattern_database::PatternDatabase,
+    progress::ProgressReporter,
+    search_space::{SearchState, StateSubset},
+    slice_cube::{SliceCube, SliceMove},
+    solution::{SearchStats, Solution, Stage},
+    solver::{SolveError, Step},
+};
+
+/// Cases of the parts of the step.
+const PARTS: [&str; 3] = ["EO", "UL/UR", "M slice"];
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Solver {
+    candidate_moves: Vec<SliceMove>,
+    cases: PatternDatabase<Lse>,
+}
+
+impl Solver {
+    /// Solve the step like [`Step::apply`], and append its solution as one
+    /// stage per part, whose case names the part. The first stage holds the
+    /// statistics of the search.
+    pub(crate) fn apply_in_parts(
+        &self,
+        cube: &mut Cube,
+        solution: &mut Solution,
+        budget: &Budget,
+    ) -> Result<(), SolveError> {
+        let mut state = cube.clone();
+        self.apply(cube, solution, budget)?;
+        let stage = solution.stages.pop().expect("The step appended its stage");
+        let mut parts = vec![vec![]; PARTS.len()];
+        let mut done = parts_done(&state).unwrap_or(0);
+        for move_ in stage.moves {
+            parts[done.min(PARTS.len() - 1)].push(move_.clone());
+            state.execute_move(&move_);
+            // Parts only end between slice moves
+            done = parts_done(&state).map_or(done, |parts| parts.max(done));
+        }
+        for (index, (part, moves)) in PARTS.iter().zip(parts).enumerate() {
+            solution.stages.push(Stage {
+                case: Some(part.to_string()),
+                stats: if index == 0 {
+                    stage.stats
+                } else {
+                    SearchStats::default()
+                },
+                ..Stage::new(<Self as Step>::NAME, moves)
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Number of parts of the step the cube has done, or `None` in the middle
+/// of a slice move.
+fn parts_done(cube: &Cube) -> Option<usize> {
+    let state = SliceCube::from_cube(cube);
+    let lse = Lse::from_state(&state);
+    if !lse.is_valid() {
+        return None;
+    }
+    if !lse.is_oriented() {
+        return Some(0);
+    }
+    let mut aligned = state.cube;
+    while aligned.corners != Cube::default().corners {
+        aligned.execute_move(&Move::U);
+    }
+    let placed = [cube::UL, cube::UR]
+        .iter()
+        .all(|slot| aligned.edges[*slot].piece.clone() as usize == *slot);
+    Some(if placed { 2 } else { 1 })
+}
+
+impl IDAStepSolver for Solver {
+    const NAME: &'static str = "lse";
+    type State = SliceCube;
+
+    #[inline]
+    fn get_all_moves(&self) -> &[SliceMove] {
+        &self.candidate_moves
+    }
+
+    fn assess_distance(&self, state: &SliceCube) -> usize {
+        self.cases.distance(state)
+    }
+
+    fn heuristics_memory_usage(&self) -> usize {
+        self.cases.memory_usage()
+    }
+
+    fn heuristics_size() -> Option<u64> {
+        Some(PatternDatabase::<Lse>::encoded_size::<Self::State>())
+    }
+
+    fn populate_candidate_moves(&mut self) {
+        self.candidate_moves = vec![
+            SliceMove::Face(Move::U),
+            SliceMove::Face(Move::U2),
+            SliceMove::Face(Move::Up),
+            SliceMove::M,
+            SliceMove::M2,
+            SliceMove::Mp,
+        ];
+    }
+
+    fn populate_heuristics(&mut self, progress: &dyn ProgressReporter) {
+        self.cases = self.generate_heuristic("LSE", progress);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts_after(algorithm: &[SliceMove]) -> Option<usize> {
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&SliceCube::default().face_moves(algorithm));
+        parts_done(&cube)
+    }
+
+    #[test]
+    fn recognise_parts() {
+        use SliceMove::{Face, Mp, M, M2};
+        assert_eq!(parts_after(&[]), Some(2));
+        assert_eq!(parts_after(&[M, Face(Move::U), Mp]), Some(0));
+        assert_eq!(parts_after(&[Face(Move::U), M2, Face(Move::Up)]), Some(1));
+        assert_eq!(parts_after(&[M2, Face(Move::U2), M2]), Some(2));
+        assert_eq!(parts_after(&[Face(Move::R)]), None);
+    }
+}
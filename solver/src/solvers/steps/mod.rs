@@ -1,8 +1,28 @@
 pub mod all;
+pub mod cmll;
+pub mod coll;
 pub mod cross;
+pub mod eo_cross;
+pub mod eo_line;
 pub mod f2l;
+pub mod f2l_pairs;
+pub mod first_block;
 pub mod free_f2l;
+pub(crate) mod last_layer_table;
+pub mod lse;
 pub mod oll;
+pub mod one_look_last_layer;
 pub mod orientation;
 pub mod permutation;
+pub mod petrus_block;
+pub mod petrus_eo;
+pub mod petrus_expansion;
 pub mod pll;
+pub mod second_block;
+pub mod thistlethwaite_co;
+pub mod thistlethwaite_eo;
+pub mod thistlethwaite_half_turns;
+pub mod thistlethwaite_tetrads;
+pub mod zbll;
+pub mod zz_left_block;
+pub mod zz_right_block;
@@ -0,0 +1,107 @@
+//! # Solve the last layer in one look.
+//!
+//! The algorithms are generated, see [`last_layer_table`].
+
+use std::{collections::HashMap, sync::OnceLock, time::Duration};
+
+use cube::{algorithms::Move, Cube};
+use serde::{Deserialize, Serialize};
+
+use crate::solvers::{
+    solver::{SolveError, Step},
+    steps::last_layer_table::{self, LastLayer},
+    utils::algorithm_table_memory_usage,
+};
+
+/// Average length of the algorithms, moves of the U face included, as
+/// measured on the generated table.
+const AVERAGE_LENGTH: usize = 16;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Solver {
+    cases: HashMap<u32, Vec<Move>>,
+    #[serde(skip)]
+    case_numbers: OnceLock<HashMap<u32, usize>>,
+}
+
+impl Step for Solver {
+    const NAME: &'static str = "one_look_last_layer";
+
+    fn revision() -> u64 {
+        last_layer_table::revision()
+    }
+
+    fn generate() -> Self {
+        Self {
+            cases: last_layer_table::generate(LastLayer::is_solved, |_| true),
+            case_numbers: OnceLock::new(),
+        }
+    }
+
+    fn solve(&self, cube: &Cube) -> Result<Vec<Move>, SolveError> {
+        last_layer_table::lookup(&self.cases, cube, Self::NAME)
+    }
+
+    /// Name of the case, such as `1LLL 1234`, numbered in the order of the
+    /// keys of the cases
+    fn case_name(&self, cube: &Cube) -> Option<String> {
+        let numbers = self
+            .case_numbers
+            .get_or_init(|| last_layer_table::case_numbers(&self.cases, |state| state));
+        last_layer_table::case_name(&self.cases, numbers, |state| state, cube, "1LLL")
+    }
+
+    fn table_size() -> Option<u64> {
+        Some(last_layer_table::table_size(
+            last_layer_table::STATES,
+            AVERAGE_LENGTH,
+        ))
+    }
+
+    fn generation_time() -> Option<Duration> {
+        Some(last_layer_table::generation_time())
+    }
+
+    fn memory_usage(&self) -> usize {
+        algorithm_table_memory_usage(&self.cases)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cube::algorithms::{invert_algorithm, parse_algorithm};
+
+    use super::*;
+
+    fn case(algorithm: &str) -> Cube {
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&invert_algorithm(&parse_algorithm(algorithm)));
+        cube
+    }
+
+    #[test]
+    fn measure_average_length() {
+        let solver = Solver::generate();
+        let moves = solver.cases.values().map(Vec::len).sum::<usize>();
+        let average = (moves + solver.cases.len() / 2) / solver.cases.len();
+        assert_eq!(average, AVERAGE_LENGTH);
+    }
+
+    #[test]
+    fn name_cases() {
+        let solver = Solver::generate();
+        let case_number = |algorithm: &str| {
+            let name = solver.case_name(&case(algorithm)).unwrap();
+            name.strip_prefix("1LLL ")
+                .unwrap()
+                .parse::<usize>()
+                .unwrap()
+        };
+        let sune = case_number("R U R' U R U2 R'");
+        assert!((1..=3915).contains(&sune));
+        assert_eq!(case_number("U R U R' U R U2 R' U2"), sune);
+        assert_ne!(case_number("F R U R' U' F'"), sune);
+        assert_eq!(solver.case_name(&Cube::default()).unwrap(), "1LLL skip");
+        assert_eq!(solver.case_name(&case("F")), None);
+    }
+}
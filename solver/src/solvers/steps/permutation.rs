@@ -1,34 +1,45 @@
-use std::collections::HashMap;
-
-use cube::{algorithms::Move, subcases::CubeSubset};
+use cube::{algorithms::Move, Cube};
 use serde::{Deserialize, Serialize};
 
 use crate::solvers::{
-    cube_subsets::{CornerPermutation, EdgePermutation, CP_CASES, EP_CASES},
+    cube_subsets::{CornerPermutation, EdgePermutation},
     ida_solver::IDAStepSolver,
+    pattern_database::PatternDatabase,
+    progress::ProgressReporter,
 };
 
 #[derive(Serialize, Deserialize, Default, Clone)]
 pub struct Solver {
     candidate_moves: Vec<Move>,
-    corner_permutation: HashMap<CornerPermutation, usize>,
-    edge_permutation: HashMap<EdgePermutation, usize>,
+    corner_permutation: PatternDatabase<CornerPermutation>,
+    edge_permutation: PatternDatabase<EdgePermutation>,
 }
 
 impl IDAStepSolver for Solver {
+    const NAME: &'static str = "permutation";
+    type State = Cube;
+
     #[inline]
     fn get_all_moves(&self) -> &[Move] {
         &self.candidate_moves
     }
 
     fn assess_distance(&self, cube: &cube::Cube) -> usize {
-        let corner_permutation = CornerPermutation::from_cube(cube);
-        let edge_permutation = EdgePermutation::from_cube(cube);
+        let corner_permutation_moves = self.corner_permutation.distance(cube);
+        let edge_permutation_moves = self.edge_permutation.distance(cube);
 
-        let corner_permutation_moves = self.corner_permutation.get(&corner_permutation).unwrap();
-        let edge_permutation_moves = self.edge_permutation.get(&edge_permutation).unwrap();
+        corner_permutation_moves.max(edge_permutation_moves)
+    }
+
+    fn heuristics_memory_usage(&self) -> usize {
+        self.corner_permutation.memory_usage() + self.edge_permutation.memory_usage()
+    }
 
-        *corner_permutation_moves.max(edge_permutation_moves)
+    fn heuristics_size() -> Option<u64> {
+        Some(
+            PatternDatabase::<CornerPermutation>::encoded_size::<Self::State>()
+                + PatternDatabase::<EdgePermutation>::encoded_size::<Self::State>(),
+        )
     }
 
     fn populate_candidate_moves(&mut self) {
@@ -46,8 +57,8 @@ impl IDAStepSolver for Solver {
         ]);
     }
 
-    fn populate_heuristics(&mut self) {
-        self.corner_permutation = self.generate_heuristic(CP_CASES, "Permutation/Corner");
-        self.edge_permutation = self.generate_heuristic(EP_CASES, "Permutation/Edge");
+    fn populate_heuristics(&mut self, progress: &dyn ProgressReporter) {
+        self.corner_permutation = self.generate_heuristic("Permutation/Corner", progress);
+        self.edge_permutation = self.generate_heuristic("Permutation/Edge", progress);
     }
 }
@@ -0,0 +1,71 @@
+//! # Orient the edges left after the 2x2x3 block of the Petrus method.
+//!
+//! F quarter turns flip the edges, but also break the 2x2x3 block, so the
+//! search uses `<R, U, F>` and only stops once the block is back.
+
+use cube::{algorithms::Move, subcases::CubeSubset, Cube};
+use serde::{Deserialize, Serialize};
+
+use crate::solvers::{
+    cube_subsets::{BackLeftBlock, EoLine, FrontLeftBlock},
+    ida_solver::IDAStepSolver,
+    pattern_database::PatternDatabase,
+    progress::ProgressReporter,
+};
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Solver {
+    candidate_moves: Vec<Move>,
+    eo_line: PatternDatabase<EoLine>,
+    front_left_block: PatternDatabase<FrontLeftBlock>,
+}
+
+impl IDAStepSolver for Solver {
+    const NAME: &'static str = "petrus_eo";
+    type State = Cube;
+
+    #[inline]
+    fn get_all_moves(&self) -> &[Move] {
+        &self.candidate_moves
+    }
+
+    fn assess_distance(&self, cube: &Cube) -> usize {
+        // None of the moves can fix the 2x2x2 block.
+        if BackLeftBlock::from_cube(cube) != BackLeftBlock::from_cube(&Cube::default()) {
+            return usize::MAX;
+        }
+        self.eo_line
+            .distance(cube)
+            .max(self.front_left_block.distance(cube))
+    }
+
+    fn heuristics_memory_usage(&self) -> usize {
+        self.eo_line.memory_usage() + self.front_left_block.memory_usage()
+    }
+
+    fn heuristics_size() -> Option<u64> {
+        Some(
+            PatternDatabase::<EoLine>::encoded_size::<Self::State>()
+                + PatternDatabase::<FrontLeftBlock>::encoded_size::<Self::State>(),
+        )
+    }
+
+    fn populate_candidate_moves(&mut self) {
+        self.candidate_moves = vec![
+            Move::U,
+            Move::U2,
+            Move::Up,
+            Move::F,
+            Move::F2,
+            Move::Fp,
+            Move::R,
+            Move::R2,
+            Move::Rp,
+        ];
+    }
+
+    fn populate_heuristics(&mut self, progress: &dyn ProgressReporter) {
+        self.eo_line = self.generate_heuristic("Petrus/EO", progress);
+        self.front_left_block = self.generate_heuristic("Petrus/Expansion", progress);
+    }
+}
@@ -0,0 +1,64 @@
+//! # Expand the 2x2x2 block of the Petrus method to a 2x2x3 block.
+//!
+//! The DF and FL edges and the DLF corner are added with `<R, U, F>`, which
+//! never moves the 2x2x2 block.
+
+use cube::{algorithms::Move, subcases::CubeSubset, Cube};
+use serde::{Deserialize, Serialize};
+
+use crate::solvers::{
+    cube_subsets::{BackLeftBlock, FrontLeftBlock},
+    ida_solver::IDAStepSolver,
+    pattern_database::PatternDatabase,
+    progress::ProgressReporter,
+};
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Solver {
+    candidate_moves: Vec<Move>,
+    front_left_block: PatternDatabase<FrontLeftBlock>,
+}
+
+impl IDAStepSolver for Solver {
+    const NAME: &'static str = "petrus_expansion";
+    type State = Cube;
+
+    #[inline]
+    fn get_all_moves(&self) -> &[Move] {
+        &self.candidate_moves
+    }
+
+    fn assess_distance(&self, cube: &Cube) -> usize {
+        // None of the moves can fix the 2x2x2 block.
+        if BackLeftBlock::from_cube(cube) != BackLeftBlock::from_cube(&Cube::default()) {
+            return usize::MAX;
+        }
+        self.front_left_block.distance(cube)
+    }
+
+    fn heuristics_memory_usage(&self) -> usize {
+        self.front_left_block.memory_usage()
+    }
+
+    fn heuristics_size() -> Option<u64> {
+        Some(PatternDatabase::<FrontLeftBlock>::encoded_size::<Self::State>())
+    }
+
+    fn populate_candidate_moves(&mut self) {
+        self.candidate_moves = vec![
+            Move::U,
+            Move::U2,
+            Move::Up,
+            Move::F,
+            Move::F2,
+            Move::Fp,
+            Move::R,
+            Move::R2,
+            Move::Rp,
+        ];
+    }
+
+    fn populate_heuristics(&mut self, progress: &dyn ProgressReporter) {
+        self.front_left_block = self.generate_heuristic("Petrus/Expansion", progress);
+    }
+}
@@ -3,14 +3,24 @@
 //! There are 21 PLL cases, so we can generate a lookup table beforehand and use
 //! it to solve the cube in a single hash table lookup.
 //!
-//! For now the lookup table is hardcoded, but in the future I might be able to
-//! generate it programmatically.
+//! The lookup table is built from a hardcoded list of algorithms, which
+//! [`crate::solvers::last_layer`] audits against the cases of the step and
+//! against algorithms it generates.
 
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use serde::{Deserialize, Serialize};
 
-use crate::solvers::solver::Step;
+use crate::solvers::{
+    algorithm_set::case_name,
+    last_layer::LastLayerStep,
+    solver::{SolveError, Step},
+    table_store::fingerprint,
+    utils::{
+        algorithm_table_memory_usage, algorithm_table_size, generation_time,
+        ALGORITHM_TABLE_NANOS_PER_BYTE,
+    },
+};
 
 use cube::{
     algorithms::{invert_algorithm, invert_move, parse_algorithm, Move},
@@ -21,52 +31,58 @@ use cube::{
 use crate::solvers::cube_subsets::Pll;
 use crate::solvers::cube_subsets::PLL_CASES;
 
+pub(crate) const PLL_ALGS: [&str; 22] = [
+    "",
+    "R' F R' B2 R F' R' B2 R2",
+    "R2 B2 R F R' B2 R F' R",
+    "R U' R U R U R U' R' U' R2",
+    "R2 U R U R' U' R' U' R' U R'",
+    "R U R' U' R' F R2 U' R' U' R U R' F'",
+    "L R2 U R U R2 U' R' U' R2 U' R U2 L' U R'",
+    "R U R D R' U R D' R' U' R D R' U' R D' R2",
+    "L U2 L' U2 L F' L' U' L U L F L2",
+    "R' U2 R U2 R' F R U R' U' R' F' R2'",
+    "R2 U R' U R' U' R U' R2 D U' R' U R D'",
+    "R' U' R U D' R2 U R' U R U' R U' R2 D",
+    "R2 U' R U' R U R' U R2 D' U R U' R' D",
+    "R U R' U' D R2 U' R U' R' U R' U R2 D'",
+    "L U' R' U L' U2 R U' R' U2 R",
+    "R U R' F' R U R' U' R' F R2 U' R'",
+    "R U R' U R U R' F' R U R' U' R' F R2 U' R' U2 R U' R'",
+    "R' U R U' R' F' U' F R U R' F R' F' R U' R",
+    "R2 U2 R U2 R2 U2 R2 U2 R U2 R2",
+    "R' U' R U' R U R U' R' U R U R2 U' R'",
+    "R' U' R U' L R U2 R' U' R U2 L' U R2 U R",
+    "R' U R' U' B' R' B2 U' B' U B' R B R",
+];
+
+/// Names of the cases of [`PLL_ALGS`], the solved case excepted.
+pub(crate) const PLL_NAMES: [&str; 22] = [
+    "", "Aa", "Ab", "Ua", "Ub", "T", "F", "E", "Ra", "Rb", "Ga", "Gb", "Gc", "Gd", "Ja", "Jb",
+    "Na", "Nb", "H", "Z", "Y", "V",
+];
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Solver {
     cases: HashMap<Pll, Vec<Move>>,
 }
 
 impl Solver {
-    fn get_cases() -> HashMap<Pll, Vec<Move>> {
+    /// Solver looking up the given algorithms, each solving its case.
+    pub(crate) fn from_algorithms(algorithms: &[Vec<Move>]) -> Self {
         let mut cases = HashMap::with_capacity(PLL_CASES);
 
-        let pll_algs = vec![
-            "",
-            "R' F R' B2 R F' R' B2 R2",
-            "R2 B2 R F R' B2 R F' R",
-            "R U' R U R U R U' R' U' R2",
-            "R2 U R U R' U' R' U' R' U R'",
-            "R U R' U' R' F R2 U' R' U' R U R' F'",
-            "L R2 U R U R2 U' R' U' R2 U' R U2 L' U R'",
-            "R U R D R' U R D' R' U' R D R' U' R D' R2",
-            "L U2 L' U2 L F' L' U' L U L F L2",
-            "R' U2 R U2 R' F R U R' U' R' F' R2'",
-            "R2 U R' U R' U' R U' R2 D U' R' U R D'",
-            "R' U' R U D' R2 U R' U R U' R U' R2 D",
-            "R2 U' R U' R U R' U R2 D' U R U' R' D",
-            "R U R' U' D R2 U' R U' R' U R' U R2 D'",
-            "L U' R' U L' U2 R U' R' U2 R",
-            "R U R' F' R U R' U' R' F R2 U' R'",
-            "R U R' U R U R' F' R U R' U' R' F R2 U' R' U2 R U' R'",
-            "R' U R U' R' F' U' F R U R' F R' F' R U' R",
-            "R2 U2 R U2 R2 U2 R2 U2 R U2 R2",
-            "R' U' R U' R U R U' R' U R U R2 U' R'",
-            "R' U' R U' L R U2 R' U' R U2 L' U R2 U R",
-            "R' U R' U' B' R' B2 U' B' U B' R B R",
-        ];
-
-        for alg in pll_algs {
-            let alg = parse_algorithm(alg);
+        for alg in algorithms {
+            let case_alg = invert_algorithm(alg);
             for auf in &[Move::None, Move::U, Move::U2, Move::Up] {
                 let mut cube = Cube::default();
                 cube.execute_move(auf);
-                cube.execute_algorithm(&alg);
+                cube.execute_algorithm(&case_alg);
                 let case = Pll::from_cube(&cube);
-                let alg = invert_algorithm(&alg);
-                cases.insert(case, alg);
+                cases.insert(case, alg.clone());
             }
         }
-        cases
+        Self { cases }
     }
 
     fn is_solved(cube: &Cube) -> bool {
@@ -75,12 +91,17 @@ impl Solver {
 }
 
 impl Step for Solver {
+    const NAME: &'static str = "pll";
+
+    fn revision() -> u64 {
+        fingerprint(&PLL_ALGS)
+    }
+
     fn generate() -> Self {
-        let cases = Self::get_cases();
-        Self { cases }
+        Self::from_algorithms(&LastLayerStep::Pll.hardcoded_algorithms())
     }
 
-    fn solve(&self, cube: &Cube) -> Vec<Move> {
+    fn solve(&self, cube: &Cube) -> Result<Vec<Move>, SolveError> {
         let mut cube = cube.clone();
         for pre_u_move in &[Move::None, Move::U, Move::U2, Move::Up] {
             cube.execute_move(pre_u_move);
@@ -99,13 +120,60 @@ impl Step for Solver {
                             .cloned()
                             .collect::<Vec<Move>>();
 
-                        return solution;
+                        return Ok(solution);
                     }
                     cube.execute_move(&invert_move(&post_u_move));
                 }
             }
             cube.execute_move(&invert_move(pre_u_move));
         }
-        vec![]
+        Err(SolveError::UnreachableCase { step: Self::NAME })
+    }
+
+    fn case_name(&self, cube: &Cube) -> Option<String> {
+        case_name(LastLayerStep::Pll, cube)
+    }
+
+    fn table_size() -> Option<u64> {
+        // A case for each move of the U face before the algorithm
+        let moves: usize = PLL_ALGS.iter().map(|alg| parse_algorithm(alg).len()).sum();
+        Some(algorithm_table_size::<Pll>(PLL_ALGS.len() * 4, moves * 4))
+    }
+
+    fn generation_time() -> Option<Duration> {
+        Self::table_size().map(|size| generation_time(size, ALGORITHM_TABLE_NANOS_PER_BYTE))
+    }
+
+    fn memory_usage(&self) -> usize {
+        algorithm_table_memory_usage(&self.cases)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cube::algorithms::parse_algorithm;
+
+    use super::*;
+
+    #[test]
+    fn solve_t_perm() {
+        let solver = Solver::generate();
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&parse_algorithm("R U R' U' R' F R2 U' R' U' R U R' F'"));
+
+        let solution = solver.solve(&cube).unwrap();
+        cube.execute_algorithm(&solution);
+        assert_eq!(cube, Cube::default());
+    }
+
+    #[test]
+    fn reject_unsolved_f2l() {
+        let solver = Solver::generate();
+        let mut cube = Cube::default();
+        cube.execute_move(&Move::R);
+        assert!(matches!(
+            solver.solve(&cube),
+            Err(SolveError::UnreachableCase { step: "pll" })
+        ));
     }
 }
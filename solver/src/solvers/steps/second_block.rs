@@ -0,0 +1,75 @@
+//! # Solve the second block of the Roux method using IDA* search.
+//!
+//! The second block is the 1x2x3 block on the R face, built with `<R, r, U, M>`
+//! so that the first block is never disturbed. The search runs on a
+//! [`SliceCube`], which knows about `M` and `r`.
+
+use serde::{Deserialize, Serialize};
+
+use cube::algorithms::Move;
+
+use crate::solvers::{
+    cube_subsets::{is_first_block_solved, SecondBlockBack, SecondBlockFront},
+    ida_solver::IDAStepSolver,
+    pattern_database::PatternDatabase,
+    progress::ProgressReporter,
+    slice_cube::{SliceCube, SliceMove},
+};
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Solver {
+    candidate_moves: Vec<SliceMove>,
+    front: PatternDatabase<SecondBlockFront>,
+    back: PatternDatabase<SecondBlockBack>,
+}
+
+impl IDAStepSolver for Solver {
+    const NAME: &'static str = "second_block";
+    type State = SliceCube;
+
+    #[inline]
+    fn get_all_moves(&self) -> &[SliceMove] {
+        &self.candidate_moves
+    }
+
+    fn assess_distance(&self, state: &SliceCube) -> usize {
+        // None of the moves can fix the first block.
+        if !is_first_block_solved(&state.cube) {
+            return usize::MAX;
+        }
+        self.front.distance(state).max(self.back.distance(state))
+    }
+
+    fn heuristics_memory_usage(&self) -> usize {
+        self.front.memory_usage() + self.back.memory_usage()
+    }
+
+    fn heuristics_size() -> Option<u64> {
+        Some(
+            PatternDatabase::<SecondBlockFront>::encoded_size::<Self::State>()
+                + PatternDatabase::<SecondBlockBack>::encoded_size::<Self::State>(),
+        )
+    }
+
+    fn populate_candidate_moves(&mut self) {
+        self.candidate_moves = vec![
+            SliceMove::Face(Move::R),
+            SliceMove::Face(Move::R2),
+            SliceMove::Face(Move::Rp),
+            SliceMove::Rw,
+            SliceMove::Rw2,
+            SliceMove::Rwp,
+            SliceMove::Face(Move::U),
+            SliceMove::Face(Move::U2),
+            SliceMove::Face(Move::Up),
+            SliceMove::M,
+            SliceMove::M2,
+            SliceMove::Mp,
+        ];
+    }
+
+    fn populate_heuristics(&mut self, progress: &dyn ProgressReporter) {
+        self.front = self.generate_heuristic("SecondBlock/Front", progress);
+        self.back = self.generate_heuristic("SecondBlock/Back", progress);
+    }
+}
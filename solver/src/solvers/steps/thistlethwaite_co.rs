@@ -0,0 +1,76 @@
+//! # Second phase of Thistlethwaite's algorithm: orient the corners and
+//! bring the E-slice edges into the E slice.
+//!
+//! Brings the cube from G1 into G2 = `<U, D, L2, R2, F2, B2>`.
+
+use cube::{algorithms::Move, Cube};
+use serde::{Deserialize, Serialize};
+
+use crate::solvers::{
+    cube_subsets::{is_in_group, CornerOrientation, EdgeInSlice},
+    ida_solver::IDAStepSolver,
+    pattern_database::PatternDatabase,
+    progress::ProgressReporter,
+};
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Solver {
+    candidate_moves: Vec<Move>,
+    corner_orientation: PatternDatabase<CornerOrientation>,
+    edge_in_slice: PatternDatabase<EdgeInSlice>,
+}
+
+impl IDAStepSolver for Solver {
+    const NAME: &'static str = "thistlethwaite_co";
+    type State = Cube;
+
+    #[inline]
+    fn get_all_moves(&self) -> &[Move] {
+        &self.candidate_moves
+    }
+
+    fn assess_distance(&self, cube: &Cube) -> usize {
+        // None of the moves of the phase can bring the cube into G1.
+        if !is_in_group(cube, 1) {
+            return usize::MAX;
+        }
+        self.corner_orientation
+            .distance(cube)
+            .max(self.edge_in_slice.distance(cube))
+    }
+
+    fn heuristics_memory_usage(&self) -> usize {
+        self.corner_orientation.memory_usage() + self.edge_in_slice.memory_usage()
+    }
+
+    fn heuristics_size() -> Option<u64> {
+        Some(
+            PatternDatabase::<CornerOrientation>::encoded_size::<Self::State>()
+                + PatternDatabase::<EdgeInSlice>::encoded_size::<Self::State>(),
+        )
+    }
+
+    fn populate_candidate_moves(&mut self) {
+        self.candidate_moves = vec![
+            Move::U,
+            Move::U2,
+            Move::Up,
+            Move::D,
+            Move::D2,
+            Move::Dp,
+            Move::F2,
+            Move::B2,
+            Move::R,
+            Move::R2,
+            Move::Rp,
+            Move::L,
+            Move::L2,
+            Move::Lp,
+        ];
+    }
+
+    fn populate_heuristics(&mut self, progress: &dyn ProgressReporter) {
+        self.corner_orientation = self.generate_heuristic("Thistlethwaite/CO", progress);
+        self.edge_in_slice = self.generate_heuristic("Thistlethwaite/EIS", progress);
+    }
+}
@@ -0,0 +1,49 @@
+//! # First phase of Thistlethwaite's algorithm: orient the edges.
+//!
+//! Brings the cube into G1 = `<U, D, L, R, F2, B2>`.
+
+use cube::{algorithms::Move, Cube};
+use serde::{Deserialize, Serialize};
+
+use crate::solvers::{
+    cube_subsets::EdgeOrientation, ida_solver::IDAStepSolver, pattern_database::PatternDatabase,
+    progress::ProgressReporter,
+};
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Solver {
+    candidate_moves: Vec<Move>,
+    edge_orientation: PatternDatabase<EdgeOrientation>,
+}
+
+impl IDAStepSolver for Solver {
+    const NAME: &'static str = "thistlethwaite_eo";
+    type State = Cube;
+
+    #[inline]
+    fn get_all_moves(&self) -> &[Move] {
+        &self.candidate_moves
+    }
+
+    fn assess_distance(&self, cube: &Cube) -> usize {
+        self.edge_orientation.distance(cube)
+    }
+
+    fn heuristics_memory_usage(&self) -> usize {
+        self.edge_orientation.memory_usage()
+    }
+
+    fn heuristics_size() -> Option<u64> {
+        Some(PatternDatabase::<EdgeOrientation>::encoded_size::<
+            Self::State,
+        >())
+    }
+
+    fn populate_candidate_moves(&mut self) {
+        self.candidate_moves = cube::algorithms::ALL_MOVES.to_vec();
+    }
+
+    fn populate_heuristics(&mut self, progress: &dyn ProgressReporter) {
+        self.edge_orientation = self.generate_heuristic("Thistlethwaite/EO", progress);
+    }
+}
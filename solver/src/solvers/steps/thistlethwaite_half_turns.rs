@@ -0,0 +1,52 @@
+//! # Last phase of Thistlethwaite's algorithm: solve the cube with half
+//! turns only.
+
+use cube::{algorithms::Move, Cube};
+use serde::{Deserialize, Serialize};
+
+use crate::solvers::{
+    cube_subsets::{is_in_group, HalfTurns},
+    ida_solver::IDAStepSolver,
+    pattern_database::PatternDatabase,
+    progress::ProgressReporter,
+};
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Solver {
+    candidate_moves: Vec<Move>,
+    half_turns: PatternDatabase<HalfTurns>,
+}
+
+impl IDAStepSolver for Solver {
+    const NAME: &'static str = "thistlethwaite_half_turns";
+    type State = Cube;
+
+    #[inline]
+    fn get_all_moves(&self) -> &[Move] {
+        &self.candidate_moves
+    }
+
+    fn assess_distance(&self, cube: &Cube) -> usize {
+        // None of the moves of the phase can bring the cube into G3.
+        if !is_in_group(cube, 3) {
+            return usize::MAX;
+        }
+        self.half_turns.distance(cube)
+    }
+
+    fn heuristics_memory_usage(&self) -> usize {
+        self.half_turns.memory_usage()
+    }
+
+    fn heuristics_size() -> Option<u64> {
+        Some(PatternDatabase::<HalfTurns>::encoded_size::<Self::State>())
+    }
+
+    fn populate_candidate_moves(&mut self) {
+        self.candidate_moves = vec![Move::U2, Move::D2, Move::F2, Move::B2, Move::R2, Move::L2];
+    }
+
+    fn populate_heuristics(&mut self, progress: &dyn ProgressReporter) {
+        self.half_turns = self.generate_heuristic("Thistlethwaite/Half turns", progress);
+    }
+}
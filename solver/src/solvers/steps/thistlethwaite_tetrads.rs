@@ -0,0 +1,66 @@
+//! # Third phase of Thistlethwaite's algorithm: bring the corners into
+//! their tetrads with an even permutation, and the M-slice edges into the
+//! M slice.
+//!
+//! Brings the cube from G2 into G3 = `<U2, D2, L2, R2, F2, B2>`.
+
+use cube::{algorithms::Move, Cube};
+use serde::{Deserialize, Serialize};
+
+use crate::solvers::{
+    cube_subsets::{is_in_group, Tetrads},
+    ida_solver::IDAStepSolver,
+    pattern_database::PatternDatabase,
+    progress::ProgressReporter,
+};
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Solver {
+    candidate_moves: Vec<Move>,
+    tetrads: PatternDatabase<Tetrads>,
+}
+
+impl IDAStepSolver for Solver {
+    const NAME: &'static str = "thistlethwaite_tetrads";
+    type State = Cube;
+
+    #[inline]
+    fn get_all_moves(&self) -> &[Move] {
+        &self.candidate_moves
+    }
+
+    fn assess_distance(&self, cube: &Cube) -> usize {
+        // None of the moves of the phase can bring the cube into G2.
+        if !is_in_group(cube, 2) {
+            return usize::MAX;
+        }
+        self.tetrads.distance(cube)
+    }
+
+    fn heuristics_memory_usage(&self) -> usize {
+        self.tetrads.memory_usage()
+    }
+
+    fn heuristics_size() -> Option<u64> {
+        Some(PatternDatabase::<Tetrads>::encoded_size::<Self::State>())
+    }
+
+    fn populate_candidate_moves(&mut self) {
+        self.candidate_moves = vec![
+            Move::U,
+            Move::U2,
+            Move::Up,
+            Move::D,
+            Move::D2,
+            Move::Dp,
+            Move::F2,
+            Move::B2,
+            Move::R2,
+            Move::L2,
+        ];
+    }
+
+    fn populate_heuristics(&mut self, progress: &dyn ProgressReporter) {
+        self.tetrads = self.generate_heuristic("Thistlethwaite/Tetrads", progress);
+    }
+}
@@ -0,0 +1,65 @@
+//! # Solve the left block of the ZZ F2L using IDA* search.
+//!
+//! Once the edges are oriented, the F2L of the ZZ method is built with
+//! `<R, U, L>` only, starting with the 1x2x3 block on the L face.
+
+use cube::{algorithms::Move, Cube};
+use serde::{Deserialize, Serialize};
+
+use crate::solvers::{
+    cube_subsets::{FirstBlockBack, FirstBlockFront},
+    ida_solver::IDAStepSolver,
+    pattern_database::PatternDatabase,
+    progress::ProgressReporter,
+};
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Solver {
+    candidate_moves: Vec<Move>,
+    front: PatternDatabase<FirstBlockFront>,
+    back: PatternDatabase<FirstBlockBack>,
+}
+
+impl IDAStepSolver for Solver {
+    const NAME: &'static str = "zz_left_block";
+    type State = Cube;
+
+    #[inline]
+    fn get_all_moves(&self) -> &[Move] {
+        &self.candidate_moves
+    }
+
+    fn assess_distance(&self, cube: &Cube) -> usize {
+        self.front.distance(cube).max(self.back.distance(cube))
+    }
+
+    fn heuristics_memory_usage(&self) -> usize {
+        self.front.memory_usage() + self.back.memory_usage()
+    }
+
+    fn heuristics_size() -> Option<u64> {
+        Some(
+            PatternDatabase::<FirstBlockFront>::encoded_size::<Self::State>()
+                + PatternDatabase::<FirstBlockBack>::encoded_size::<Self::State>(),
+        )
+    }
+
+    fn populate_candidate_moves(&mut self) {
+        self.candidate_moves = vec![
+            Move::U,
+            Move::U2,
+            Move::Up,
+            Move::R,
+            Move::R2,
+            Move::Rp,
+            Move::L,
+            Move::L2,
+            Move::Lp,
+        ];
+    }
+
+    fn populate_heuristics(&mut self, progress: &dyn ProgressReporter) {
+        self.front = self.generate_heuristic("ZZ/LeftFront", progress);
+        self.back = self.generate_heuristic("ZZ/LeftBack", progress);
+    }
+}
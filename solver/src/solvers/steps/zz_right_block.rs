@@ -0,0 +1,60 @@
+//! # Solve the right block of the ZZ F2L using IDA* search.
+//!
+//! The 1x2x3 block on the R face is built with `<R, U>`, which keeps the left
+//! block and the edge orientation solved. Petrus ends its F2L the same way,
+//! once its 2x2x3 block and the edge orientation are solved.
+
+use cube::{algorithms::Move, Cube};
+use serde::{Deserialize, Serialize};
+
+use crate::solvers::{
+    cube_subsets::{is_first_block_solved, SecondBlockBack, SecondBlockFront},
+    ida_solver::IDAStepSolver,
+    pattern_database::PatternDatabase,
+    progress::ProgressReporter,
+};
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Solver {
+    candidate_moves: Vec<Move>,
+    front: PatternDatabase<SecondBlockFront>,
+    back: PatternDatabase<SecondBlockBack>,
+}
+
+impl IDAStepSolver for Solver {
+    const NAME: &'static str = "zz_right_block";
+    type State = Cube;
+
+    #[inline]
+    fn get_all_moves(&self) -> &[Move] {
+        &self.candidate_moves
+    }
+
+    fn assess_distance(&self, cube: &Cube) -> usize {
+        // None of the moves can fix the left block.
+        if !is_first_block_solved(cube) {
+            return usize::MAX;
+        }
+        self.front.distance(cube).max(self.back.distance(cube))
+    }
+
+    fn heuristics_memory_usage(&self) -> usize {
+        self.front.memory_usage() + self.back.memory_usage()
+    }
+
+    fn heuristics_size() -> Option<u64> {
+        Some(
+            PatternDatabase::<SecondBlockFront>::encoded_size::<Self::State>()
+                + PatternDatabase::<SecondBlockBack>::encoded_size::<Self::State>(),
+        )
+    }
+
+    fn populate_candidate_moves(&mut self) {
+        self.candidate_moves = vec![Move::U, Move::U2, Move::Up, Move::R, Move::R2, Move::Rp];
+    }
+
+    fn populate_heuristics(&mut self, progress: &dyn ProgressReporter) {
+        self.front = self.generate_heuristic("ZZ/RightFront", progress);
+        self.back = self.generate_heuristic("ZZ/RightBack", progress);
+    }
+}
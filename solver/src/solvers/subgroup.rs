@@ -0,0 +1,321 @@
+//! # Membership in the subgroup generated by a set of moves.
+//!
+//! Moves permute the 48 stickers of the pieces: each edge has 2 orientations
+//! in each of its 12 slots, and each corner 3 in each of its 8 slots. The
+//! group they generate is described by a stabilizer chain, built with the
+//! Schreier-Sims algorithm. The chain tells whether some element of the
+//! group sends the first points of its base to given points, which is
+//! whether a sequence of the moves brings the pieces of these points there,
+//! without enumerating the group.
+
+use std::collections::HashSet;
+
+use cube::Cube;
+use serde::{Deserialize, Serialize};
+
+/// Points moved by the permutations: the edge stickers, then the corner
+/// stickers.
+const POINTS: usize = 48;
+
+/// Image of each point.
+type Permutation = Vec<u8>;
+
+/// Point of an edge in the given slot and orientation.
+pub(crate) fn edge_point(slot: usize, orientation: u8) -> usize {
+    slot * 2 + usize::from(orientation)
+}
+
+/// Point of a corner in the given slot and orientation.
+pub(crate) fn corner_point(slot: usize, orientation: u8) -> usize {
+    24 + slot * 3 + usize::from(orientation)
+}
+
+/// Permutation of the points done by the moves that lead to the cube.
+pub(crate) fn permutation(cube: &Cube) -> Vec<u8> {
+    let mut permutation = vec![0; POINTS];
+    for (slot, edge) in cube.edges.iter().enumerate() {
+        for orientation in 0..2 {
+            permutation[edge_point(edge.piece.clone() as usize, orientation)] =
+                edge_point(slot, (orientation + edge.orientation) % 2) as u8;
+        }
+    }
+    for (slot, corner) in cube.corners.iter().enumerate() {
+        for orientation in 0..3 {
+            permutation[corner_point(corner.piece.clone() as usize, orientation)] =
+                corner_point(slot, (orientation + corner.orientation) % 3) as u8;
+        }
+    }
+    permutation
+}
+
+/// `first ∘ second`
+fn compose(first: &[u8], second: &[u8]) -> Permutation {
+    second
+        .iter()
+        .map(|point| first[usize::from(*point)])
+        .collect()
+}
+
+fn inverse(permutation: &[u8]) -> Permutation {
+    let mut inverse = vec![0; permutation.len()];
+    for (point, image) in permutation.iter().enumerate() {
+        inverse[usize::from(*image)] = point as u8;
+    }
+    inverse
+}
+
+fn is_identity(permutation: &[u8]) -> bool {
+    permutation
+        .iter()
+        .enumerate()
+        .all(|(point, image)| usize::from(*image) == point)
+}
+
+/// Subgroup fixing the base points of the previous levels.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Level {
+    base: usize,
+    /// For each point of the orbit of the base, an element sending the base
+    /// to it
+    transversal: Vec<Option<Permutation>>,
+}
+
+impl Level {
+    fn new(base: usize) -> Self {
+        let mut transversal = vec![None; POINTS];
+        transversal[base] = Some((0..POINTS as u8).collect());
+        Self { base, transversal }
+    }
+
+    /// Complete the orbit of the base with the generators, keeping the
+    /// elements already in the transversal.
+    fn extend_orbit<'a>(&mut self, generators: impl Iterator<Item = &'a Permutation> + Clone) {
+        let mut stack = (0..POINTS)
+            .filter(|point| self.transversal[*point].is_some())
+            .collect::<Vec<_>>();
+        while let Some(point) = stack.pop() {
+            for generator in generators.clone() {
+                let image = usize::from(generator[point]);
+                if self.transversal[image].is_none() {
+                    let element = self.transversal[point].as_ref().unwrap();
+                    self.transversal[image] = Some(compose(generator, element));
+                    stack.push(image);
+                }
+            }
+        }
+    }
+}
+
+/// Stabilizer chain of the group generated by a set of permutations.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct Subgroup {
+    levels: Vec<Level>,
+}
+
+impl Subgroup {
+    /// Chain of the group generated by the permutations, whose base starts
+    /// with the given points.
+    ///
+    /// Each strong generator fixes the base points of the levels before its
+    /// own, and generates the orbits of all the levels up to its own. Going
+    /// from the deepest level up, the Schreier generators of each level must
+    /// be generated by the deeper levels, otherwise what is left of one of
+    /// them is a new strong generator, and its level is checked again.
+    pub(crate) fn new(generators: &[Permutation], base: &[usize]) -> Self {
+        let mut group = Self {
+            levels: base.iter().map(|point| Level::new(*point)).collect(),
+        };
+        let mut strong = vec![];
+        for generator in generators {
+            if !is_identity(generator) {
+                let level = group.level_of(generator);
+                strong.push((level, generator.clone()));
+            }
+        }
+        // Schreier generators already generated by the deeper levels, by
+        // level, as their point and strong generator
+        let mut checked = vec![HashSet::new(); group.levels.len()];
+        let mut level = group.levels.len();
+        while level > 0 {
+            let index = level - 1;
+            checked.resize_with(group.levels.len(), HashSet::new);
+            let generators = strong
+                .iter()
+                .filter(|(generator_level, _)| *generator_level >= index)
+                .map(|(_, generator)| generator);
+            group.levels[index].extend_orbit(generators);
+            match group.unchecked_residue(index, &strong, &mut checked[index]) {
+                Some(residue) => {
+                    let residue_level = group.level_of(&residue);
+                    strong.push((residue_level, residue));
+                    level = residue_level + 1;
+                }
+                None => level -= 1,
+            }
+        }
+        group
+    }
+
+    /// Whether an element of the group sends the first points of the base
+    /// to the given points, in order.
+    pub(crate) fn sends_base_to(&self, images: &[usize]) -> bool {
+        let mut images = images.to_vec();
+        for (index, level) in self.levels.iter().enumerate().take(images.len()) {
+            let Some(element) = &level.transversal[images[index]] else {
+                return false;
+            };
+            let element = inverse(element);
+            for image in &mut images[index + 1..] {
+                *image = usize::from(element[*image]);
+            }
+        }
+        true
+    }
+
+    /// First level whose base the permutation moves, adding a level when it
+    /// fixes all of them.
+    fn level_of(&mut self, permutation: &[u8]) -> usize {
+        if let Some(level) = self
+            .levels
+            .iter()
+            .position(|level| usize::from(permutation[level.base]) != level.base)
+        {
+            return level;
+        }
+        let base = (0..POINTS)
+            .find(|point| usize::from(permutation[*point]) != *point)
+            .expect("Strong generators are not the identity");
+        self.levels.push(Level::new(base));
+        self.levels.len() - 1
+    }
+
+    /// What is left of the first Schreier generator of the level that the
+    /// deeper levels do not generate, if any.
+    fn unchecked_residue(
+        &self,
+        level: usize,
+        strong: &[(usize, Permutation)],
+        checked: &mut HashSet<(usize, usize)>,
+    ) -> Option<Permutation> {
+        let transversal = &self.levels[level].transversal;
+        for (point, element) in transversal.iter().enumerate() {
+            let Some(element) = element else {
+                continue;
+            };
+            for (index, (generator_level, generator)) in strong.iter().enumerate() {
+                if *generator_level < level || !checked.insert((point, index)) {
+                    continue;
+                }
+                let moved = compose(generator, element);
+                let image = usize::from(moved[self.levels[level].base]);
+                let representative = transversal[image].as_ref().unwrap();
+                let schreier = compose(&inverse(representative), &moved);
+                let residue = self.sift(&schreier, level + 1);
+                if !is_identity(&residue) {
+                    return Some(residue);
+                }
+            }
+        }
+        None
+    }
+
+    /// What is left of the permutation once divided by the transversals of
+    /// the levels from `from` on, stopping at the first level whose orbit
+    /// misses the image of its base.
+    fn sift(&self, permutation: &[u8], from: usize) -> Permutation {
+        let mut residue = permutation.to_vec();
+        for level in &self.levels[from..] {
+            let Some(element) = &level.transversal[usize::from(residue[level.base])] else {
+                break;
+            };
+            residue = compose(&inverse(element), &residue);
+        }
+        residue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cube::algorithms::{parse_algorithm, Move};
+
+    use super::*;
+
+    fn permutations(moves: &[Move]) -> Vec<Permutation> {
+        moves
+            .iter()
+            .map(|move_| {
+                let mut cube = Cube::default();
+                cube.execute_move(move_);
+                permutation(&cube)
+            })
+            .collect()
+    }
+
+    /// Order of the group, as the product of the sizes of the orbits.
+    fn order(group: &Subgroup) -> u128 {
+        group
+            .levels
+            .iter()
+            .map(|level| level.transversal.iter().flatten().count() as u128)
+            .product()
+    }
+
+    #[test]
+    fn count_elements() {
+        let half_turns = Subgroup::new(&permutations(&[Move::U2, Move::R2]), &[]);
+        assert_eq!(order(&half_turns), 12);
+        let two_gen = Subgroup::new(&permutations(&[Move::U, Move::R]), &[]);
+        assert_eq!(order(&two_gen), 73_483_200);
+        let all = Subgroup::new(&permutations(&cube::algorithms::ALL_MOVES), &[]);
+        assert_eq!(order(&all), 43_252_003_274_489_856_000);
+    }
+
+    /// The home of every piece, which the elements of the group tell apart.
+    fn pieces() -> Vec<usize> {
+        (0..12)
+            .map(|edge| edge_point(edge, 0))
+            .chain((0..8).map(|corner| corner_point(corner, 0)))
+            .collect()
+    }
+
+    fn images(cube: &Cube) -> Vec<usize> {
+        let permutation = permutation(cube);
+        pieces()
+            .into_iter()
+            .map(|point| usize::from(permutation[point]))
+            .collect()
+    }
+
+    #[test]
+    fn recognise_members() {
+        let two_gen = Subgroup::new(&permutations(&[Move::U, Move::R]), &pieces());
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&parse_algorithm("R U R' U R U2 R'"));
+        assert!(two_gen.sends_base_to(&images(&cube)));
+        cube.execute_move(&Move::F);
+        assert!(!two_gen.sends_base_to(&images(&cube)));
+
+        // Both swaps are even, but the half turns only swap them along with
+        // other pieces
+        let half_turns = Subgroup::new(&permutations(&[Move::U2, Move::R2]), &pieces());
+        let mut cube = Cube::default();
+        cube.execute_algorithm(&parse_algorithm("U2 R2 U2 R2 U2 R2"));
+        assert!(half_turns.sends_base_to(&images(&cube)));
+        let mut cube = Cube::default();
+        cube.edges.swap(cube::UF, cube::UB);
+        cube.corners.swap(cube::URF, cube::ULB);
+        assert!(!half_turns.sends_base_to(&images(&cube)));
+    }
+
+    #[test]
+    fn start_with_given_base() {
+        let base = [edge_point(cube::DF, 0), corner_point(cube::DFR, 0)];
+        let two_gen = Subgroup::new(&permutations(&[Move::U, Move::R]), &base);
+        assert_eq!(order(&two_gen), 73_483_200);
+        // DF never moves, DFR reaches the 6 corners of U and R in 3
+        // orientations
+        assert!(two_gen.sends_base_to(&[edge_point(cube::DF, 0), corner_point(cube::UFL, 2)]));
+        assert!(!two_gen.sends_base_to(&[edge_point(cube::UF, 0)]));
+        assert!(!two_gen.sends_base_to(&[edge_point(cube::DF, 0), corner_point(cube::DLF, 0)]));
+    }
+}
@@ -0,0 +1,427 @@
+//! # On-disk cache for the lookup tables of the steps.
+//!
+//! Tables are written in a small binary format: a header holding the format
+//! version, the kind of table, the revision of the code that generated it and
+//! a checksum of the payload, followed by the bincode-encoded table. When the
+//! header does not match what the running code expects, the table is
+//! regenerated instead of silently reusing a stale file.
+
+use std::{
+    error::Error,
+    fmt::Display,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use super::{
+    progress::{LogProgress, ProgressReporter},
+    solver::Step,
+};
+
+/// Environment variable overriding the directory where tables are cached.
+pub const TABLE_DIR_ENV: &str = "KUBIN_TABLE_DIR";
+
+const MAGIC: &[u8; 4] = b"KUBN";
+/// Bumped whenever the header or the encoding of the tables changes.
+const FORMAT_VERSION: u32 = 1;
+const EXTENSION: &str = "table";
+
+#[derive(Debug)]
+pub enum TableError {
+    Io(io::Error),
+    Encoding(bincode::Error),
+    /// The table is not cached and the store does not generate missing tables
+    Missing(PathBuf),
+    /// The file is not a table written by this crate
+    InvalidHeader,
+    FormatVersion {
+        found: u32,
+    },
+    Kind {
+        expected: String,
+        found: String,
+    },
+    /// The table was generated from different hard-coded data
+    Revision {
+        expected: u64,
+        found: u64,
+    },
+    Checksum,
+}
+
+impl Display for TableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableError::Io(error) => write!(f, "I/O error: {error}"),
+            TableError::Encoding(error) => write!(f, "encoding error: {error}"),
+            TableError::Missing(path) => write!(f, "table {} is missing", path.display()),
+            TableError::InvalidHeader => f.write_str("invalid table header"),
+            TableError::FormatVersion { found } => write!(
+                f,
+                "table format version {found} does not match version {FORMAT_VERSION}"
+            ),
+            TableError::Kind { expected, found } => {
+                write!(f, "expected a {expected} table, found a {found} table")
+            }
+            TableError::Revision { expected, found } => {
+                write!(
+                    f,
+                    "table revision {found:x} is stale, expected {expected:x}"
+                )
+            }
+            TableError::Checksum => f.write_str("table checksum mismatch"),
+        }
+    }
+}
+
+impl Error for TableError {}
+
+impl From<io::Error> for TableError {
+    fn from(error: io::Error) -> Self {
+        TableError::Io(error)
+    }
+}
+
+impl From<bincode::Error> for TableError {
+    fn from(error: bincode::Error) -> Self {
+        TableError::Encoding(error)
+    }
+}
+
+/// Location and policy used to load the tables of the steps.
+#[derive(Clone)]
+pub struct TableStore {
+    directory: PathBuf,
+    generate_missing: bool,
+    progress: Arc<dyn ProgressReporter>,
+}
+
+impl std::fmt::Debug for TableStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TableStore")
+            .field("directory", &self.directory)
+            .field("generate_missing", &self.generate_missing)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for TableStore {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+impl TableStore {
+    #[must_use]
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            generate_missing: true,
+            progress: Arc::new(LogProgress),
+        }
+    }
+
+    /// Store in the directory given by `KUBIN_TABLE_DIR`, or in a `kubin`
+    /// directory under the system temporary directory.
+    #[must_use]
+    pub fn from_env() -> Self {
+        match std::env::var_os(TABLE_DIR_ENV) {
+            Some(directory) => Self::new(directory),
+            None => Self::new(std::env::temp_dir().join("kubin")),
+        }
+    }
+
+    /// Whether tables that are missing or stale are generated, or reported
+    /// as errors.
+    #[must_use]
+    pub fn generate_missing(mut self, generate_missing: bool) -> Self {
+        self.generate_missing = generate_missing;
+        self
+    }
+
+    /// Where the progress of table generation goes, the `log` facade by
+    /// default.
+    #[must_use]
+    pub fn progress(mut self, progress: impl ProgressReporter + 'static) -> Self {
+        self.progress = Arc::new(progress);
+        self
+    }
+
+    #[must_use]
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    #[must_use]
+    pub fn path(&self, kind: &str) -> PathBuf {
+        self.directory.join(kind).with_extension(EXTENSION)
+    }
+
+    /// Read the table of the given kind, falling back to generating it (and
+    /// trying to cache it) when the cached file is missing or invalid.
+    pub(super) fn load<S: Step>(&self, kind: &str) -> Result<S, TableError> {
+        self.load_with(kind, S::revision(), S::generate_with_progress)
+    }
+
+    /// Same as [`TableStore::load`], for tables whose revision and
+    /// generation depend on more than their type, such as the steps of a
+    /// configured method.
+    pub(super) fn load_with<S: Step>(
+        &self,
+        kind: &str,
+        revision: u64,
+        generate: impl FnOnce(&dyn ProgressReporter) -> S,
+    ) -> Result<S, TableError> {
+        match self.read_revision(kind, revision) {
+            Ok(table) => Ok(table),
+            Err(error) if !self.generate_missing => Err(error),
+            Err(_) => {
+                let table = generate(self.progress.as_ref());
+                if let Err(error) = self.write_revision(kind, revision, &table) {
+                    log::warn!(
+                        "Could not cache table {}: {error}",
+                        self.path(kind).display()
+                    );
+                }
+                Ok(table)
+            }
+        }
+    }
+
+    #[cfg(test)]
+    fn read<S: Step>(&self, kind: &str) -> Result<S, TableError> {
+        self.read_revision(kind, S::revision())
+    }
+
+    /// Whether the table of the given kind is cached with the given
+    /// revision, checking its header only.
+    #[must_use]
+    pub fn is_cached(&self, kind: &str, revision: u64) -> bool {
+        self.read_header(kind, revision).is_ok()
+    }
+
+    fn read_revision<S: Step>(&self, kind: &str, expected: u64) -> Result<S, TableError> {
+        let (reader, length, checksum) = self.read_header(kind, expected)?;
+
+        // Decode the payload while it is streamed from the file, hashing it on the way.
+        let mut payload = ChecksumReader::new(reader.take(length));
+        let table = bincode::deserialize_from(&mut payload)?;
+        io::copy(&mut payload, &mut io::sink())?;
+        if payload.read != length || payload.checksum != checksum {
+            return Err(TableError::Checksum);
+        }
+        Ok(table)
+    }
+
+    /// Open the table and check its header, returning the reader positioned
+    /// at the payload along with the length and checksum of the payload.
+    fn read_header(
+        &self,
+        kind: &str,
+        expected: u64,
+    ) -> Result<(BufReader<File>, u64, u64), TableError> {
+        let path = self.path(kind);
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                return Err(TableError::Missing(path))
+            }
+            Err(error) => return Err(error.into()),
+        };
+        let mut reader = BufReader::new(file);
+
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(TableError::InvalidHeader);
+        }
+        let format_version = u32::from_le_bytes(read_array(&mut reader)?);
+        if format_version != FORMAT_VERSION {
+            return Err(TableError::FormatVersion {
+                found: format_version,
+            });
+        }
+        let kind_length = u16::from_le_bytes(read_array(&mut reader)?);
+        let mut found_kind = vec![0; usize::from(kind_length)];
+        reader.read_exact(&mut found_kind)?;
+        let found_kind = String::from_utf8_lossy(&found_kind);
+        if found_kind != kind {
+            return Err(TableError::Kind {
+                expected: kind.to_string(),
+                found: found_kind.to_string(),
+            });
+        }
+        let revision = u64::from_le_bytes(read_array(&mut reader)?);
+        if revision != expected {
+            return Err(TableError::Revision {
+                expected,
+                found: revision,
+            });
+        }
+        let length = u64::from_le_bytes(read_array(&mut reader)?);
+        let checksum = u64::from_le_bytes(read_array(&mut reader)?);
+        Ok((reader, length, checksum))
+    }
+
+    #[cfg(test)]
+    fn write<S: Step>(&self, kind: &str, table: &S) -> Result<(), TableError> {
+        self.write_revision(kind, S::revision(), table)
+    }
+
+    fn write_revision<S: Step>(
+        &self,
+        kind: &str,
+        revision: u64,
+        table: &S,
+    ) -> Result<(), TableError> {
+        fs::create_dir_all(&self.directory)?;
+        let payload = bincode::serialize(table)?;
+        let kind_length = u16::try_from(kind.len()).map_err(|_| TableError::InvalidHeader)?;
+
+        // Write to a temporary file first so that readers never see a partial table.
+        let path = self.path(kind);
+        let temporary_path = path.with_extension(format!("{EXTENSION}.{}", std::process::id()));
+        let written = write_file(&temporary_path, kind_length, kind, revision, &payload)
+            .and_then(|()| fs::rename(&temporary_path, &path));
+        if written.is_err() {
+            let _ = fs::remove_file(&temporary_path);
+        }
+        Ok(written?)
+    }
+}
+
+/// Write the header and the payload of a table to the file.
+fn write_file(
+    path: &Path,
+    kind_length: u16,
+    kind: &str,
+    revision: u64,
+    payload: &[u8],
+) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(MAGIC)?;
+    writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+    writer.write_all(&kind_length.to_le_bytes())?;
+    writer.write_all(kind.as_bytes())?;
+    writer.write_all(&revision.to_le_bytes())?;
+    writer.write_all(&(payload.len() as u64).to_le_bytes())?;
+    writer.write_all(&fnv1a(FNV_OFFSET, payload).to_le_bytes())?;
+    writer.write_all(payload)?;
+    writer.flush()
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Hash of the given strings, used as revision of the hard-coded data a
+/// table is generated from.
+pub(crate) fn fingerprint(parts: &[&str]) -> u64 {
+    parts.iter().fold(FNV_OFFSET, |hash, part| {
+        fnv1a(fnv1a(hash, part.as_bytes()), &[0])
+    })
+}
+
+/// Reader computing the FNV-1a hash of everything read through it.
+struct ChecksumReader<R> {
+    inner: R,
+    checksum: u64,
+    read: u64,
+}
+
+impl<R> ChecksumReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            checksum: FNV_OFFSET,
+            read: 0,
+        }
+    }
+}
+
+impl<R: Read> Read for ChecksumReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let count = self.inner.read(buf)?;
+        self.checksum = fnv1a(self.checksum, &buf[..count]);
+        self.read += count as u64;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solvers::steps::pll;
+
+    fn temporary_store(name: &str) -> TableStore {
+        let directory =
+            std::env::temp_dir().join(format!("kubin-test-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        TableStore::new(directory)
+    }
+
+    #[test]
+    fn write_then_read() {
+        let store = temporary_store("write-then-read");
+        let table = pll::Solver::generate();
+        assert!(!store.is_cached("pll", pll::Solver::revision()));
+        store.write("pll", &table).unwrap();
+        assert!(store.is_cached("pll", pll::Solver::revision()));
+        assert!(!store.is_cached("pll", pll::Solver::revision() + 1));
+        let read: pll::Solver = store.read("pll").unwrap();
+        assert_eq!(read.memory_usage(), table.memory_usage());
+        fs::remove_dir_all(store.directory()).unwrap();
+    }
+
+    #[test]
+    fn reject_invalid_tables() {
+        let store = temporary_store("reject");
+        store.write("pll", &pll::Solver::generate()).unwrap();
+        assert!(matches!(
+            store.read::<pll::Solver>("oll"),
+            Err(TableError::Missing(_))
+        ));
+
+        fs::copy(store.path("pll"), store.path("oll")).unwrap();
+        assert!(matches!(
+            store.read::<pll::Solver>("oll"),
+            Err(TableError::Kind { .. })
+        ));
+
+        let mut bytes = fs::read(store.path("pll")).unwrap();
+        *bytes.last_mut().unwrap() ^= 1;
+        fs::write(store.path("pll"), bytes).unwrap();
+        assert!(matches!(
+            store.read::<pll::Solver>("pll"),
+            Err(TableError::Checksum | TableError::Encoding(_))
+        ));
+
+        let store = store.generate_missing(false);
+        assert!(store.load::<pll::Solver>("pll").is_err());
+        fs::remove_dir_all(store.directory()).unwrap();
+    }
+
+    #[test]
+    fn remove_temporary_file_on_failure() {
+        let store = temporary_store("failure");
+        // A directory in the way of the table makes the rename fail
+        fs::create_dir_all(store.path("pll").join("in-the-way")).unwrap();
+        assert!(store.write("pll", &pll::Solver::generate()).is_err());
+        let entries = fs::read_dir(store.directory()).unwrap().count();
+        assert_eq!(entries, 1);
+        fs::remove_dir_all(store.directory()).unwrap();
+    }
+}
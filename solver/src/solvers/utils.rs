@@ -1,26 +1,62 @@
-// Macro to print progress of BFS.
-macro_rules! print_progress {
-    ($title:expr, $name:expr, $current:ident, $total:ident) => {
-        if $current % 1000 == 0 {
-            let icon = match ($current / 1000) % 4 {
-                0 => "◜",
-                1 => "◝",
-                2 => "◞",
-                3 => "◟",
-                _ => unreachable!(),
-            };
-            print!("{} {} {}: {} / {}\r", $title, $name, icon, $current, $total);
-            std::io::stdout().flush().unwrap();
-        }
-    };
+use std::{collections::HashMap, time::Duration};
+
+use cube::algorithms::{Move, ALL_MOVES};
+
+/// Approximate memory used by a table of algorithms, in bytes.
+pub(crate) fn algorithm_table_memory_usage<K>(cases: &HashMap<K, Vec<Move>>) -> usize {
+    cases.capacity() * std::mem::size_of::<(K, Vec<Move>)>()
+        + cases
+            .values()
+            .map(|alg| alg.capacity() * std::mem::size_of::<Move>())
+            .sum::<usize>()
+}
+
+/// Approximate size of an encoded table of algorithms, in bytes, from its
+/// number of cases and the total length of their algorithms.
+pub(crate) fn algorithm_table_size<K>(cases: usize, moves: usize) -> u64 {
+    // The number of cases, then each key followed by the length of its
+    // algorithm and its moves, each move encoded as a `u32`
+    let entry = std::mem::size_of::<K>() + std::mem::size_of::<u64>();
+    (std::mem::size_of::<u64>() + cases * entry + moves * std::mem::size_of::<u32>()) as u64
+}
+
+/// Time to generate a byte of the breadth-first search tables, on a single
+/// core. Measured on the pattern databases of the built-in steps, whose rates
+/// range from 30 ns to 2 µs a byte.
+pub(crate) const SEARCH_TABLE_NANOS_PER_BYTE: u64 = 1_300;
+
+/// Time to generate a byte of the tables of hardcoded algorithms, on a single
+/// core.
+pub(crate) const ALGORITHM_TABLE_NANOS_PER_BYTE: u64 = 20;
+
+/// Approximate time to generate a table of the given size, at the given rate.
+pub(crate) fn generation_time(size: u64, nanos_per_byte: u64) -> Duration {
+    Duration::from_nanos(size * nanos_per_byte)
 }
 
-pub(crate) use print_progress;
+/// Append the moves, merging the turns of the same face at the join.
+/// `Move::None` is skipped.
+pub(crate) fn append_cancelling(algorithm: &mut Vec<Move>, moves: &[Move]) {
+    for move_ in moves {
+        match algorithm.last() {
+            _ if *move_ == Move::None => {}
+            Some(last) if layer(last) == layer(move_) => {
+                let turns = (quarter_turns(last) + quarter_turns(move_)) % 4;
+                let layer = layer(last);
+                algorithm.pop();
+                if turns != 0 {
+                    algorithm.push(ALL_MOVES[layer * 3 + turns - 1].clone());
+                }
+            }
+            _ => algorithm.push(move_.clone()),
+        }
+    }
+}
 
-macro_rules! print_terminated {
-    ($title:expr, $name:expr, $current:expr, $total:ident) => {
-        println!("{} {} ✅: {} / {}", $title, $name, $current, $total);
-    };
+pub(crate) fn layer(move_: &Move) -> usize {
+    move_.clone() as usize / 3
 }
 
-pub(crate) use print_terminated;
+fn quarter_turns(move_: &Move) -> usize {
+    move_.clone() as usize % 3 + 1
+}